@@ -0,0 +1,56 @@
+use ms_manager_lib::api_error::{ApiError, ApiResult};
+use ms_manager_lib::services::{bridge_ctl, distribution, version_audit};
+
+use crate::AuditArgs;
+use crate::context::Context;
+
+pub async fn run(ctx: &Context, args: AuditArgs) -> ApiResult<()> {
+    let state = &ctx.state;
+    let layout = state.layout_get();
+    let tag = match args.tag {
+        Some(tag) => tag,
+        None => state
+            .install_state_get()
+            .map(|installed| installed.tag)
+            .ok_or_else(|| ApiError::new("install_missing", "no release is installed"))?,
+    };
+
+    let report = if args.repair {
+        let _install = state.install_lock().await?;
+        let source = state.distribution_source_get();
+        let keyring = distribution::load_keyring(&state.http(), &layout, &source).await;
+        bridge_ctl::shutdown_enabled(&state.bridge_instances_get()).await;
+        version_audit::repair_installed_version(&layout, &keyring, &tag).await?
+    } else {
+        tokio::task::spawn_blocking(move || version_audit::audit_installed_version(&layout, &tag))
+            .await
+            .map_err(|e| {
+                ApiError::new("internal_error", format!("version audit task failed: {e}"))
+            })??
+    };
+
+    ctx.print(&report, |r| {
+        for path in &r.missing {
+            println!("missing  {path}");
+        }
+        for path in &r.modified {
+            println!("modified {path}");
+        }
+        for path in &r.extra {
+            println!("extra    {path}");
+        }
+        let verdict = if r.ok { "ok" } else { "FAILED" };
+        println!("{} {verdict} ({} files checked)", r.tag, r.files_checked);
+    });
+
+    if !report.ok {
+        return Err(ApiError::new(
+            "version_audit_failed",
+            format!(
+                "installed version {} does not match its inventory",
+                report.tag
+            ),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use serde::Serialize;
+
+use ms_manager_core::BridgeInstanceBinding;
+use ms_manager_lib::api_error::{ApiError, ApiResult};
+use ms_manager_lib::models::{FlashEvent, FlashMessageLevel};
+use ms_manager_lib::services::flash;
+
+use crate::FlashArgs;
+use crate::context::Context;
+
+#[derive(Debug, Serialize)]
+struct FlashReport {
+    instance_id: String,
+    firmware: String,
+    device: String,
+}
+
+/// Flash through the payload loader, pausing the instance's bridge around it like the app does.
+pub async fn run(ctx: &Context, args: FlashArgs) -> ApiResult<()> {
+    let state = &ctx.state;
+    let layout = state.layout_get();
+    let installed = state.install_state_get();
+    let binding = select_binding(
+        state.bridge_instances_get().instances,
+        args.instance.as_deref(),
+    )?;
+
+    let plan = flash::plan_flash(
+        &layout,
+        installed.as_ref(),
+        &binding,
+        args.firmware.map(|path| (path, None)),
+        args.device,
+    )
+    .await?;
+    // Loader progress goes to stderr so stdout only carries the result.
+    let last = flash::run_flash(&plan, installed.as_ref(), &binding, &report_event).await?;
+    state.controller_last_flashed_set(&binding.instance_id, last)?;
+
+    let report = FlashReport {
+        instance_id: binding.instance_id,
+        firmware: plan.firmware.display().to_string(),
+        device: plan.device_target_id,
+    };
+    ctx.print(&report, |r| {
+        println!("flashed {} to {}", r.firmware, r.device);
+    });
+    Ok(())
+}
+
+fn report_event(event: FlashEvent) {
+    match event {
+        FlashEvent::Message {
+            level: FlashMessageLevel::Warn,
+            message,
+        } => eprintln!("warning: {message}"),
+        FlashEvent::Message { message, .. } => eprintln!("{message}"),
+        FlashEvent::Output { line } => eprintln!("{line}"),
+        FlashEvent::Begin { .. } | FlashEvent::Done { .. } => {}
+    }
+}
+
+/// The requested instance, or the only configured one.
+fn select_binding(
+    instances: Vec<BridgeInstanceBinding>,
+    instance_id: Option<&str>,
+) -> ApiResult<BridgeInstanceBinding> {
+    match instance_id {
+        Some(id) => instances
+            .into_iter()
+            .find(|binding| binding.instance_id == id)
+            .ok_or_else(|| {
+                ApiError::new(
+                    "bridge_instance_not_found",
+                    format!("unknown instance_id: {id}"),
+                )
+            }),
+        None if instances.len() == 1 => Ok(instances.into_iter().next().unwrap()),
+        None => Err(ApiError::new(
+            "bridge_instance_required",
+            format!(
+                "{} bridge instances configured; pass --instance",
+                instances.len()
+            ),
+        )),
+    }
+}
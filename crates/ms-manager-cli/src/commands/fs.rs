@@ -0,0 +1,88 @@
+use serde::Serialize;
+
+use ms_manager_lib::api_error::{ApiError, ApiResult};
+use ms_manager_lib::services::controller_fs::{
+    self, ControllerFsClient, ControllerFsError, FsFileType,
+};
+
+use crate::context::Context;
+use crate::{FsArgs, FsCommand};
+
+#[derive(Debug, Serialize)]
+struct TransferReport {
+    remote_path: String,
+    local_path: String,
+    bytes: usize,
+}
+
+pub async fn run(ctx: &Context, args: FsArgs) -> ApiResult<()> {
+    let control_port = controller_fs::resolve_control_port(
+        &ctx.state.bridge_instances_get(),
+        args.instance.as_deref(),
+        args.port,
+    )
+    .map_err(fs_error)?;
+    let mut client = controller_fs::client_for_port(control_port).map_err(fs_error)?;
+    let result = run_with_client(ctx, &mut client, args.command).await;
+    client.close().await;
+    result
+}
+
+async fn run_with_client(
+    ctx: &Context,
+    client: &mut ControllerFsClient,
+    command: FsCommand,
+) -> ApiResult<()> {
+    match command {
+        FsCommand::Ls { path } => {
+            let entries = client.list(&path).await.map_err(fs_error)?;
+            ctx.print(&entries, |entries| {
+                for entry in entries {
+                    let suffix = if entry.file_type == FsFileType::Directory {
+                        "/"
+                    } else {
+                        ""
+                    };
+                    println!("{:>10}  {}{suffix}", entry.size_bytes, entry.name);
+                }
+            });
+        }
+        FsCommand::Pull { remote, local } => {
+            let bytes = client
+                .pull_file_to_path_with_progress(&remote, &local, progress)
+                .await
+                .map_err(fs_error)?;
+            print_transfer(ctx, remote, local.display().to_string(), bytes);
+        }
+        FsCommand::Push { local, remote } => {
+            let bytes = client
+                .push_file_from_path_with_progress(&remote, &local, progress)
+                .await
+                .map_err(fs_error)?;
+            print_transfer(ctx, remote, local.display().to_string(), bytes);
+        }
+    }
+    Ok(())
+}
+
+fn progress(done: usize, total: usize) {
+    eprint!("\r{done}/{total} bytes");
+    if done >= total {
+        eprintln!();
+    }
+}
+
+fn print_transfer(ctx: &Context, remote_path: String, local_path: String, bytes: usize) {
+    let report = TransferReport {
+        remote_path,
+        local_path,
+        bytes,
+    };
+    ctx.print(&report, |r| {
+        println!("{} bytes: {} <-> {}", r.bytes, r.remote_path, r.local_path);
+    });
+}
+
+fn fs_error(err: ControllerFsError) -> ApiError {
+    ApiError::new(err.kind, err.message)
+}
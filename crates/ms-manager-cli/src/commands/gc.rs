@@ -0,0 +1,46 @@
+use ms_manager_lib::api_error::{ApiError, ApiResult};
+use ms_manager_lib::services::payload_gc;
+
+use crate::GcArgs;
+use crate::context::Context;
+
+pub async fn run(ctx: &Context, args: GcArgs) -> ApiResult<()> {
+    let state = &ctx.state;
+    let _install = state.install_lock().await?;
+    let layout = state.layout_get();
+    let installed = state.install_state_get();
+    let bindings = state.bridge_instances_get();
+    let report = tokio::task::spawn_blocking(move || {
+        payload_gc::collect_garbage(
+            &layout,
+            installed.as_ref(),
+            &bindings,
+            args.keep,
+            args.dry_run,
+        )
+    })
+    .await
+    .map_err(|e| ApiError::new("internal_error", format!("payload gc task failed: {e}")))??;
+
+    ctx.print(&report, |r| {
+        let verb = if r.dry_run { "would remove" } else { "removed" };
+        for kept in &r.kept_versions {
+            println!("keep    {}", kept.tag);
+        }
+        for entry in r.removed_versions.iter().chain(&r.removed_blobs) {
+            println!("{verb} {} ({} bytes)", entry.path, entry.bytes);
+        }
+        for failure in &r.failed {
+            println!("FAILED  {}: {}", failure.path, failure.message);
+        }
+        println!("{verb} {} bytes in total", r.bytes_freed);
+    });
+
+    if !report.failed.is_empty() {
+        return Err(ApiError::new(
+            "payload_gc_incomplete",
+            format!("{} entries could not be removed", report.failed.len()),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use serde::Serialize;
+
+use ms_manager_core::{Channel, InstallHistoryAction};
+use ms_manager_lib::api_error::ApiResult;
+use ms_manager_lib::models::InstallEvent;
+use ms_manager_lib::services::{distribution, install, install_history};
+
+use crate::InstallArgs;
+use crate::context::Context;
+
+#[derive(Debug, Serialize)]
+struct InstallReport {
+    channel: Channel,
+    tag: String,
+    profile: String,
+    path: String,
+    activated: bool,
+}
+
+pub async fn run(ctx: &Context, args: InstallArgs) -> ApiResult<()> {
+    let state = &ctx.state;
+    let install = state.install_lock().await?;
+    let layout = state.layout_get();
+    let plan = distribution::plan_install(
+        &state.http(),
+        &layout,
+        &state.distribution_source_get(),
+        args.channel,
+        args.profile.trim(),
+        args.tag.as_deref(),
+    )
+    .await?;
+
+    // Same anti-rollback rule as the app: an explicit tag is a pin and may downgrade.
+    let allow_downgrade = args.tag.is_some();
+    // Download progress goes to stderr so stdout only carries the result.
+    let installed = install::install_release(
+        state,
+        args.channel,
+        plan,
+        allow_downgrade,
+        args.activate,
+        &install,
+        report_event,
+    )
+    .await?;
+
+    let report = InstallReport {
+        channel: args.channel,
+        path: layout.version_dir(&installed.tag).display().to_string(),
+        tag: installed.tag,
+        profile: installed.profile,
+        activated: args.activate,
+    };
+    ctx.print(&report, |r| {
+        println!("installed {} ({}) into {}", r.tag, r.profile, r.path);
+        if r.activated {
+            println!("current -> {}", r.tag);
+        }
+    });
+    Ok(())
+}
+
+fn report_event(event: InstallEvent) {
+    match event {
+        InstallEvent::Downloading {
+            index,
+            total,
+            filename,
+            ..
+        } => eprintln!("[{index}/{total}] {filename}"),
+        InstallEvent::DownloadProgress {
+            bytes_done,
+            bytes_total,
+            bytes_per_sec,
+            ..
+        } => {
+            let mib = |bytes: u64| bytes as f64 / (1024.0 * 1024.0);
+            eprintln!(
+                "  {:.1}/{:.1} MiB at {:.1} MiB/s",
+                mib(bytes_done),
+                mib(bytes_total),
+                mib(bytes_per_sec)
+            );
+        }
+        InstallEvent::Begin { .. } | InstallEvent::Applying { .. } | InstallEvent::Done { .. } => {}
+    }
+}
+
+/// Switch `current` back to the previously active release.
+pub async fn rollback(ctx: &Context) -> ApiResult<()> {
+    let next = install_history::rollback(&ctx.state).await?;
+    ctx.print(&next, |next| {
+        println!("current -> {} ({})", next.tag, next.profile);
+    });
+    Ok(())
+}
+
+/// Activated releases, oldest first.
+pub fn history(ctx: &Context) -> ApiResult<()> {
+    let entries = install_history::read(&ctx.state.layout_get())?;
+    ctx.print(&entries, |entries| {
+        for entry in entries {
+            let action = match entry.action {
+                InstallHistoryAction::Install => "install",
+                InstallHistoryAction::Rollback => "rollback",
+            };
+            println!(
+                "{} {action:<8} {} ({}, {})",
+                entry.at_ms,
+                entry.tag,
+                entry.channel.as_str(),
+                entry.profile
+            );
+        }
+    });
+    Ok(())
+}
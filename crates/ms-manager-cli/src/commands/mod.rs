@@ -0,0 +1,8 @@
+pub mod audit;
+pub mod flash;
+pub mod fs;
+pub mod gc;
+pub mod install;
+pub mod presets;
+pub mod releases;
+pub mod verify;
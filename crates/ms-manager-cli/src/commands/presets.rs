@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use ms_manager_core::{StepPresetReport, StepPresetStatus, StepPresetTool};
+use ms_manager_lib::api_error::{ApiError, ApiResult};
+use ms_manager_lib::services::artifact_resolver;
+
+use crate::context::Context;
+
+const VALIDATE_OPERATION: &str = "validate-step-graph-preset";
+
+#[derive(Debug, Serialize)]
+struct PresetValidation {
+    path: String,
+    report: StepPresetReport,
+}
+
+/// Validate step presets with the payload's core file tool; fails when any preset is not `ok`.
+pub fn validate(ctx: &Context, files: &[PathBuf]) -> ApiResult<()> {
+    let layout = ctx.state.layout_get();
+    let tool = StepPresetTool::new(artifact_resolver::resolve_management_core_file_tool_exe(
+        &layout,
+    )?);
+
+    let mut results = Vec::with_capacity(files.len());
+    for path in files {
+        let report = tool
+            .validate(path)
+            .map_err(|e| ApiError::new("step_preset_failed", e.to_string()))?;
+        if report.operation != VALIDATE_OPERATION {
+            return Err(ApiError::new(
+                "step_preset_failed",
+                format!(
+                    "unexpected step preset tool operation {} for {}",
+                    report.operation,
+                    path.display()
+                ),
+            ));
+        }
+        results.push(PresetValidation {
+            path: path.display().to_string(),
+            report,
+        });
+    }
+
+    ctx.print(&results, |results| {
+        for result in results {
+            println!(
+                "{:?} {:?} {} ({})",
+                result.report.status,
+                result.report.compatibility,
+                result.path,
+                result.report.semantic_name
+            );
+        }
+    });
+
+    let invalid = results
+        .iter()
+        .filter(|result| result.report.status != StepPresetStatus::Ok)
+        .map(|result| result.path.clone())
+        .collect::<Vec<_>>();
+    if !invalid.is_empty() {
+        return Err(ApiError::new(
+            "step_preset_invalid",
+            format!("invalid step presets: {}", invalid.join(", ")),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use ms_manager_core::Channel;
+use ms_manager_lib::api_error::ApiResult;
+use ms_manager_lib::services::distribution;
+
+use crate::context::Context;
+
+pub async fn list(ctx: &Context, channel: Channel) -> ApiResult<()> {
+    let source = ctx.state.distribution_source_get();
+    let tags = distribution::list_tags_for_channel(&ctx.state.http(), &source, channel).await?;
+    ctx.print(&tags, |tags| {
+        for tag in tags {
+            println!("{tag}");
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use serde::Serialize;
+
+use ms_manager_core::{
+    Channel, DistributionSource, ManifestDiagnostic, Platform, select_install_set_assets,
+};
+use ms_manager_lib::api_error::{ApiError, ApiResult};
+use ms_manager_lib::services::{assets, distribution};
+
+use crate::VerifyArgs;
+use crate::context::Context;
+
+#[derive(Debug, Serialize)]
+struct VerifyReport {
+    channel: Channel,
+    tag: String,
+    source: DistributionSource,
+    /// Set when the source was unreachable and the channel's last verified manifest was used.
+    stale_fetched_at_ms: Option<u64>,
+    warnings: Vec<ManifestDiagnostic>,
+    assets: Vec<AssetCheck>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum AssetStatus {
+    Ok,
+    Missing,
+    Invalid,
+}
+
+#[derive(Debug, Serialize)]
+struct AssetCheck {
+    id: String,
+    filename: String,
+    status: AssetStatus,
+    /// Signing key of the asset's detached signature, when it has one.
+    key_id: Option<String>,
+    message: Option<String>,
+}
+
+/// The manifest signature and structure are checked while resolving it; with a profile, the
+/// cached assets of that install set are re-hashed and their signatures re-checked.
+pub async fn run(ctx: &Context, args: VerifyArgs) -> ApiResult<()> {
+    let state = &ctx.state;
+    let layout = state.layout_get();
+    let source = state.distribution_source_get();
+    let distribution::AvailableManifest {
+        source,
+        manifest,
+        tag,
+        stale_fetched_at_ms,
+    } = distribution::resolve_available_manifest(
+        &state.http(),
+        &layout,
+        &source,
+        args.channel,
+        args.tag.as_deref(),
+    )
+    .await?;
+
+    let mut checks = Vec::new();
+    if let Some(profile) = args.profile.as_deref() {
+        let platform = Platform::current()?;
+        let keyring = distribution::load_keyring(&state.http(), &layout, &source).await;
+        for asset in select_install_set_assets(
+            &manifest,
+            profile,
+            platform.os.as_str(),
+            platform.arch.as_str(),
+        )? {
+            let (status, key_id, message) = if !layout
+                .asset_cache_path(&asset.sha256, &asset.filename)
+                .exists()
+            {
+                (AssetStatus::Missing, None, None)
+            } else {
+                match assets::verify_cached_asset(&layout, &keyring, &asset.sha256, &asset.filename)
+                {
+                    Ok(key_id) => (AssetStatus::Ok, key_id, None),
+                    Err(err) => (AssetStatus::Invalid, None, Some(err.message)),
+                }
+            };
+            checks.push(AssetCheck {
+                id: asset.id,
+                filename: asset.filename,
+                status,
+                key_id,
+                message,
+            });
+        }
+    }
+
+    let report = VerifyReport {
+        channel: args.channel,
+        tag,
+        source,
+        stale_fetched_at_ms,
+        warnings: manifest.validate().warnings().cloned().collect(),
+        assets: checks,
+    };
+    ctx.print(&report, |r| {
+        println!("manifest {} ({}): signature ok", r.tag, r.channel.as_str());
+        if let Some(fetched_at_ms) = r.stale_fetched_at_ms {
+            println!(
+                "  source unreachable: last verified manifest, fetched at {fetched_at_ms} (unix ms)"
+            );
+        }
+        for warning in &r.warnings {
+            println!(
+                "  warning {} at {}: {}",
+                warning.code, warning.path, warning.message
+            );
+        }
+        for check in &r.assets {
+            let status = match check.status {
+                AssetStatus::Ok => "ok",
+                AssetStatus::Missing => "missing",
+                AssetStatus::Invalid => "INVALID",
+            };
+            let detail = check
+                .message
+                .as_deref()
+                .or(check.key_id.as_deref())
+                .map(|d| format!(" ({d})"))
+                .unwrap_or_default();
+            println!("  {status:<8} {}{detail}", check.filename);
+        }
+    });
+
+    let invalid = report
+        .assets
+        .iter()
+        .filter(|check| matches!(check.status, AssetStatus::Invalid))
+        .map(|check| check.id.clone())
+        .collect::<Vec<_>>();
+    if !invalid.is_empty() {
+        return Err(ApiError::new(
+            "asset_verify_failed",
+            format!("cached assets failed verification: {}", invalid.join(", ")),
+        )
+        .with_details(serde_json::json!({ "asset_ids": invalid })));
+    }
+    Ok(())
+}
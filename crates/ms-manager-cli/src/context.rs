@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use ms_manager_lib::api_error::{ApiError, ApiResult};
+use ms_manager_lib::models::PayloadRecoveryReport;
+use ms_manager_lib::state::AppState;
+
+/// Bundle identifier of the desktop app; Tauri keeps its config dir under this name.
+const APP_IDENTIFIER: &str = "io.petitechose.midistudio.manager";
+
+pub struct Context {
+    pub state: AppState,
+    json: bool,
+}
+
+impl Context {
+    pub fn load(
+        settings_path: Option<PathBuf>,
+        payload_root: Option<&str>,
+        json: bool,
+    ) -> ApiResult<Self> {
+        let settings_path = match settings_path {
+            Some(path) => path,
+            None => default_settings_path()?,
+        };
+        let state = AppState::load_from_settings_path(settings_path, payload_root)?;
+        if let Some(report) = state.payload_recovery_get() {
+            report_recovery(&report);
+        }
+
+        Ok(Self { state, json })
+    }
+
+    /// Print `value` as JSON with `--json`, or through `human` otherwise.
+    pub fn print<T: Serialize>(&self, value: &T, human: impl FnOnce(&T)) {
+        if self.json {
+            match serde_json::to_string_pretty(value) {
+                Ok(text) => println!("{text}"),
+                Err(e) => eprintln!("error: serialize output: {e}"),
+            }
+        } else {
+            human(value);
+        }
+    }
+}
+
+/// Tell the user on stderr what was cleaned up after an interrupted install.
+fn report_recovery(report: &PayloadRecoveryReport) {
+    if let (Some(phase), Some(tag)) = (report.interrupted, report.interrupted_tag.as_deref()) {
+        eprintln!("recovery: previous install step {phase:?} of {tag} was interrupted");
+    }
+    for dir in &report.removed_dirs {
+        eprintln!("recovery: removed {dir}");
+    }
+    for tag in &report.restored_versions {
+        eprintln!("recovery: restored {tag}");
+    }
+    if let Some(tag) = &report.current_restored {
+        eprintln!("recovery: current -> {tag}");
+    }
+    for failure in &report.failed {
+        eprintln!("recovery: FAILED {failure}");
+    }
+}
+
+/// The desktop app's settings file (Tauri's app config dir).
+fn default_settings_path() -> ApiResult<PathBuf> {
+    let base = match std::env::consts::OS {
+        "windows" => env_dir("APPDATA")?,
+        "macos" => env_dir("HOME")?.join("Library").join("Application Support"),
+        "linux" => match std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+            Some(v) => PathBuf::from(v),
+            None => env_dir("HOME")?.join(".config"),
+        },
+        other => {
+            return Err(ApiError::new(
+                "unsupported_platform",
+                format!("unsupported platform: {other}"),
+            ));
+        }
+    };
+    Ok(base.join(APP_IDENTIFIER).join("settings.json"))
+}
+
+fn env_dir(name: &str) -> ApiResult<PathBuf> {
+    std::env::var_os(name)
+        .map(PathBuf::from)
+        .ok_or_else(|| ApiError::new("env_missing", format!("missing {name}")))
+}
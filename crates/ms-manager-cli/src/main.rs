@@ -0,0 +1,252 @@
+//! Headless `ms-manager`: release resolution, installs, flashing and controller FS transfers
+//! without a desktop session.
+//!
+//! It reads and writes the same settings and payload state files as the desktop app.
+
+mod commands;
+mod context;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand};
+use ms_manager_core::Channel;
+use ms_manager_lib::models::DEFAULT_GC_KEEP_PER_CHANNEL;
+
+use crate::context::Context;
+
+#[derive(Debug, Parser)]
+#[command(name = "ms-manager-cli", version, about)]
+struct Cli {
+    /// Settings file (defaults to the desktop app's settings.json).
+    #[arg(long, global = true, value_name = "PATH")]
+    settings: Option<PathBuf>,
+
+    /// Payload root, overriding the one configured in settings.
+    #[arg(long, global = true, value_name = "PATH")]
+    payload_root: Option<String>,
+
+    /// Print JSON on stdout instead of text.
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Published releases.
+    #[command(subcommand)]
+    Releases(ReleasesCommand),
+    /// Download, verify and install a release.
+    Install(InstallArgs),
+    /// Switch back to the previously active release.
+    Rollback,
+    /// Releases activated so far, oldest first.
+    History,
+    /// Verify a release manifest and the cached assets of an install set.
+    Verify(VerifyArgs),
+    /// Check an installed version against the files it was installed with.
+    Audit(AuditArgs),
+    /// Flash controller firmware with the payload loader.
+    Flash(FlashArgs),
+    /// Controller filesystem, through a running bridge.
+    Fs(FsArgs),
+    /// Step presets.
+    #[command(subcommand)]
+    Presets(PresetsCommand),
+    /// Remove installed versions and cached assets nothing refers to.
+    Gc(GcArgs),
+}
+
+#[derive(Debug, Subcommand)]
+enum ReleasesCommand {
+    /// List release tags of a channel, newest first.
+    List {
+        #[arg(long, default_value = "stable", value_parser = parse_channel)]
+        channel: Channel,
+    },
+}
+
+#[derive(Debug, Args)]
+struct InstallArgs {
+    #[arg(long, default_value = "stable", value_parser = parse_channel)]
+    channel: Channel,
+    /// Install set id (e.g. `default`, `bitwig`).
+    #[arg(long)]
+    profile: String,
+    /// Release tag; the latest release of the channel when omitted.
+    #[arg(long)]
+    tag: Option<String>,
+    /// Also switch `current` to this release and record it as the installed release.
+    #[arg(long)]
+    activate: bool,
+}
+
+#[derive(Debug, Args)]
+struct VerifyArgs {
+    #[arg(long, default_value = "stable", value_parser = parse_channel)]
+    channel: Channel,
+    /// Release tag; the latest release of the channel when omitted.
+    #[arg(long)]
+    tag: Option<String>,
+    /// Install set whose cached assets to check.
+    #[arg(long)]
+    profile: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct AuditArgs {
+    /// Installed version; the active release when omitted.
+    #[arg(long)]
+    tag: Option<String>,
+    /// Rebuild the version from its cached assets.
+    #[arg(long)]
+    repair: bool,
+}
+
+#[derive(Debug, Args)]
+struct FlashArgs {
+    /// Bridge instance whose loader, firmware and controller to use; optional with one instance.
+    #[arg(long)]
+    instance: Option<String>,
+    /// Firmware image, instead of the one resolved for the instance.
+    #[arg(long, value_name = "PATH")]
+    firmware: Option<PathBuf>,
+    /// Loader device target id, instead of the instance's controller.
+    #[arg(long)]
+    device: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct FsArgs {
+    /// Bridge instance to go through.
+    #[arg(long, global = true)]
+    instance: Option<String>,
+    /// Bridge control port to go through.
+    #[arg(long, global = true)]
+    port: Option<u16>,
+
+    #[command(subcommand)]
+    command: FsCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum FsCommand {
+    /// List a controller directory.
+    Ls {
+        #[arg(default_value = "/")]
+        path: String,
+    },
+    /// Copy a controller file to the local disk.
+    Pull { remote: String, local: PathBuf },
+    /// Copy a local file to the controller.
+    Push { local: PathBuf, remote: String },
+}
+
+#[derive(Debug, Args)]
+struct GcArgs {
+    /// Only report what would be removed.
+    #[arg(long)]
+    dry_run: bool,
+    /// Newest installed versions kept per channel, besides referenced ones.
+    #[arg(long, default_value_t = DEFAULT_GC_KEEP_PER_CHANNEL)]
+    keep: usize,
+}
+
+#[derive(Debug, Subcommand)]
+enum PresetsCommand {
+    /// Validate local step preset files.
+    Validate {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+}
+
+fn parse_channel(value: &str) -> Result<Channel, String> {
+    Channel::ALL
+        .into_iter()
+        .find(|channel| channel.as_str() == value)
+        .ok_or_else(|| {
+            let known = Channel::ALL.map(Channel::as_str).join(", ");
+            format!("unknown channel {value} (expected one of: {known})")
+        })
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let json = cli.json;
+
+    let result = async {
+        let ctx = Context::load(cli.settings, cli.payload_root.as_deref(), json)?;
+        match cli.command {
+            Command::Releases(ReleasesCommand::List { channel }) => {
+                commands::releases::list(&ctx, channel).await
+            }
+            Command::Install(args) => commands::install::run(&ctx, args).await,
+            Command::Rollback => commands::install::rollback(&ctx).await,
+            Command::History => commands::install::history(&ctx),
+            Command::Verify(args) => commands::verify::run(&ctx, args).await,
+            Command::Audit(args) => commands::audit::run(&ctx, args).await,
+            Command::Flash(args) => commands::flash::run(&ctx, args).await,
+            Command::Fs(args) => commands::fs::run(&ctx, args).await,
+            Command::Presets(PresetsCommand::Validate { files }) => {
+                commands::presets::validate(&ctx, &files)
+            }
+            Command::Gc(args) => commands::gc::run(&ctx, args).await,
+        }
+    }
+    .await;
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            if json {
+                eprintln!(
+                    "{}",
+                    serde_json::to_string(&err).unwrap_or_else(|_| err.message.clone())
+                );
+            } else {
+                eprintln!("error: {} ({})", err.message, err.code);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn cli_definition_is_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn parses_install_arguments() {
+        let cli = Cli::parse_from([
+            "ms-manager-cli",
+            "install",
+            "--channel",
+            "beta",
+            "--profile",
+            "bitwig",
+            "--json",
+        ]);
+        assert!(cli.json);
+        let Command::Install(args) = cli.command else {
+            panic!("expected install");
+        };
+        assert_eq!(args.channel, Channel::Beta);
+        assert_eq!(args.profile, "bitwig");
+        assert!(args.tag.is_none());
+        assert!(!args.activate);
+
+        assert!(parse_channel("preview").is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use std::path::Path;
+use std::process::ExitCode;
+
+use ms_manager_core::{DiagnosticSeverity, Manifest};
+
+fn usage() -> ExitCode {
+    eprintln!("usage: check_manifest <manifest.json>");
+    ExitCode::from(2)
+}
+
+/// Run `Manifest::validate` on a manifest before it is signed and published.
+///
+/// Prints every diagnostic with its JSON path and exits non-zero on any error.
+fn main() -> ExitCode {
+    let mut args = std::env::args_os().skip(1);
+    let Some(manifest_path) = args.next() else {
+        return usage();
+    };
+    if args.next().is_some() {
+        return usage();
+    }
+
+    let bytes = match std::fs::read(Path::new(&manifest_path)) {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            eprintln!("could not read manifest: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    // Not `parse_manifest_json`: an unsupported schema is reported with the other diagnostics.
+    let manifest: Manifest = match serde_json::from_slice(&bytes) {
+        Ok(manifest) => manifest,
+        Err(error) => {
+            eprintln!("error manifest_json_invalid $: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let report = manifest.validate();
+    for diagnostic in &report.diagnostics {
+        let severity = match diagnostic.severity {
+            DiagnosticSeverity::Error => "error",
+            DiagnosticSeverity::Warning => "warning",
+        };
+        println!(
+            "{severity} {} {}: {}",
+            diagnostic.code, diagnostic.path, diagnostic.message
+        );
+    }
+
+    let errors = report.errors().count();
+    let warnings = report.warnings().count();
+    println!("{errors} error(s), {warnings} warning(s)");
+    if errors > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, Result};
+use crate::{Keyring, SemVer, channel_for_tag};
+
+pub const APP_UPDATE_SCHEMA: u32 = 1;
+
+/// Repository whose releases carry ms-manager itself.
+pub const MANAGER_REPO_SLUG: &str = "petitechose-midi-studio/ms-manager";
+
+/// Signed description of an ms-manager release, published next to its packages.
+pub const APP_UPDATE_MANIFEST_FILE: &str = "ms-manager-update.json";
+
+/// How a package replaces the running app.
+///
+/// - `appimage`: a single executable file that replaces the running AppImage.
+/// - `tarball`: a `.tar.gz` of the app directory that replaces the directory of the executable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppPackageKind {
+    Appimage,
+    Tarball,
+}
+
+impl AppPackageKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Appimage => "appimage",
+            Self::Tarball => "tarball",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AppPackage {
+    pub kind: AppPackageKind,
+    pub os: String,
+    pub arch: String,
+    pub filename: String,
+    pub size: u64,
+    pub sha256: String,
+    /// Defaults to the release's own download location.
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AppUpdateManifest {
+    pub schema: u32,
+    /// Version without the `v` prefix, e.g. `0.2.0` or `0.2.0-beta.1`.
+    pub version: String,
+    #[serde(default)]
+    pub published_at: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    pub packages: Vec<AppPackage>,
+}
+
+impl AppUpdateManifest {
+    pub fn tag(&self) -> String {
+        format!("v{}", self.version)
+    }
+
+    pub fn package_for(&self, kind: AppPackageKind, os: &str, arch: &str) -> Option<&AppPackage> {
+        self.packages
+            .iter()
+            .find(|package| package.kind == kind && package.os == os && package.arch == arch)
+    }
+
+    /// Whether this release has a higher precedence than `current` (a version or a tag).
+    pub fn is_newer_than(&self, current: &str) -> bool {
+        let current = current.strip_prefix('v').unwrap_or(current);
+        match (SemVer::parse(&self.version), SemVer::parse(current)) {
+            (Some(latest), Some(current)) => latest.cmp_precedence(&current).is_gt(),
+            _ => false,
+        }
+    }
+}
+
+pub fn parse_app_update_json(bytes: &[u8]) -> Result<AppUpdateManifest> {
+    let manifest: AppUpdateManifest =
+        serde_json::from_slice(bytes).map_err(CoreError::AppUpdateJson)?;
+    if manifest.schema != APP_UPDATE_SCHEMA {
+        return Err(CoreError::UnsupportedAppUpdateSchema(manifest.schema));
+    }
+    let invalid = |reason: String| Err(CoreError::InvalidAppUpdate(reason));
+    if SemVer::parse(&manifest.version).is_none() {
+        return invalid(format!("version {} is not SemVer", manifest.version));
+    }
+    for package in &manifest.packages {
+        if package.filename.is_empty()
+            || package.filename.contains(['/', '\\'])
+            || package.filename.starts_with('.')
+        {
+            return invalid(format!("bad package filename {:?}", package.filename));
+        }
+        if package.sha256.len() != 64
+            || !package
+                .sha256
+                .bytes()
+                .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+        {
+            return invalid(format!("bad sha256 for {}", package.filename));
+        }
+    }
+    Ok(manifest)
+}
+
+/// Verify an app update manifest against `keyring` and parse it.
+///
+/// The signing key must be trusted for the channel of the announced version, so a key that
+/// only signs beta releases cannot push a stable ms-manager.
+pub fn verify_app_update(
+    keyring: &Keyring,
+    manifest_json_bytes: &[u8],
+    signature_text: &str,
+) -> Result<AppUpdateManifest> {
+    let manifest = parse_app_update_json(manifest_json_bytes)?;
+    let channel = channel_for_tag(&manifest.tag())
+        .ok_or_else(|| CoreError::InvalidAppUpdate(format!("no channel for {}", manifest.tag())))?;
+    keyring.verify_manifest(channel, manifest_json_bytes, signature_text)?;
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD as B64;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+    use crate::{Channel, TrustedKey};
+
+    const SHA: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    fn manifest_json(version: &str) -> String {
+        format!(
+            r#"{{
+  "schema": 1,
+  "version": "{version}",
+  "packages": [
+    {{"kind": "appimage", "os": "linux", "arch": "x86_64", "filename": "ms-manager.AppImage", "size": 3, "sha256": "{SHA}"}},
+    {{"kind": "tarball", "os": "linux", "arch": "x86_64", "filename": "ms-manager.tar.gz", "size": 3, "sha256": "{SHA}"}}
+  ]
+}}"#
+        )
+    }
+
+    fn keyring(channels: Vec<Channel>) -> (Keyring, SigningKey) {
+        let key = SigningKey::from_bytes(&[9; 32]);
+        let keyring = Keyring::new(vec![TrustedKey {
+            id: "app".to_string(),
+            public_key_b64: B64.encode(key.verifying_key().to_bytes()),
+            channels,
+        }]);
+        (keyring, key)
+    }
+
+    fn sign(key: &SigningKey, bytes: &[u8]) -> String {
+        format!("app:{}", B64.encode(key.sign(bytes).to_bytes()))
+    }
+
+    #[test]
+    fn verified_manifest_selects_package_and_compares_versions() {
+        let (keyring, key) = keyring(vec![Channel::Stable]);
+        let json = manifest_json("0.2.0");
+        let manifest =
+            verify_app_update(&keyring, json.as_bytes(), &sign(&key, json.as_bytes())).unwrap();
+
+        let package = manifest
+            .package_for(AppPackageKind::Tarball, "linux", "x86_64")
+            .unwrap();
+        assert_eq!(package.filename, "ms-manager.tar.gz");
+        assert!(
+            manifest
+                .package_for(AppPackageKind::Appimage, "linux", "aarch64")
+                .is_none()
+        );
+        assert!(manifest.is_newer_than("0.1.4-beta.3"));
+        assert!(manifest.is_newer_than("v0.2.0-rc.1"));
+        assert!(!manifest.is_newer_than("0.2.0"));
+
+        let tampered = json.replace("\"size\": 3", "\"size\": 4");
+        assert!(matches!(
+            verify_app_update(&keyring, tampered.as_bytes(), &sign(&key, json.as_bytes())),
+            Err(CoreError::Signature)
+        ));
+    }
+
+    #[test]
+    fn verify_requires_a_key_trusted_for_the_version_channel() {
+        let (keyring, key) = keyring(vec![Channel::Beta]);
+        let json = manifest_json("0.2.0");
+        assert!(matches!(
+            verify_app_update(&keyring, json.as_bytes(), &sign(&key, json.as_bytes())),
+            Err(CoreError::KeyNotTrustedForChannel { .. })
+        ));
+        let json = manifest_json("0.2.0-beta.1");
+        assert!(verify_app_update(&keyring, json.as_bytes(), &sign(&key, json.as_bytes())).is_ok());
+    }
+
+    #[test]
+    fn parse_rejects_unsafe_packages() {
+        let json = manifest_json("0.2.0").replace("ms-manager.tar.gz", "../ms-manager.tar.gz");
+        assert!(matches!(
+            parse_app_update_json(json.as_bytes()),
+            Err(CoreError::InvalidAppUpdate(_))
+        ));
+        let json = manifest_json("0.2.0").replace("\"schema\": 1", "\"schema\": 2");
+        assert!(matches!(
+            parse_app_update_json(json.as_bytes()),
+            Err(CoreError::UnsupportedAppUpdateSchema(2))
+        ));
+    }
+}
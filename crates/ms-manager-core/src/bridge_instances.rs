@@ -1,14 +1,18 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
-use crate::{ArtifactSource, Channel};
+use crate::{ArtifactSource, Channel, FirmwareTarget, VersionRange, parse_tag_for_channel};
 
 pub const BRIDGE_INSTANCES_SCHEMA: u32 = 1;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// Host application an instance drives; see [`crate::HostAppDescriptor`] for what each needs.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum BridgeApp {
     Bitwig,
+    /// Any DAW or MIDI software talking to the controller over plain MIDI, such as Reaper or
+    /// Ableton Live.
+    MidiHost,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -19,27 +23,96 @@ pub enum BridgeMode {
     WasmSim,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+/// What the background update check does when an instance's channel has a newer release.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
-pub enum FirmwareTarget {
-    Standalone,
-    Bitwig,
+pub enum UpdatePolicyMode {
+    /// Nothing; updates are only looked for on request.
+    Manual,
+    /// Report the update and leave it to the user.
+    #[default]
+    Notify,
+    /// Install the release for the instance without flashing it.
+    AutoInstall,
+    /// Install the release, then flash the instance's controller with it.
+    AutoInstallAndFlash,
+}
+
+impl UpdatePolicyMode {
+    pub fn is_automatic(self) -> bool {
+        matches!(self, Self::AutoInstall | Self::AutoInstallAndFlash)
+    }
+}
+
+/// Local hours `[start_hour, end_hour)` in which automatic updates may run. The window wraps
+/// past midnight when `end_hour` is before `start_hour`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MaintenanceWindow {
+    pub start_hour: u8,
+    pub end_hour: u8,
+}
+
+impl MaintenanceWindow {
+    pub fn contains(&self, hour: u8) -> bool {
+        if self.start_hour <= self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UpdatePolicy {
+    #[serde(default)]
+    pub mode: UpdatePolicyMode,
+    /// Only take releases with the same major and minor version as the running one.
+    #[serde(default)]
+    pub same_minor_only: bool,
+    #[serde(default)]
+    pub maintenance_window: Option<MaintenanceWindow>,
 }
 
-impl FirmwareTarget {
-    pub fn profile_id(self) -> &'static str {
-        match self {
-            Self::Standalone => "default",
-            Self::Bitwig => "bitwig",
+impl UpdatePolicy {
+    /// Whether `latest` may replace `current` without user action at local `hour`.
+    ///
+    /// Never allows a downgrade, or a tag that does not belong to `channel`.
+    pub fn allows_auto_update(
+        &self,
+        channel: Channel,
+        current: &str,
+        latest: &str,
+        hour: u8,
+    ) -> bool {
+        if !self.mode.is_automatic() {
+            return false;
         }
+        let (Some(current), Some(latest)) = (
+            parse_tag_for_channel(channel, current),
+            parse_tag_for_channel(channel, latest),
+        ) else {
+            return false;
+        };
+        if !latest.cmp_precedence(&current).is_gt() {
+            return false;
+        }
+        if self.same_minor_only && (latest.major, latest.minor) != (current.major, current.minor) {
+            return false;
+        }
+        self.maintenance_window
+            .is_none_or(|window| window.contains(hour))
     }
 
-    pub fn from_profile_id(value: &str) -> Option<Self> {
-        match value.trim() {
-            "default" => Some(Self::Standalone),
-            "bitwig" => Some(Self::Bitwig),
-            _ => None,
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(window) = self.maintenance_window {
+            if window.start_hour > 23 || window.end_hour > 23 {
+                return Err("maintenance window hours must be 0..=23".to_string());
+            }
+            if window.start_hour == window.end_hour {
+                return Err("maintenance window cannot be empty".to_string());
+            }
         }
+        Ok(())
     }
 }
 
@@ -48,7 +121,7 @@ fn default_enabled() -> bool {
 }
 
 fn default_target() -> FirmwareTarget {
-    FirmwareTarget::Bitwig
+    FirmwareTarget::bitwig()
 }
 
 fn default_artifact_source() -> ArtifactSource {
@@ -77,11 +150,16 @@ pub struct BridgeInstanceBinding {
     pub installed_channel: Option<Channel>,
     #[serde(default)]
     pub installed_pinned_tag: Option<String>,
+    /// Range the instance follows, e.g. `~0.2`; installs take the highest matching tag.
+    #[serde(default)]
+    pub installed_version_range: Option<String>,
     pub host_udp_port: u16,
     pub control_port: u16,
     pub log_broadcast_port: u16,
     #[serde(default = "default_enabled")]
     pub enabled: bool,
+    #[serde(default)]
+    pub update_policy: UpdatePolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -143,6 +221,10 @@ impl BridgeInstancesState {
                     instance.log_broadcast_port
                 ));
             }
+            instance
+                .update_policy
+                .validate()
+                .map_err(|err| format!("{err} for {}", instance.instance_id))?;
             match instance.artifact_source {
                 ArtifactSource::Installed => {
                     if instance.installed_channel.is_none() {
@@ -151,8 +233,24 @@ impl BridgeInstancesState {
                             instance.instance_id
                         ));
                     }
+                    if let Some(range) = &instance.installed_version_range {
+                        VersionRange::parse(range)
+                            .map_err(|err| format!("{err} for {}", instance.instance_id))?;
+                    }
                 }
                 ArtifactSource::Workspace => {
+                    if !instance.target.is_standalone() && !instance.target.is_bitwig() {
+                        return Err(format!(
+                            "workspace builds only provide standalone and bitwig firmware, not {} for {}",
+                            instance.target, instance.instance_id
+                        ));
+                    }
+                    if instance.update_policy.mode.is_automatic() {
+                        return Err(format!(
+                            "automatic updates need installed artifacts for {}",
+                            instance.instance_id
+                        ));
+                    }
                     if instance.installed_channel.is_some() {
                         return Err(format!(
                             "installed_channel must be empty for workspace instance {}",
@@ -165,6 +263,12 @@ impl BridgeInstancesState {
                             instance.instance_id
                         ));
                     }
+                    if instance.installed_version_range.is_some() {
+                        return Err(format!(
+                            "installed_version_range must be empty for workspace instance {}",
+                            instance.instance_id
+                        ));
+                    }
                 }
             }
         }
@@ -186,14 +290,16 @@ mod tests {
             controller_serial: serial.to_string(),
             controller_vid: 0x16C0,
             controller_pid: 0x0489,
-            target: FirmwareTarget::Bitwig,
+            target: FirmwareTarget::bitwig(),
             artifact_source: ArtifactSource::Installed,
             installed_channel: Some(Channel::Stable),
             installed_pinned_tag: None,
+            installed_version_range: None,
             host_udp_port: 9000 + offset,
             control_port: 7999 + offset,
             log_broadcast_port: 9999 + offset,
             enabled: true,
+            update_policy: UpdatePolicy::default(),
         }
     }
 
@@ -248,4 +354,76 @@ mod tests {
         let err = state.validate().unwrap_err();
         assert!(err.contains("installed_channel must be empty"));
     }
+
+    #[test]
+    fn validate_limits_workspace_instances_to_workspace_targets() {
+        let mut instance = binding("bitwig-hardware-17081760", "17081760", 0);
+        instance.target = FirmwareTarget::parse("factory-test").unwrap();
+        let mut state = BridgeInstancesState {
+            schema: BRIDGE_INSTANCES_SCHEMA,
+            instances: vec![instance],
+        };
+        state.validate().unwrap();
+
+        let instance = &mut state.instances[0];
+        instance.artifact_source = ArtifactSource::Workspace;
+        instance.installed_channel = None;
+        assert!(state.validate().unwrap_err().contains("factory-test"));
+    }
+
+    #[test]
+    fn update_policy_allows_newer_releases_within_constraints() {
+        let mut policy = UpdatePolicy {
+            mode: UpdatePolicyMode::AutoInstall,
+            ..UpdatePolicy::default()
+        };
+        assert!(policy.allows_auto_update(Channel::Beta, "v1.2.0-beta.1", "v1.3.0-beta.1", 12));
+        assert!(!policy.allows_auto_update(Channel::Beta, "v1.3.0-beta.1", "v1.2.0-beta.1", 12));
+        assert!(!policy.allows_auto_update(Channel::Beta, "v1.2.0-beta.1", "v1.3.0", 12));
+
+        policy.same_minor_only = true;
+        assert!(!policy.allows_auto_update(Channel::Beta, "v1.2.0-beta.1", "v1.3.0-beta.1", 12));
+        assert!(policy.allows_auto_update(Channel::Beta, "v1.2.0-beta.1", "v1.2.0-beta.2", 12));
+
+        policy.maintenance_window = Some(MaintenanceWindow {
+            start_hour: 22,
+            end_hour: 4,
+        });
+        assert!(policy.allows_auto_update(Channel::Beta, "v1.2.0-beta.1", "v1.2.0-beta.2", 23));
+        assert!(policy.allows_auto_update(Channel::Beta, "v1.2.0-beta.1", "v1.2.0-beta.2", 3));
+        assert!(!policy.allows_auto_update(Channel::Beta, "v1.2.0-beta.1", "v1.2.0-beta.2", 12));
+
+        policy.mode = UpdatePolicyMode::Notify;
+        assert!(!policy.allows_auto_update(Channel::Beta, "v1.2.0-beta.1", "v1.2.0-beta.2", 23));
+    }
+
+    #[test]
+    fn validate_rejects_automatic_updates_for_workspace_instances() {
+        let mut instance = binding("bitwig-hardware-17081760", "17081760", 0);
+        instance.artifact_source = ArtifactSource::Workspace;
+        instance.installed_channel = None;
+        instance.update_policy.mode = UpdatePolicyMode::AutoInstallAndFlash;
+        let state = BridgeInstancesState {
+            schema: BRIDGE_INSTANCES_SCHEMA,
+            instances: vec![instance],
+        };
+
+        let err = state.validate().unwrap_err();
+        assert!(err.contains("automatic updates need installed artifacts"));
+    }
+
+    #[test]
+    fn validate_checks_version_range_syntax() {
+        let mut instance = binding("bitwig-hardware-17081760", "17081760", 0);
+        instance.installed_version_range = Some("~0.2".to_string());
+        let mut state = BridgeInstancesState {
+            schema: BRIDGE_INSTANCES_SCHEMA,
+            instances: vec![instance],
+        };
+        assert!(state.validate().is_ok());
+
+        state.instances[0].installed_version_range = Some("~zero".to_string());
+        let err = state.validate().unwrap_err();
+        assert!(err.contains("invalid version range"));
+    }
 }
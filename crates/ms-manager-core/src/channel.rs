@@ -1,74 +1,346 @@
-use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Channel {
     Stable,
+    Rc,
     Beta,
+    Alpha,
+    Nightly,
 }
 
 impl Channel {
+    pub const ALL: [Channel; 5] = [
+        Channel::Stable,
+        Channel::Rc,
+        Channel::Beta,
+        Channel::Alpha,
+        Channel::Nightly,
+    ];
+
     pub fn as_str(self) -> &'static str {
         match self {
             Channel::Stable => "stable",
+            Channel::Rc => "rc",
             Channel::Beta => "beta",
+            Channel::Alpha => "alpha",
+            Channel::Nightly => "nightly",
+        }
+    }
+
+    /// First prerelease identifier of tags published on this channel (`None` for stable).
+    pub fn prerelease_label(self) -> Option<&'static str> {
+        match self {
+            Channel::Stable => None,
+            other => Some(other.as_str()),
+        }
+    }
+
+    /// Whether releases on this channel are published as GitHub prereleases.
+    pub fn is_prerelease(self) -> bool {
+        self != Channel::Stable
+    }
+}
+
+/// A single dot-separated SemVer prerelease identifier.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PrereleaseIdent {
+    Numeric(u64),
+    AlphaNumeric(String),
+}
+
+impl PartialOrd for PrereleaseIdent {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PrereleaseIdent {
+    // SemVer 2.0 §11.4: numeric identifiers sort numerically and always before alphanumeric ones,
+    // which sort in ASCII order.
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Numeric(a), Self::Numeric(b)) => a.cmp(b),
+            (Self::Numeric(_), Self::AlphaNumeric(_)) => Ordering::Less,
+            (Self::AlphaNumeric(_), Self::Numeric(_)) => Ordering::Greater,
+            (Self::AlphaNumeric(a), Self::AlphaNumeric(b)) => a.as_bytes().cmp(b.as_bytes()),
+        }
+    }
+}
+
+impl fmt::Display for PrereleaseIdent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Numeric(n) => write!(f, "{n}"),
+            Self::AlphaNumeric(s) => f.write_str(s),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// A SemVer 2.0 version: `MAJOR.MINOR.PATCH[-PRERELEASE][+BUILD]`.
+///
+/// `Ord` follows SemVer precedence and only falls back to comparing build metadata to keep the
+/// order total; use [`SemVer::cmp_precedence`] when build metadata must be ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SemVer {
     pub major: u64,
     pub minor: u64,
     pub patch: u64,
+    pub pre: Vec<PrereleaseIdent>,
+    pub build: Vec<String>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct BetaVersion {
-    pub base: SemVer,
-    pub n: u64,
-}
-
-pub fn parse_stable_tag(tag: &str) -> Option<SemVer> {
-    // vMAJOR.MINOR.PATCH
-    static RE: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
-    let re = RE.get_or_init(|| Regex::new(r"^v(\d+)\.(\d+)\.(\d+)$").expect("valid regex"));
-    let cap = re.captures(tag)?;
-    Some(SemVer {
-        major: cap.get(1)?.as_str().parse().ok()?,
-        minor: cap.get(2)?.as_str().parse().ok()?,
-        patch: cap.get(3)?.as_str().parse().ok()?,
-    })
-}
-
-pub fn parse_beta_tag(tag: &str) -> Option<BetaVersion> {
-    // vMAJOR.MINOR.PATCH-beta.N
-    static RE: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
-    let re =
-        RE.get_or_init(|| Regex::new(r"^v(\d+)\.(\d+)\.(\d+)-beta\.(\d+)$").expect("valid regex"));
-    let cap = re.captures(tag)?;
-    Some(BetaVersion {
-        base: SemVer {
-            major: cap.get(1)?.as_str().parse().ok()?,
-            minor: cap.get(2)?.as_str().parse().ok()?,
-            patch: cap.get(3)?.as_str().parse().ok()?,
-        },
-        n: cap.get(4)?.as_str().parse().ok()?,
-    })
+impl SemVer {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre: Vec::new(),
+            build: Vec::new(),
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let (rest, build) = match value.split_once('+') {
+            Some((rest, build)) => (rest, parse_build(build)?),
+            None => (value, Vec::new()),
+        };
+        let (core, pre) = match rest.split_once('-') {
+            Some((core, pre)) => (core, parse_prerelease(pre)?),
+            None => (rest, Vec::new()),
+        };
+
+        let mut parts = core.split('.');
+        let major = parse_numeric(parts.next()?)?;
+        let minor = parse_numeric(parts.next()?)?;
+        let patch = parse_numeric(parts.next()?)?;
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(Self {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        })
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
+    /// The release channel implied by the prerelease label, if any.
+    pub fn channel(&self) -> Option<Channel> {
+        let Some(first) = self.pre.first() else {
+            return Some(Channel::Stable);
+        };
+        let PrereleaseIdent::AlphaNumeric(label) = first else {
+            return None;
+        };
+        Channel::ALL
+            .into_iter()
+            .find(|channel| channel.prerelease_label() == Some(label.as_str()))
+    }
+
+    /// SemVer 2.0 §11 precedence; build metadata is ignored.
+    pub fn cmp_precedence(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                // A version without prerelease has higher precedence than one with.
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            })
+    }
 }
 
-pub fn is_tag_for_channel(channel: Channel, tag: &str) -> bool {
-    match channel {
-        Channel::Stable => parse_stable_tag(tag).is_some(),
-        Channel::Beta => parse_beta_tag(tag).is_some(),
+impl PartialOrd for SemVer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SemVer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_precedence(other)
+            .then_with(|| self.build.cmp(&other.build))
     }
 }
 
+impl fmt::Display for SemVer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            let pre = self.pre.iter().map(ToString::to_string).collect::<Vec<_>>();
+            write!(f, "-{}", pre.join("."))?;
+        }
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-'
+}
+
+fn parse_numeric(value: &str) -> Option<u64> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // No leading zeros.
+    if value.len() > 1 && value.starts_with('0') {
+        return None;
+    }
+    value.parse().ok()
+}
+
+fn parse_prerelease(value: &str) -> Option<Vec<PrereleaseIdent>> {
+    value
+        .split('.')
+        .map(|ident| {
+            if ident.is_empty() || !ident.chars().all(is_ident_char) {
+                return None;
+            }
+            if ident.bytes().all(|b| b.is_ascii_digit()) {
+                return parse_numeric(ident).map(PrereleaseIdent::Numeric);
+            }
+            Some(PrereleaseIdent::AlphaNumeric(ident.to_string()))
+        })
+        .collect()
+}
+
+fn parse_build(value: &str) -> Option<Vec<String>> {
+    value
+        .split('.')
+        .map(|ident| {
+            if ident.is_empty() || !ident.chars().all(is_ident_char) {
+                return None;
+            }
+            Some(ident.to_string())
+        })
+        .collect()
+}
+
+/// Parse a release tag (`v` + SemVer 2.0).
+pub fn parse_tag(tag: &str) -> Option<SemVer> {
+    SemVer::parse(tag.strip_prefix('v')?)
+}
+
+pub fn channel_for_tag(tag: &str) -> Option<Channel> {
+    parse_tag(tag)?.channel()
+}
+
+/// Parse a tag and keep it only when it belongs to `channel`.
+pub fn parse_tag_for_channel(channel: Channel, tag: &str) -> Option<SemVer> {
+    parse_tag(tag).filter(|version| version.channel() == Some(channel))
+}
+
+pub fn is_tag_for_channel(channel: Channel, tag: &str) -> bool {
+    parse_tag_for_channel(channel, tag).is_some()
+}
+
 pub fn compare_tags(channel: Channel, a: &str, b: &str) -> Option<Ordering> {
-    match channel {
-        Channel::Stable => Some(parse_stable_tag(a)?.cmp(&parse_stable_tag(b)?)),
-        Channel::Beta => Some(parse_beta_tag(a)?.cmp(&parse_beta_tag(b)?)),
+    Some(parse_tag_for_channel(channel, a)?.cmp_precedence(&parse_tag_for_channel(channel, b)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_prerelease_and_build_metadata() {
+        let v = parse_tag("v1.2.3-rc.1+build.5").unwrap();
+        assert_eq!((v.major, v.minor, v.patch), (1, 2, 3));
+        assert_eq!(
+            v.pre,
+            vec![
+                PrereleaseIdent::AlphaNumeric("rc".to_string()),
+                PrereleaseIdent::Numeric(1)
+            ]
+        );
+        assert_eq!(v.build, vec!["build".to_string(), "5".to_string()]);
+        assert_eq!(v.to_string(), "1.2.3-rc.1+build.5");
+        assert_eq!(v.channel(), Some(Channel::Rc));
+    }
+
+    #[test]
+    fn rejects_invalid_versions() {
+        for tag in [
+            "1.2.3",
+            "v1.2",
+            "v1.2.3.4",
+            "v01.2.3",
+            "v1.2.3-",
+            "v1.2.3-beta.01",
+            "v1.2.3-beta..1",
+            "v1.2.3+",
+            "v1.2.3-b_eta",
+        ] {
+            assert!(parse_tag(tag).is_none(), "{tag}");
+        }
+    }
+
+    #[test]
+    fn precedence_follows_semver_spec() {
+        // Example ordering from SemVer 2.0 §11.4.
+        let ordered = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+        ];
+        for pair in ordered.windows(2) {
+            let a = SemVer::parse(pair[0]).unwrap();
+            let b = SemVer::parse(pair[1]).unwrap();
+            assert_eq!(
+                a.cmp_precedence(&b),
+                Ordering::Less,
+                "{} < {}",
+                pair[0],
+                pair[1]
+            );
+        }
+    }
+
+    #[test]
+    fn precedence_ignores_build_metadata() {
+        let a = SemVer::parse("1.0.0+linux").unwrap();
+        let b = SemVer::parse("1.0.0+windows").unwrap();
+        assert_eq!(a.cmp_precedence(&b), Ordering::Equal);
+        assert_eq!(
+            compare_tags(Channel::Stable, "v1.0.0+a", "v1.0.0+b"),
+            Some(Ordering::Equal)
+        );
+    }
+
+    #[test]
+    fn channel_is_derived_from_first_prerelease_label() {
+        assert_eq!(channel_for_tag("v0.2.0"), Some(Channel::Stable));
+        assert_eq!(channel_for_tag("v0.2.0-beta.3"), Some(Channel::Beta));
+        assert_eq!(channel_for_tag("v0.2.0-alpha.1"), Some(Channel::Alpha));
+        assert_eq!(
+            channel_for_tag("v0.2.0-nightly.20261017"),
+            Some(Channel::Nightly)
+        );
+        assert_eq!(channel_for_tag("v0.2.0-preview.1"), None);
+        assert!(!is_tag_for_channel(Channel::Beta, "v0.2.0-rc.1"));
+        assert_eq!(
+            compare_tags(Channel::Beta, "v0.2.0-rc.1", "v0.2.0-beta.1"),
+            None
+        );
     }
 }
@@ -1,41 +1,184 @@
+use serde::{Deserialize, Serialize};
+
 use crate::Channel;
+use crate::error::{CoreError, Result};
+use crate::keyring::{Keyring, TrustedKey};
 
 pub const DIST_REPO_SLUG: &str = "petitechose-midi-studio/distribution";
 
+pub const STABLE_KEY_ID: &str = "dist-2026-1";
 pub const STABLE_PUBLIC_KEY_B64: &str = "2rHtM99leFGTpjZ8fZHNCdGXlEKmAw6hEyaat1uGO3M=";
 
-pub fn public_key_b64_for_channel(channel: Channel) -> &'static str {
-    match channel {
-        Channel::Stable | Channel::Beta => STABLE_PUBLIC_KEY_B64,
-    }
-}
+/// Signing keys shipped with this build: `(key id, public key, channels)`.
+///
+/// Rotating keys means publishing the next key here ahead of time, then retiring the old one
+/// through the signed revocation list.
+pub const BUILTIN_SIGNING_KEYS: &[(&str, &str, &[Channel])] =
+    &[(STABLE_KEY_ID, STABLE_PUBLIC_KEY_B64, &Channel::ALL)];
 
-pub fn stable_latest_manifest_url() -> String {
-    format!("https://github.com/{DIST_REPO_SLUG}/releases/latest/download/manifest.json")
+pub fn builtin_keyring() -> Keyring {
+    Keyring::new(
+        BUILTIN_SIGNING_KEYS
+            .iter()
+            .map(|(id, public_key_b64, channels)| TrustedKey {
+                id: id.to_string(),
+                public_key_b64: public_key_b64.to_string(),
+                channels: channels.to_vec(),
+            })
+            .collect(),
+    )
 }
 
-pub fn stable_latest_sig_url() -> String {
-    format!("https://github.com/{DIST_REPO_SLUG}/releases/latest/download/manifest.json.sig")
+/// Prefix of locations that point into a local [`DistributionSource::Directory`].
+///
+/// The remainder is a native filesystem path, not a percent-encoded URL.
+pub const FILE_LOCATION_PREFIX: &str = "file://";
+
+/// Native path behind a `file://` location, or `None` for network URLs.
+pub fn local_path_for_location(location: &str) -> Option<&str> {
+    location.strip_prefix(FILE_LOCATION_PREFIX)
 }
 
-pub fn manifest_url_for_tag(tag: &str) -> String {
-    format!(
-        "https://github.com/{DIST_REPO_SLUG}/releases/download/{tag}/manifest.json",
-        tag = tag
-    )
+/// Where signed releases are fetched from.
+///
+/// Every source serves the same files (`manifest.json`, `manifest.json.sig`, assets and
+/// `revocations.json`), and manifests are verified against the keyring whatever the source.
+///
+/// - `github`: GitHub releases of `repo` (`owner/name`).
+/// - `https`: a mirror laid out as `<base_url>/<tag>/<file>`, listing releases in
+///   `<base_url>/releases.json` (same shape as the GitHub releases API).
+/// - `directory`: the same layout as `https`, rooted at a local absolute path.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DistributionSource {
+    Github { repo: String },
+    Https { base_url: String },
+    Directory { path: String },
 }
 
-pub fn manifest_sig_url_for_tag(tag: &str) -> String {
-    format!(
-        "https://github.com/{DIST_REPO_SLUG}/releases/download/{tag}/manifest.json.sig",
-        tag = tag
-    )
+impl Default for DistributionSource {
+    fn default() -> Self {
+        Self::Github {
+            repo: DIST_REPO_SLUG.to_string(),
+        }
+    }
 }
 
-pub fn asset_url_for_tag(tag: &str, filename: &str) -> String {
-    format!(
-        "https://github.com/{DIST_REPO_SLUG}/releases/download/{tag}/{filename}",
-        tag = tag,
-        filename = filename
-    )
+impl DistributionSource {
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: &str| Err(CoreError::InvalidDistributionSource(reason.to_string()));
+        match self {
+            Self::Github { repo } => {
+                let mut parts = repo.split('/');
+                let valid = matches!(
+                    (parts.next(), parts.next(), parts.next()),
+                    (Some(owner), Some(name), None) if !owner.trim().is_empty() && !name.trim().is_empty()
+                );
+                if !valid {
+                    return invalid("github repo must be 'owner/name'");
+                }
+            }
+            Self::Https { base_url } => {
+                let Some(rest) = base_url.strip_prefix("https://") else {
+                    return invalid("mirror base_url must start with https://");
+                };
+                if rest.trim_matches('/').is_empty() {
+                    return invalid("mirror base_url is missing a host");
+                }
+            }
+            Self::Directory { path } => {
+                if !std::path::Path::new(path).is_absolute() {
+                    return invalid("directory source must be an absolute path");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Location of the newest stable manifest and its signature, when the source has one.
+    pub fn latest_stable_manifest_urls(&self) -> Option<(String, String)> {
+        match self {
+            Self::Github { repo } => Some((
+                format!("https://github.com/{repo}/releases/latest/download/manifest.json"),
+                format!("https://github.com/{repo}/releases/latest/download/manifest.json.sig"),
+            )),
+            Self::Https { .. } | Self::Directory { .. } => None,
+        }
+    }
+
+    /// Location of `filename` in the newest release: GitHub's `releases/latest` download, or
+    /// the source root for mirrors and directories.
+    pub fn latest_release_file_url(&self, filename: &str) -> String {
+        match self {
+            Self::Github { repo } => {
+                format!("https://github.com/{repo}/releases/latest/download/{filename}")
+            }
+            Self::Https { .. } | Self::Directory { .. } => self.root_url(filename),
+        }
+    }
+
+    pub fn manifest_url_for_tag(&self, tag: &str) -> String {
+        self.file_url_for_tag(tag, "manifest.json")
+    }
+
+    pub fn manifest_sig_url_for_tag(&self, tag: &str) -> String {
+        self.file_url_for_tag(tag, "manifest.json.sig")
+    }
+
+    pub fn asset_url_for_tag(&self, tag: &str, filename: &str) -> String {
+        self.file_url_for_tag(tag, filename)
+    }
+
+    pub fn revocation_list_url(&self) -> String {
+        match self {
+            Self::Github { repo } => {
+                format!("https://github.com/{repo}/releases/latest/download/revocations.json")
+            }
+            Self::Https { .. } | Self::Directory { .. } => self.root_url("revocations.json"),
+        }
+    }
+
+    pub fn revocation_list_sig_url(&self) -> String {
+        format!("{}.sig", self.revocation_list_url())
+    }
+
+    /// Release index in the GitHub releases API shape.
+    pub fn releases_index_url(&self) -> String {
+        match self {
+            Self::Github { repo } => {
+                format!("https://api.github.com/repos/{repo}/releases?per_page=100")
+            }
+            Self::Https { .. } | Self::Directory { .. } => self.root_url("releases.json"),
+        }
+    }
+
+    /// Atom feed fallback for tag discovery (GitHub only).
+    pub fn releases_atom_url(&self) -> Option<String> {
+        match self {
+            Self::Github { repo } => Some(format!("https://github.com/{repo}/releases.atom")),
+            Self::Https { .. } | Self::Directory { .. } => None,
+        }
+    }
+
+    fn file_url_for_tag(&self, tag: &str, filename: &str) -> String {
+        match self {
+            Self::Github { repo } => {
+                format!("https://github.com/{repo}/releases/download/{tag}/{filename}")
+            }
+            Self::Https { .. } | Self::Directory { .. } => {
+                self.root_url(&format!("{tag}/{filename}"))
+            }
+        }
+    }
+
+    fn root_url(&self, rel: &str) -> String {
+        match self {
+            Self::Github { repo } => format!("https://github.com/{repo}/{rel}"),
+            Self::Https { base_url } => format!("{}/{rel}", base_url.trim_end_matches('/')),
+            Self::Directory { path } => format!(
+                "{FILE_LOCATION_PREFIX}{}/{rel}",
+                path.trim_end_matches(['/', '\\'])
+            ),
+        }
+    }
 }
@@ -13,6 +13,24 @@ pub enum CoreError {
     #[error("invalid public key")]
     PublicKey,
 
+    #[error("unknown signing key id: {0}")]
+    UnknownKeyId(String),
+
+    #[error("signing key has been revoked: {0}")]
+    RevokedKey(String),
+
+    #[error("signing key {key_id} is not trusted for channel {channel}")]
+    KeyNotTrustedForChannel { key_id: String, channel: String },
+
+    #[error("invalid revocation list JSON")]
+    RevocationListJson(#[source] serde_json::Error),
+
+    #[error("unsupported revocation list schema: {0}")]
+    UnsupportedRevocationSchema(u32),
+
+    #[error("revocation list rejected: {0}")]
+    RevocationListRejected(String),
+
     #[error("invalid manifest JSON")]
     ManifestJson(#[from] serde_json::Error),
 
@@ -32,6 +50,36 @@ pub enum CoreError {
     #[error("install set references unknown asset id: {0}")]
     UnknownAssetId(String),
 
+    #[error(
+        "release {tag} requires ms-manager {required} or newer (this is {current}); update ms-manager first"
+    )]
+    ManagerTooOld {
+        tag: String,
+        required: String,
+        current: String,
+    },
+
+    #[error("invalid app update manifest JSON")]
+    AppUpdateJson(#[source] serde_json::Error),
+
+    #[error("unsupported app update manifest schema: {0}")]
+    UnsupportedAppUpdateSchema(u32),
+
+    #[error("invalid app update manifest: {0}")]
+    InvalidAppUpdate(String),
+
+    #[error("invalid distribution source: {0}")]
+    InvalidDistributionSource(String),
+
+    #[error("invalid network settings: {0}")]
+    InvalidNetworkSettings(String),
+
+    #[error("invalid firmware target: {0:?}")]
+    InvalidFirmwareTarget(String),
+
+    #[error("invalid version range: {0}")]
+    InvalidVersionRange(String),
+
     #[error("unsupported platform: os={os} arch={arch}")]
     UnsupportedPlatform { os: String, arch: String },
 }
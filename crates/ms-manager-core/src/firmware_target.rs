@@ -0,0 +1,194 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Manifest;
+use crate::error::{CoreError, Result};
+
+/// Target id of the manifest's `default` install set.
+pub const STANDALONE_TARGET: &str = "standalone";
+pub const BITWIG_TARGET: &str = "bitwig";
+/// Install set behind [`STANDALONE_TARGET`].
+pub const DEFAULT_PROFILE_ID: &str = "default";
+
+/// Firmware an instance runs: one install set (profile) of the distribution manifest.
+///
+/// A target is stored as the id of its install set, except `default`, which is stored as
+/// `standalone`. `standalone` and `bitwig`, the only targets before install sets became
+/// targets, stay valid, so existing bindings keep their meaning.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct FirmwareTarget(String);
+
+impl FirmwareTarget {
+    pub fn standalone() -> Self {
+        Self(STANDALONE_TARGET.to_string())
+    }
+
+    pub fn bitwig() -> Self {
+        Self(BITWIG_TARGET.to_string())
+    }
+
+    /// Accepts a target id or an install set id; ids are lowercase ASCII letters, digits, `-`
+    /// and `_`.
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim();
+        if value.is_empty()
+            || !value
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'_')
+        {
+            return Err(CoreError::InvalidFirmwareTarget(value.to_string()));
+        }
+        if value == DEFAULT_PROFILE_ID {
+            return Ok(Self::standalone());
+        }
+        Ok(Self(value.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Id of the manifest install set this target installs.
+    pub fn profile_id(&self) -> &str {
+        if self.is_standalone() {
+            DEFAULT_PROFILE_ID
+        } else {
+            &self.0
+        }
+    }
+
+    pub fn from_profile_id(value: &str) -> Option<Self> {
+        Self::parse(value).ok()
+    }
+
+    pub fn is_standalone(&self) -> bool {
+        self.0 == STANDALONE_TARGET
+    }
+
+    pub fn is_bitwig(&self) -> bool {
+        self.0 == BITWIG_TARGET
+    }
+
+    /// Label used when the manifest does not give one.
+    pub fn default_label(&self) -> String {
+        match self.0.as_str() {
+            STANDALONE_TARGET => "Standalone".to_string(),
+            BITWIG_TARGET => "Bitwig".to_string(),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl TryFrom<String> for FirmwareTarget {
+    type Error = CoreError;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::parse(&value)
+    }
+}
+
+impl From<FirmwareTarget> for String {
+    fn from(target: FirmwareTarget) -> Self {
+        target.0
+    }
+}
+
+impl fmt::Display for FirmwareTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A firmware target a release offers, as listed to choose from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FirmwareTargetInfo {
+    pub target: FirmwareTarget,
+    pub profile_id: String,
+    pub label: String,
+    pub description: Option<String>,
+}
+
+/// Targets of every install set `manifest` publishes for `os`/`arch`, in manifest order.
+///
+/// Install sets whose id cannot be a target id are skipped.
+pub fn firmware_targets(manifest: &Manifest, os: &str, arch: &str) -> Vec<FirmwareTargetInfo> {
+    let mut targets: Vec<FirmwareTargetInfo> = Vec::new();
+    for set in manifest
+        .install_sets
+        .iter()
+        .filter(|set| set.os.as_deref() == Some(os) && set.arch.as_deref() == Some(arch))
+    {
+        let Ok(target) = FirmwareTarget::parse(&set.id) else {
+            continue;
+        };
+        if targets.iter().any(|info| info.target == target) {
+            continue;
+        }
+        targets.push(FirmwareTargetInfo {
+            profile_id: target.profile_id().to_string(),
+            label: set
+                .label
+                .clone()
+                .filter(|label| !label.trim().is_empty())
+                .unwrap_or_else(|| target.default_label()),
+            description: set.description.clone(),
+            target,
+        });
+    }
+    targets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_manifest_json;
+
+    #[test]
+    fn legacy_ids_stay_aliases_of_their_install_sets() {
+        let standalone: FirmwareTarget = serde_json::from_str("\"standalone\"").unwrap();
+        assert_eq!(standalone.profile_id(), "default");
+        assert_eq!(FirmwareTarget::parse("default").unwrap(), standalone);
+        assert_eq!(
+            serde_json::to_string(&standalone).unwrap(),
+            "\"standalone\""
+        );
+        assert_eq!(FirmwareTarget::bitwig().profile_id(), "bitwig");
+
+        let ableton = FirmwareTarget::from_profile_id("ableton").unwrap();
+        assert_eq!(ableton.profile_id(), "ableton");
+        assert_eq!(ableton.default_label(), "ableton");
+        for bad in ["", "Ableton", "../x", "a b"] {
+            assert!(FirmwareTarget::parse(bad).is_err(), "{bad}");
+        }
+        assert!(serde_json::from_str::<FirmwareTarget>("\"../x\"").is_err());
+    }
+
+    #[test]
+    fn firmware_targets_follow_install_sets_for_the_platform() {
+        let json = r#"{
+  "schema": 4,
+  "channel": "stable",
+  "tag": "v0.3.0",
+  "published_at": "2026-10-01T00:00:00Z",
+  "repos": [],
+  "assets": [],
+  "install_sets": [
+    {"id": "default", "os": "linux", "arch": "x86_64", "assets": []},
+    {"id": "factory-test", "os": "linux", "arch": "x86_64", "assets": [], "label": "Factory test", "description": "Board bring-up"},
+    {"id": "ableton", "os": "windows", "arch": "x86_64", "assets": []},
+    {"id": "Bad Id", "os": "linux", "arch": "x86_64", "assets": []}
+  ]
+}"#;
+        let manifest = parse_manifest_json(json.as_bytes()).unwrap();
+        let targets = firmware_targets(&manifest, "linux", "x86_64");
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].target, FirmwareTarget::standalone());
+        assert_eq!(targets[0].profile_id, "default");
+        assert_eq!(targets[0].label, "Standalone");
+        assert_eq!(targets[1].target.as_str(), "factory-test");
+        assert_eq!(targets[1].label, "Factory test");
+        assert_eq!(targets[1].description.as_deref(), Some("Board bring-up"));
+    }
+}
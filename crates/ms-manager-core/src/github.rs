@@ -1,7 +1,7 @@
 use regex::Regex;
 use serde::Deserialize;
 
-use crate::channel::{Channel, parse_beta_tag, parse_stable_tag};
+use crate::channel::{Channel, parse_tag_for_channel};
 use crate::error::Result;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -35,7 +35,7 @@ pub fn extract_tags_from_releases_atom(xml: &str) -> Vec<String> {
     // <link ... href="https://github.com/<org>/<repo>/releases/tag/<tag>"/>
     static RE: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
     let re = RE.get_or_init(|| {
-        Regex::new(r"https://github\.com/[^/]+/[^/]+/releases/tag/([A-Za-z0-9._-]+)")
+        Regex::new(r"https://github\.com/[^/]+/[^/]+/releases/tag/([A-Za-z0-9._+-]+)")
             .expect("valid regex")
     });
 
@@ -53,20 +53,10 @@ pub fn extract_tags_from_releases_atom(xml: &str) -> Vec<String> {
 }
 
 pub fn latest_tag_for_channel(channel: Channel, tags: &[String]) -> Option<String> {
-    match channel {
-        Channel::Stable => tags
-            .iter()
-            .filter_map(|t| parse_stable_tag(t).map(|v| (v, t)))
-            .max_by_key(|(v, _)| *v)
-            .map(|(_, t)| t.to_string()),
-
-        Channel::Beta => tags
-            .iter()
-            .filter_map(|t| parse_beta_tag(t).map(|v| (v, t)))
-            .max_by_key(|(v, _)| *v)
-            .map(|(_, t)| t.to_string()),
-
-    }
+    tags.iter()
+        .filter_map(|t| parse_tag_for_channel(channel, t).map(|v| (v, t)))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, t)| t.to_string())
 }
 
 pub fn latest_tag_for_channel_from_releases(
@@ -79,17 +69,8 @@ pub fn latest_tag_for_channel_from_releases(
             continue;
         }
 
-        match channel {
-            Channel::Stable => {
-                if r.prerelease {
-                    continue;
-                }
-            }
-            Channel::Beta => {
-                if !r.prerelease {
-                    continue;
-                }
-            }
+        if r.prerelease != channel.is_prerelease() {
+            continue;
         }
 
         tags.push(r.tag.clone());
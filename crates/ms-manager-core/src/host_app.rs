@@ -0,0 +1,85 @@
+use serde::Serialize;
+
+use crate::BridgeApp;
+
+/// Asset kind of the Bitwig Studio extension.
+pub const BITWIG_EXTENSION_KIND: &str = "bitwig-extension";
+
+/// How ms-manager integrates a controller with one host application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct HostAppDescriptor {
+    pub app: BridgeApp,
+    /// Prefix of the ids of instances bound to this host.
+    pub id: &'static str,
+    pub label: &'static str,
+    /// Manifest asset kind of the extension the host loads; `None` when it talks plain MIDI.
+    pub extension_asset_kind: Option<&'static str>,
+    /// Where that extension is installed, relative to the version directory.
+    pub extension_install_dir: Option<&'static str>,
+    /// Passed to the bridge daemon after the instance's identity and ports.
+    pub daemon_args: &'static [&'static str],
+}
+
+/// Every supported host, in the order they are offered.
+pub const HOST_APPS: &[HostAppDescriptor] = &[
+    HostAppDescriptor {
+        app: BridgeApp::Bitwig,
+        id: "bitwig",
+        label: "Bitwig Studio",
+        extension_asset_kind: Some(BITWIG_EXTENSION_KIND),
+        extension_install_dir: Some("integrations/bitwig"),
+        daemon_args: &[],
+    },
+    HostAppDescriptor {
+        app: BridgeApp::MidiHost,
+        id: "midi-host",
+        label: "MIDI host",
+        extension_asset_kind: None,
+        extension_install_dir: None,
+        daemon_args: &["--host", "midi"],
+    },
+];
+
+impl BridgeApp {
+    pub fn descriptor(self) -> &'static HostAppDescriptor {
+        HOST_APPS
+            .iter()
+            .find(|descriptor| descriptor.app == self)
+            .expect("every BridgeApp has a descriptor")
+    }
+}
+
+/// Host whose extension ships as assets of `kind`.
+pub fn host_app_for_extension_kind(kind: &str) -> Option<&'static HostAppDescriptor> {
+    HOST_APPS
+        .iter()
+        .find(|descriptor| descriptor.extension_asset_kind == Some(kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_app_has_a_distinct_descriptor() {
+        for app in [BridgeApp::Bitwig, BridgeApp::MidiHost] {
+            assert_eq!(app.descriptor().app, app);
+        }
+        let mut ids: Vec<_> = HOST_APPS.iter().map(|descriptor| descriptor.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), HOST_APPS.len());
+
+        assert_eq!(
+            host_app_for_extension_kind(BITWIG_EXTENSION_KIND).map(|descriptor| descriptor.app),
+            Some(BridgeApp::Bitwig)
+        );
+        assert!(host_app_for_extension_kind("firmware").is_none());
+        assert!(
+            BridgeApp::MidiHost
+                .descriptor()
+                .extension_asset_kind
+                .is_none()
+        );
+    }
+}
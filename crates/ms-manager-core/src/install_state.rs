@@ -11,3 +11,82 @@ pub struct InstallState {
     pub profile: String,
     pub tag: String,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallHistoryAction {
+    Install,
+    Rollback,
+}
+
+/// One activation of a release, appended to the install history.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct InstallHistoryEntry {
+    pub at_ms: u64,
+    pub action: InstallHistoryAction,
+    pub channel: Channel,
+    pub profile: String,
+    pub tag: String,
+    /// Tag that was active right before this entry.
+    #[serde(default)]
+    pub previous_tag: Option<String>,
+}
+
+/// The activation to go back to from `current_tag`: the newest entry with another tag that is
+/// still `available`.
+///
+/// Rolling back is itself recorded, so a second rollback returns to the release that was
+/// rolled back from.
+pub fn rollback_target<'a>(
+    history: &'a [InstallHistoryEntry],
+    current_tag: &str,
+    available: impl Fn(&str) -> bool,
+) -> Option<&'a InstallHistoryEntry> {
+    history
+        .iter()
+        .rev()
+        .find(|entry| entry.tag != current_tag && available(&entry.tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(action: InstallHistoryAction, tag: &str) -> InstallHistoryEntry {
+        InstallHistoryEntry {
+            at_ms: 0,
+            action,
+            channel: Channel::Stable,
+            profile: "default".to_string(),
+            tag: tag.to_string(),
+            previous_tag: None,
+        }
+    }
+
+    #[test]
+    fn rollback_targets_previous_activation_and_toggles_back() {
+        let mut history = vec![
+            entry(InstallHistoryAction::Install, "v1.0.0"),
+            entry(InstallHistoryAction::Install, "v1.1.0"),
+            entry(InstallHistoryAction::Install, "v1.1.0"),
+        ];
+        let target = rollback_target(&history, "v1.1.0", |_| true).unwrap();
+        assert_eq!(target.tag, "v1.0.0");
+
+        history.push(entry(InstallHistoryAction::Rollback, "v1.0.0"));
+        let target = rollback_target(&history, "v1.0.0", |_| true).unwrap();
+        assert_eq!(target.tag, "v1.1.0");
+    }
+
+    #[test]
+    fn rollback_skips_versions_that_are_gone() {
+        let history = vec![
+            entry(InstallHistoryAction::Install, "v1.0.0"),
+            entry(InstallHistoryAction::Install, "v1.1.0"),
+            entry(InstallHistoryAction::Install, "v1.2.0"),
+        ];
+        let target = rollback_target(&history, "v1.2.0", |tag| tag != "v1.1.0").unwrap();
+        assert_eq!(target.tag, "v1.0.0");
+        assert!(rollback_target(&history, "v1.2.0", |_| false).is_none());
+    }
+}
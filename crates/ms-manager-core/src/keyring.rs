@@ -0,0 +1,423 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Channel;
+use crate::crypto::verify_manifest_sig_b64;
+use crate::error::{CoreError, Result};
+
+pub const REVOCATION_LIST_SCHEMA: u32 = 1;
+
+/// Separates the key id from the base64 signature in a `.sig` file (`<key_id>:<base64>`).
+///
+/// `:` is not part of the base64 alphabet, so legacy unnamed signatures stay unambiguous.
+const KEY_ID_SEPARATOR: char = ':';
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TrustedKey {
+    pub id: String,
+    pub public_key_b64: String,
+    pub channels: Vec<Channel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestSignature {
+    /// `None` for legacy signatures that predate key ids.
+    pub key_id: Option<String>,
+    pub signature_b64: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RevokedKey {
+    pub key_id: String,
+    #[serde(default)]
+    pub revoked_at: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RevocationList {
+    pub schema: u32,
+    #[serde(default)]
+    pub revoked: Vec<RevokedKey>,
+}
+
+/// Message covered by a detached per-asset signature.
+///
+/// It binds the file name, size and sha256 rather than the raw bytes, so a cached file can be
+/// re-verified from its digest alone, without the manifest that listed it.
+pub fn asset_signature_message(filename: &str, size: u64, sha256: &str) -> Vec<u8> {
+    format!("ms-manager-asset-v1\n{filename}\n{size}\n{sha256}\n").into_bytes()
+}
+
+pub fn parse_signature_text(text: &str) -> Result<ManifestSignature> {
+    let text = text.trim();
+    let (key_id, signature_b64) = match text.split_once(KEY_ID_SEPARATOR) {
+        Some((key_id, sig)) => {
+            let key_id = key_id.trim();
+            if key_id.is_empty() {
+                return Err(CoreError::Signature);
+            }
+            (Some(key_id.to_string()), sig.trim())
+        }
+        None => (None, text),
+    };
+    if signature_b64.is_empty() {
+        return Err(CoreError::Signature);
+    }
+
+    Ok(ManifestSignature {
+        key_id,
+        signature_b64: signature_b64.to_string(),
+    })
+}
+
+pub fn parse_revocation_list_json(bytes: &[u8]) -> Result<RevocationList> {
+    let list: RevocationList =
+        serde_json::from_slice(bytes).map_err(CoreError::RevocationListJson)?;
+    if list.schema != REVOCATION_LIST_SCHEMA {
+        return Err(CoreError::UnsupportedRevocationSchema(list.schema));
+    }
+    Ok(list)
+}
+
+/// Set of trusted distribution signing keys plus the ids that have been revoked.
+///
+/// Revocation is monotonic: once a key id is revoked it is never trusted again, even if a
+/// later revocation list omits it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Keyring {
+    keys: Vec<TrustedKey>,
+    revoked: BTreeSet<String>,
+}
+
+impl Keyring {
+    pub fn new(keys: Vec<TrustedKey>) -> Self {
+        Self {
+            keys,
+            revoked: BTreeSet::new(),
+        }
+    }
+
+    pub fn keys(&self) -> &[TrustedKey] {
+        &self.keys
+    }
+
+    pub fn key(&self, key_id: &str) -> Option<&TrustedKey> {
+        self.keys.iter().find(|key| key.id == key_id)
+    }
+
+    pub fn is_revoked(&self, key_id: &str) -> bool {
+        self.revoked.contains(key_id)
+    }
+
+    pub fn revoke(&mut self, key_id: impl Into<String>) {
+        self.revoked.insert(key_id.into());
+    }
+
+    pub fn revoked_key_ids(&self) -> Vec<String> {
+        self.revoked.iter().cloned().collect()
+    }
+
+    /// Keys that may sign manifests for `channel` and have not been revoked.
+    pub fn active_keys_for_channel(&self, channel: Channel) -> impl Iterator<Item = &TrustedKey> {
+        self.keys
+            .iter()
+            .filter(move |key| key.channels.contains(&channel) && !self.is_revoked(&key.id))
+    }
+
+    /// Verify a manifest signature and return the id of the key that made it.
+    pub fn verify_manifest(
+        &self,
+        channel: Channel,
+        manifest_json_bytes: &[u8],
+        signature_text: &str,
+    ) -> Result<String> {
+        self.verify_signed_bytes(Some(channel), manifest_json_bytes, signature_text)
+    }
+
+    /// Verify a detached asset signature (see [`asset_signature_message`]).
+    ///
+    /// With `channel: None` any active key is accepted; that is meant for re-checking cached
+    /// files whose release channel is no longer known.
+    pub fn verify_asset(
+        &self,
+        channel: Option<Channel>,
+        filename: &str,
+        size: u64,
+        sha256: &str,
+        signature_text: &str,
+    ) -> Result<String> {
+        let message = asset_signature_message(filename, size, sha256);
+        self.verify_signed_bytes(channel, &message, signature_text)
+    }
+
+    /// Verify a signed revocation list and revoke every key id it names.
+    ///
+    /// The list must be signed by a key of this keyring that is not already revoked. A list
+    /// that revokes its own signer, or leaves a channel that had an active key without one, is
+    /// rejected as a whole: a single leaked key must not be able to lock every client out.
+    pub fn apply_revocation_list(
+        &mut self,
+        list_json_bytes: &[u8],
+        signature_text: &str,
+    ) -> Result<RevocationList> {
+        let sig = parse_signature_text(signature_text)?;
+        let key_id = sig.key_id.as_deref().ok_or(CoreError::Signature)?;
+        let key = self.trusted_key(key_id)?;
+        verify_manifest_sig_b64(list_json_bytes, &sig.signature_b64, &key.public_key_b64)?;
+
+        let list = parse_revocation_list_json(list_json_bytes)?;
+        let mut next = self.clone();
+        for revoked in &list.revoked {
+            next.revoke(revoked.key_id.trim());
+        }
+        if next.is_revoked(key_id) {
+            return Err(CoreError::RevocationListRejected(format!(
+                "it revokes its own signing key {key_id}"
+            )));
+        }
+        if let Some(channel) = Channel::ALL.into_iter().find(|&channel| {
+            self.active_keys_for_channel(channel).next().is_some()
+                && next.active_keys_for_channel(channel).next().is_none()
+        }) {
+            return Err(CoreError::RevocationListRejected(format!(
+                "it leaves channel {} without an active key",
+                channel.as_str()
+            )));
+        }
+
+        *self = next;
+        Ok(list)
+    }
+
+    fn verify_signed_bytes(
+        &self,
+        channel: Option<Channel>,
+        bytes: &[u8],
+        signature_text: &str,
+    ) -> Result<String> {
+        let sig = parse_signature_text(signature_text)?;
+        let Some(key_id) = sig.key_id.as_deref() else {
+            // Legacy unnamed signature: accept it from any active key of the channel.
+            return self
+                .keys
+                .iter()
+                .filter(|key| !self.is_revoked(&key.id))
+                .filter(|key| channel.is_none_or(|channel| key.channels.contains(&channel)))
+                .find(|key| {
+                    verify_manifest_sig_b64(bytes, &sig.signature_b64, &key.public_key_b64).is_ok()
+                })
+                .map(|key| key.id.clone())
+                .ok_or(CoreError::Signature);
+        };
+
+        let key = self.trusted_key(key_id)?;
+        if let Some(channel) = channel
+            && !key.channels.contains(&channel)
+        {
+            return Err(CoreError::KeyNotTrustedForChannel {
+                key_id: key_id.to_string(),
+                channel: channel.as_str().to_string(),
+            });
+        }
+        verify_manifest_sig_b64(bytes, &sig.signature_b64, &key.public_key_b64)?;
+        Ok(key.id.clone())
+    }
+
+    fn trusted_key(&self, key_id: &str) -> Result<&TrustedKey> {
+        if self.is_revoked(key_id) {
+            return Err(CoreError::RevokedKey(key_id.to_string()));
+        }
+        self.key(key_id)
+            .ok_or_else(|| CoreError::UnknownKeyId(key_id.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD as B64;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    fn trusted(id: &str, seed: u8, channels: &[Channel]) -> TrustedKey {
+        TrustedKey {
+            id: id.to_string(),
+            public_key_b64: B64.encode(signing_key(seed).verifying_key().to_bytes()),
+            channels: channels.to_vec(),
+        }
+    }
+
+    fn sign(seed: u8, key_id: Option<&str>, bytes: &[u8]) -> String {
+        let sig = B64.encode(signing_key(seed).sign(bytes).to_bytes());
+        match key_id {
+            Some(id) => format!("{id}:{sig}"),
+            None => sig,
+        }
+    }
+
+    fn keyring() -> Keyring {
+        Keyring::new(vec![
+            trusted("dist-1", 1, &[Channel::Stable, Channel::Beta]),
+            trusted("dist-2", 2, &[Channel::Stable, Channel::Beta]),
+            trusted("beta-only", 3, &[Channel::Beta]),
+        ])
+    }
+
+    #[test]
+    fn parse_signature_text_splits_key_id() {
+        let sig = parse_signature_text("dist-2:AAAA\n").unwrap();
+        assert_eq!(sig.key_id.as_deref(), Some("dist-2"));
+        assert_eq!(sig.signature_b64, "AAAA");
+
+        let legacy = parse_signature_text("AAAA").unwrap();
+        assert_eq!(legacy.key_id, None);
+    }
+
+    #[test]
+    fn verify_manifest_uses_named_key() {
+        let manifest = br#"{"schema":3}"#;
+        let keyring = keyring();
+
+        let sig = sign(2, Some("dist-2"), manifest);
+        assert_eq!(
+            keyring
+                .verify_manifest(Channel::Stable, manifest, &sig)
+                .unwrap(),
+            "dist-2"
+        );
+
+        let wrong_key = sign(1, Some("dist-2"), manifest);
+        assert!(matches!(
+            keyring.verify_manifest(Channel::Stable, manifest, &wrong_key),
+            Err(CoreError::Signature)
+        ));
+    }
+
+    #[test]
+    fn verify_manifest_accepts_legacy_unnamed_signature() {
+        let manifest = br#"{"schema":3}"#;
+        let sig = sign(1, None, manifest);
+        assert_eq!(
+            keyring()
+                .verify_manifest(Channel::Beta, manifest, &sig)
+                .unwrap(),
+            "dist-1"
+        );
+    }
+
+    #[test]
+    fn verify_manifest_rejects_key_outside_channel() {
+        let manifest = br#"{"schema":3}"#;
+        let sig = sign(3, Some("beta-only"), manifest);
+        assert!(matches!(
+            keyring().verify_manifest(Channel::Stable, manifest, &sig),
+            Err(CoreError::KeyNotTrustedForChannel { .. })
+        ));
+    }
+
+    #[test]
+    fn verify_asset_checks_detached_signature() {
+        let sha = "ab".repeat(32);
+        let message = asset_signature_message("fw.hex", 42, &sha);
+        let sig = sign(3, Some("beta-only"), &message);
+        let keyring = keyring();
+
+        assert_eq!(
+            keyring
+                .verify_asset(Some(Channel::Beta), "fw.hex", 42, &sha, &sig)
+                .unwrap(),
+            "beta-only"
+        );
+        assert_eq!(
+            keyring
+                .verify_asset(None, "fw.hex", 42, &sha, &sig)
+                .unwrap(),
+            "beta-only"
+        );
+        assert!(matches!(
+            keyring.verify_asset(Some(Channel::Stable), "fw.hex", 42, &sha, &sig),
+            Err(CoreError::KeyNotTrustedForChannel { .. })
+        ));
+        assert!(matches!(
+            keyring.verify_asset(Some(Channel::Beta), "fw.hex", 43, &sha, &sig),
+            Err(CoreError::Signature)
+        ));
+    }
+
+    #[test]
+    fn revocation_list_retires_key() {
+        let mut keyring = keyring();
+        let list = br#"{"schema":1,"revoked":[{"key_id":"dist-1","reason":"leaked"}]}"#;
+        let list_sig = sign(2, Some("dist-2"), list);
+
+        let applied = keyring.apply_revocation_list(list, &list_sig).unwrap();
+        assert_eq!(applied.revoked.len(), 1);
+        assert!(keyring.is_revoked("dist-1"));
+
+        let manifest = br#"{"schema":3}"#;
+        assert!(matches!(
+            keyring.verify_manifest(
+                Channel::Stable,
+                manifest,
+                &sign(1, Some("dist-1"), manifest)
+            ),
+            Err(CoreError::RevokedKey(_))
+        ));
+        assert!(matches!(
+            keyring.verify_manifest(Channel::Stable, manifest, &sign(1, None, manifest)),
+            Err(CoreError::Signature)
+        ));
+    }
+
+    #[test]
+    fn revocation_list_requires_active_signer() {
+        let mut keyring = keyring();
+        keyring.revoke("dist-1");
+
+        let list = br#"{"schema":1,"revoked":[{"key_id":"dist-2"}]}"#;
+        let list_sig = sign(1, Some("dist-1"), list);
+        assert!(matches!(
+            keyring.apply_revocation_list(list, &list_sig),
+            Err(CoreError::RevokedKey(_))
+        ));
+        assert!(!keyring.is_revoked("dist-2"));
+    }
+
+    #[test]
+    fn revocation_list_cannot_revoke_its_signer() {
+        let mut keyring = keyring();
+        let list = br#"{"schema":1,"revoked":[{"key_id":"dist-1"},{"key_id":"dist-2"}]}"#;
+        let list_sig = sign(2, Some("dist-2"), list);
+
+        assert!(matches!(
+            keyring.apply_revocation_list(list, &list_sig),
+            Err(CoreError::RevocationListRejected(_))
+        ));
+        assert!(!keyring.is_revoked("dist-1"));
+        assert!(!keyring.is_revoked("dist-2"));
+    }
+
+    #[test]
+    fn revocation_list_cannot_empty_a_channel() {
+        let mut keyring = Keyring::new(vec![
+            trusted("dist-1", 1, &[Channel::Stable, Channel::Beta]),
+            trusted("beta-only", 3, &[Channel::Beta]),
+        ]);
+        let list = br#"{"schema":1,"revoked":[{"key_id":"dist-1"}]}"#;
+        let list_sig = sign(3, Some("beta-only"), list);
+
+        assert!(matches!(
+            keyring.apply_revocation_list(list, &list_sig),
+            Err(CoreError::RevocationListRejected(_))
+        ));
+        assert!(!keyring.is_revoked("dist-1"));
+    }
+}
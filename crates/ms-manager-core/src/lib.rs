@@ -2,6 +2,7 @@
 //!
 //! This crate is intentionally UI-agnostic and does not depend on Tauri.
 
+mod app_update;
 mod bridge_instances;
 mod channel;
 mod controller_state;
@@ -9,48 +10,85 @@ mod core_file_tool;
 mod crypto;
 mod dist;
 mod error;
+mod firmware_target;
 mod github;
+mod host_app;
 mod install_state;
+mod keyring;
 mod manifest;
+mod manifest_diff;
+mod manifest_validation;
 mod platform;
 mod project_migration;
 mod settings;
 mod step_preset;
+mod version_range;
 
+pub use app_update::{
+    APP_UPDATE_MANIFEST_FILE, APP_UPDATE_SCHEMA, AppPackage, AppPackageKind, AppUpdateManifest,
+    MANAGER_REPO_SLUG, parse_app_update_json, verify_app_update,
+};
 pub use bridge_instances::{
     BRIDGE_INSTANCES_SCHEMA, BridgeApp, BridgeInstanceBinding, BridgeInstancesState, BridgeMode,
-    FirmwareTarget,
+    MaintenanceWindow, UpdatePolicy, UpdatePolicyMode,
+};
+pub use channel::{
+    Channel, PrereleaseIdent, SemVer, channel_for_tag, compare_tags, is_tag_for_channel, parse_tag,
+    parse_tag_for_channel,
 };
-pub use channel::{BetaVersion, Channel, SemVer, compare_tags, is_tag_for_channel};
 pub use controller_state::{CONTROLLER_STATE_SCHEMA, ControllerState, LastFlashed};
 pub use crypto::{decode_b64_32, sha256_hex, verify_manifest_sig_b64};
 pub use dist::{
-    DIST_REPO_SLUG, STABLE_PUBLIC_KEY_B64, asset_url_for_tag, manifest_sig_url_for_tag,
-    manifest_url_for_tag, public_key_b64_for_channel, stable_latest_manifest_url,
-    stable_latest_sig_url,
+    BUILTIN_SIGNING_KEYS, DIST_REPO_SLUG, DistributionSource, FILE_LOCATION_PREFIX, STABLE_KEY_ID,
+    STABLE_PUBLIC_KEY_B64, builtin_keyring, local_path_for_location,
 };
 pub use error::{CoreError, Result};
+pub use firmware_target::{
+    BITWIG_TARGET, DEFAULT_PROFILE_ID, FirmwareTarget, FirmwareTargetInfo, STANDALONE_TARGET,
+    firmware_targets,
+};
 pub use github::{
     ReleaseInfo, extract_tags_from_releases_atom, latest_tag_for_channel,
     latest_tag_for_channel_from_releases, parse_releases_api_json,
 };
-pub use install_state::{INSTALL_STATE_SCHEMA, InstallState};
+pub use host_app::{
+    BITWIG_EXTENSION_KIND, HOST_APPS, HostAppDescriptor, host_app_for_extension_kind,
+};
+pub use install_state::{
+    INSTALL_STATE_SCHEMA, InstallHistoryAction, InstallHistoryEntry, InstallState, rollback_target,
+};
+pub use keyring::{
+    Keyring, ManifestSignature, REVOCATION_LIST_SCHEMA, RevocationList, RevokedKey, TrustedKey,
+    asset_signature_message, parse_revocation_list_json, parse_signature_text,
+};
 pub use manifest::{
     MAX_SUPPORTED_MANIFEST_SCHEMA, MIN_SUPPORTED_MANIFEST_SCHEMA, Manifest, ManifestAsset,
-    ManifestChannel, ManifestTooling, parse_manifest_json, select_default_assets,
-    select_install_set_assets,
+    ManifestChannel, ManifestInstallSet, ManifestRepo, ManifestTooling, parse_manifest_json,
+    select_default_assets, select_install_set_assets,
+};
+pub use manifest_diff::{
+    AssetChange, ChangeKind, InstallSetChange, ManifestDiff, RepoChange, ToolingChange,
+    diff_manifests, install_set_update_size,
+};
+pub use manifest_validation::{
+    DiagnosticSeverity, KNOWN_ASSET_KINDS, ManifestDiagnostic, ManifestValidationReport,
 };
 pub use platform::{Arch, Os, Platform};
 pub use project_migration::{
     ProjectLoadReportItem, ProjectLoadStatus, ProjectMigrationError, ProjectMigrationReport,
     ProjectMigrationStatus, ProjectMigrationTool, parse_project_migration_report,
 };
-pub use settings::{ArtifactSource, SETTINGS_SCHEMA, Settings};
+pub use settings::{
+    ArtifactSource, DownloadSettings, MAX_DOWNLOAD_CONCURRENCY, MAX_NETWORK_TIMEOUT_SECS,
+    MAX_UPDATE_CHECK_INTERVAL_MINS, MIN_UPDATE_CHECK_INTERVAL_MINS, NetworkSettings, ProxySettings,
+    SETTINGS_SCHEMA, Settings, UpdateCheckSettings, default_app_update_source,
+};
 pub use step_preset::{
     StepPresetCompatibility, StepPresetError, StepPresetFlags, StepPresetReport,
     StepPresetScalePolicy, StepPresetSourceScale, StepPresetStatus, StepPresetTool,
     parse_step_preset_report,
 };
+pub use version_range::VersionRange;
 
 #[cfg(test)]
 mod tests {
@@ -73,6 +111,25 @@ mod tests {
         assert_eq!(got, "v0.0.2-beta.2");
     }
 
+    #[test]
+    fn latest_tag_for_rc_ignores_other_prerelease_channels() {
+        let tags = [
+            "v0.3.0-beta.4",
+            "v0.2.0-rc.2",
+            "v0.2.0-rc.10",
+            "v0.3.0-alpha.1",
+            "v0.2.0",
+        ]
+        .into_iter()
+        .map(|t| t.to_string())
+        .collect::<Vec<_>>();
+
+        let got = latest_tag_for_channel(Channel::Rc, &tags).unwrap();
+        assert_eq!(got, "v0.2.0-rc.10");
+        let got = latest_tag_for_channel(Channel::Stable, &tags).unwrap();
+        assert_eq!(got, "v0.2.0");
+    }
+
     #[test]
     fn atom_parser_extracts_tags() {
         let xml = r#"<?xml version=\"1.0\" encoding=\"UTF-8\"?>
@@ -172,7 +229,7 @@ mod tests {
     #[test]
     fn manifest_rejects_future_schema() {
         let json = r#"{
-  "schema": 4,
+  "schema": 5,
   "channel": "beta",
   "tag": "v0.1.0-beta.99",
   "published_at": "2026-07-15T00:00:00Z",
@@ -183,7 +240,49 @@ mod tests {
 
         assert!(matches!(
             parse_manifest_json(json.as_bytes()),
-            Err(CoreError::UnsupportedSchema(4))
+            Err(CoreError::UnsupportedSchema(5))
+        ));
+    }
+
+    #[test]
+    fn manifest_schema_4_reads_notes_and_gates_manager_version() {
+        let json = r#"{
+  "schema": 4,
+  "channel": "stable",
+  "tag": "v0.3.0",
+  "published_at": "2026-10-01T00:00:00Z",
+  "notes": "- Faster sync\n- Fewer glitches",
+  "min_manager_version": "0.4.0",
+  "repos": [],
+  "assets": [
+    {
+      "id": "firmware-default",
+      "kind": "firmware",
+      "filename": "midi-studio-default-firmware.hex",
+      "size": 1,
+      "sha256": "0000000000000000000000000000000000000000000000000000000000000000",
+      "description": "Standalone firmware"
+    }
+  ],
+  "install_sets": []
+}"#;
+
+        let m = parse_manifest_json(json.as_bytes()).unwrap();
+        assert_eq!(m.notes.as_deref(), Some("- Faster sync\n- Fewer glitches"));
+        assert_eq!(
+            m.assets[0].description.as_deref(),
+            Some("Standalone firmware")
+        );
+
+        assert!(m.check_manager_version("0.4.0").is_ok());
+        assert!(m.check_manager_version("0.5.0-beta.1").is_ok());
+        assert!(matches!(
+            m.check_manager_version("0.3.9"),
+            Err(CoreError::ManagerTooOld { .. })
+        ));
+        assert!(matches!(
+            m.check_manager_version("0.4.0-rc.1"),
+            Err(CoreError::ManagerTooOld { .. })
         ));
     }
 
@@ -248,4 +347,57 @@ mod tests {
         assert_eq!(assets[0].id, "bundle-linux-x86_64");
         assert_eq!(assets[1].id, "firmware-bitwig");
     }
+
+    #[test]
+    fn distribution_source_builds_mirror_and_directory_locations() {
+        let github = DistributionSource::default();
+        assert_eq!(
+            github.asset_url_for_tag("v0.1.0", "a.zip"),
+            format!("https://github.com/{DIST_REPO_SLUG}/releases/download/v0.1.0/a.zip")
+        );
+        assert!(github.latest_stable_manifest_urls().is_some());
+
+        let mirror = DistributionSource::Https {
+            base_url: "https://mirror.example.org/ms/".to_string(),
+        };
+        assert_eq!(
+            mirror.manifest_sig_url_for_tag("v0.1.0"),
+            "https://mirror.example.org/ms/v0.1.0/manifest.json.sig"
+        );
+        assert_eq!(
+            mirror.releases_index_url(),
+            "https://mirror.example.org/ms/releases.json"
+        );
+        assert!(mirror.latest_stable_manifest_urls().is_none());
+
+        let dir = DistributionSource::Directory {
+            path: "/srv/ms-dist".to_string(),
+        };
+        let location = dir.manifest_url_for_tag("v0.1.0");
+        assert_eq!(
+            local_path_for_location(&location),
+            Some("/srv/ms-dist/v0.1.0/manifest.json")
+        );
+    }
+
+    #[test]
+    fn distribution_source_validate_rejects_bad_values() {
+        assert!(DistributionSource::default().validate().is_ok());
+        for source in [
+            DistributionSource::Github {
+                repo: "no-slash".to_string(),
+            },
+            DistributionSource::Https {
+                base_url: "http://mirror.example.org".to_string(),
+            },
+            DistributionSource::Directory {
+                path: "relative/dir".to_string(),
+            },
+        ] {
+            assert!(matches!(
+                source.validate(),
+                Err(CoreError::InvalidDistributionSource(_))
+            ));
+        }
+    }
 }
@@ -1,15 +1,44 @@
 use serde::{Deserialize, Serialize};
 
+use crate::Channel;
+use crate::channel::SemVer;
 use crate::error::{CoreError, Result};
 
 pub const MIN_SUPPORTED_MANIFEST_SCHEMA: u32 = 2;
-pub const MAX_SUPPORTED_MANIFEST_SCHEMA: u32 = 3;
+pub const MAX_SUPPORTED_MANIFEST_SCHEMA: u32 = 4;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ManifestChannel {
     Stable,
+    Rc,
     Beta,
+    Alpha,
+    Nightly,
+}
+
+impl From<Channel> for ManifestChannel {
+    fn from(channel: Channel) -> Self {
+        match channel {
+            Channel::Stable => Self::Stable,
+            Channel::Rc => Self::Rc,
+            Channel::Beta => Self::Beta,
+            Channel::Alpha => Self::Alpha,
+            Channel::Nightly => Self::Nightly,
+        }
+    }
+}
+
+impl From<ManifestChannel> for Channel {
+    fn from(channel: ManifestChannel) -> Self {
+        match channel {
+            ManifestChannel::Stable => Self::Stable,
+            ManifestChannel::Rc => Self::Rc,
+            ManifestChannel::Beta => Self::Beta,
+            ManifestChannel::Alpha => Self::Alpha,
+            ManifestChannel::Nightly => Self::Nightly,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -25,6 +54,12 @@ pub struct Manifest {
     pub install_sets: Vec<ManifestInstallSet>,
     #[serde(default)]
     pub pages: Option<ManifestPages>,
+    /// Release notes (markdown). Schema 4+.
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Oldest ms-manager version able to install this release (SemVer, no `v`). Schema 4+.
+    #[serde(default)]
+    pub min_manager_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -34,7 +69,7 @@ pub struct ManifestRepo {
     pub sha: String,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ManifestTooling {
     pub repo: String,
     #[serde(rename = "ref")]
@@ -42,7 +77,7 @@ pub struct ManifestTooling {
     pub sha: String,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ManifestAsset {
     pub id: String,
     pub kind: String,
@@ -55,6 +90,13 @@ pub struct ManifestAsset {
     pub sha256: String,
     #[serde(default)]
     pub url: Option<String>,
+    /// Short human-readable description. Schema 4+.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Optional detached signature (`<key_id>:<base64>`) over
+    /// [`asset_signature_message`](crate::asset_signature_message).
+    #[serde(default)]
+    pub signature: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -65,6 +107,11 @@ pub struct ManifestInstallSet {
     #[serde(default)]
     pub arch: Option<String>,
     pub assets: Vec<String>,
+    /// Name shown for the firmware target this install set provides.
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -73,6 +120,29 @@ pub struct ManifestPages {
     pub demo_url: Option<String>,
 }
 
+impl Manifest {
+    /// Refuse releases that need a newer ms-manager than `manager_version`.
+    ///
+    /// An unparsable `min_manager_version` is treated as unsatisfiable rather than ignored.
+    pub fn check_manager_version(&self, manager_version: &str) -> Result<()> {
+        let Some(required) = self.min_manager_version.as_deref() else {
+            return Ok(());
+        };
+        let satisfied = match (SemVer::parse(required), SemVer::parse(manager_version)) {
+            (Some(required), Some(current)) => current.cmp_precedence(&required).is_ge(),
+            _ => false,
+        };
+        if satisfied {
+            return Ok(());
+        }
+        Err(CoreError::ManagerTooOld {
+            tag: self.tag.clone(),
+            required: required.to_string(),
+            current: manager_version.to_string(),
+        })
+    }
+}
+
 pub fn parse_manifest_json(bytes: &[u8]) -> Result<Manifest> {
     let m: Manifest = serde_json::from_slice(bytes)?;
     if !(MIN_SUPPORTED_MANIFEST_SCHEMA..=MAX_SUPPORTED_MANIFEST_SCHEMA).contains(&m.schema) {
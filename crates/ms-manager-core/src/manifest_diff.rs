@@ -0,0 +1,317 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::manifest::{Manifest, ManifestAsset, ManifestTooling, select_install_set_assets};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AssetChange {
+    pub id: String,
+    pub from_sha256: String,
+    pub to_sha256: String,
+    pub from_size: u64,
+    pub to_size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A `repos` entry whose commit moved; `None` on the side where the repo is absent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RepoChange {
+    pub id: String,
+    pub kind: ChangeKind,
+    pub from_sha: Option<String>,
+    pub to_sha: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ToolingChange {
+    pub from: Option<ManifestTooling>,
+    pub to: Option<ManifestTooling>,
+}
+
+/// Membership change of one install set, keyed by `(id, os, arch)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstallSetChange {
+    pub id: String,
+    pub os: Option<String>,
+    pub arch: Option<String>,
+    pub kind: ChangeKind,
+    pub added_assets: Vec<String>,
+    pub removed_assets: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ManifestDiff {
+    pub from_tag: String,
+    pub to_tag: String,
+    pub assets_added: Vec<ManifestAsset>,
+    pub assets_removed: Vec<ManifestAsset>,
+    pub assets_changed: Vec<AssetChange>,
+    pub repos: Vec<RepoChange>,
+    pub tooling: Option<ToolingChange>,
+    pub install_sets: Vec<InstallSetChange>,
+}
+
+impl ManifestDiff {
+    pub fn is_empty(&self) -> bool {
+        self.assets_added.is_empty()
+            && self.assets_removed.is_empty()
+            && self.assets_changed.is_empty()
+            && self.repos.is_empty()
+            && self.tooling.is_none()
+            && self.install_sets.is_empty()
+    }
+}
+
+/// Compare two manifests. Assets are matched by id and count as changed when their sha256 or
+/// size differ; entries keep the order of the manifest they come from.
+pub fn diff_manifests(from: &Manifest, to: &Manifest) -> ManifestDiff {
+    let from_assets = first_by_key(&from.assets, |a| a.id.as_str());
+    let to_assets = first_by_key(&to.assets, |a| a.id.as_str());
+
+    let assets_added = unique(&to.assets, |a| a.id.as_str())
+        .filter(|a| !from_assets.contains_key(a.id.as_str()))
+        .cloned()
+        .collect();
+    let assets_removed = unique(&from.assets, |a| a.id.as_str())
+        .filter(|a| !to_assets.contains_key(a.id.as_str()))
+        .cloned()
+        .collect();
+    let assets_changed = unique(&to.assets, |a| a.id.as_str())
+        .filter_map(|next| {
+            let prev = from_assets.get(next.id.as_str())?;
+            (prev.sha256 != next.sha256 || prev.size != next.size).then(|| AssetChange {
+                id: next.id.clone(),
+                from_sha256: prev.sha256.clone(),
+                to_sha256: next.sha256.clone(),
+                from_size: prev.size,
+                to_size: next.size,
+            })
+        })
+        .collect();
+
+    let from_repos = first_by_key(&from.repos, |r| r.id.as_str());
+    let to_repos = first_by_key(&to.repos, |r| r.id.as_str());
+    let mut repos = unique(&from.repos, |r| r.id.as_str())
+        .filter_map(|prev| match to_repos.get(prev.id.as_str()) {
+            None => Some(RepoChange {
+                id: prev.id.clone(),
+                kind: ChangeKind::Removed,
+                from_sha: Some(prev.sha.clone()),
+                to_sha: None,
+            }),
+            Some(next) if next.sha != prev.sha => Some(RepoChange {
+                id: prev.id.clone(),
+                kind: ChangeKind::Changed,
+                from_sha: Some(prev.sha.clone()),
+                to_sha: Some(next.sha.clone()),
+            }),
+            Some(_) => None,
+        })
+        .collect::<Vec<_>>();
+    repos.extend(
+        unique(&to.repos, |r| r.id.as_str())
+            .filter(|next| !from_repos.contains_key(next.id.as_str()))
+            .map(|next| RepoChange {
+                id: next.id.clone(),
+                kind: ChangeKind::Added,
+                from_sha: None,
+                to_sha: Some(next.sha.clone()),
+            }),
+    );
+
+    let tooling = (from.tooling != to.tooling).then(|| ToolingChange {
+        from: from.tooling.clone(),
+        to: to.tooling.clone(),
+    });
+
+    ManifestDiff {
+        from_tag: from.tag.clone(),
+        to_tag: to.tag.clone(),
+        assets_added,
+        assets_removed,
+        assets_changed,
+        repos,
+        tooling,
+        install_sets: diff_install_sets(from, to),
+    }
+}
+
+/// Bytes to download when moving an install set from `from` to `to`.
+///
+/// The asset cache is content-addressed, so only assets whose sha256 is not already part of the
+/// same install set in `from` count. When `from` has no such install set, everything counts.
+pub fn install_set_update_size(
+    from: &Manifest,
+    to: &Manifest,
+    install_set_id: &str,
+    os: &str,
+    arch: &str,
+) -> Result<u64> {
+    let next = select_install_set_assets(to, install_set_id, os, arch)?;
+    let known = select_install_set_assets(from, install_set_id, os, arch)
+        .map(|assets| {
+            assets
+                .into_iter()
+                .map(|a| a.sha256)
+                .collect::<BTreeSet<_>>()
+        })
+        .unwrap_or_default();
+
+    Ok(next
+        .iter()
+        .filter(|a| !known.contains(&a.sha256))
+        .map(|a| a.size)
+        .sum())
+}
+
+type InstallSetKey = (String, Option<String>, Option<String>);
+
+fn install_set_members(manifest: &Manifest) -> BTreeMap<InstallSetKey, BTreeSet<String>> {
+    let mut out: BTreeMap<_, BTreeSet<String>> = BTreeMap::new();
+    for set in &manifest.install_sets {
+        out.entry((set.id.clone(), set.os.clone(), set.arch.clone()))
+            .or_default()
+            .extend(set.assets.iter().cloned());
+    }
+    out
+}
+
+fn diff_install_sets(from: &Manifest, to: &Manifest) -> Vec<InstallSetChange> {
+    let from_sets = install_set_members(from);
+    let to_sets = install_set_members(to);
+    let empty = BTreeSet::new();
+
+    from_sets
+        .keys()
+        .chain(to_sets.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter_map(|key| {
+            let (kind, prev, next) = match (from_sets.get(key), to_sets.get(key)) {
+                (None, Some(next)) => (ChangeKind::Added, &empty, next),
+                (Some(prev), None) => (ChangeKind::Removed, prev, &empty),
+                (Some(prev), Some(next)) if prev != next => (ChangeKind::Changed, prev, next),
+                _ => return None,
+            };
+            let (id, os, arch) = key.clone();
+            Some(InstallSetChange {
+                id,
+                os,
+                arch,
+                kind,
+                added_assets: next.difference(prev).cloned().collect(),
+                removed_assets: prev.difference(next).cloned().collect(),
+            })
+        })
+        .collect()
+}
+
+fn first_by_key<T>(items: &[T], key: impl Fn(&T) -> &str) -> BTreeMap<&str, &T> {
+    let mut out = BTreeMap::new();
+    for item in items {
+        out.entry(key(item)).or_insert(item);
+    }
+    out
+}
+
+/// Items in order, skipping later duplicates of the same key.
+fn unique<'a, T>(
+    items: &'a [T],
+    key: impl Fn(&T) -> &str + 'a,
+) -> impl Iterator<Item = &'a T> + 'a {
+    let mut seen = BTreeSet::new();
+    items
+        .iter()
+        .filter(move |item| seen.insert(key(item).to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_manifest_json;
+
+    const SHA_A: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    const SHA_B: &str = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+    fn manifest(tag: &str, firmware_sha: &str, extra: bool, loader_sha: &str) -> Manifest {
+        let mut assets = vec![
+            serde_json::json!({"id": "bundle-linux", "kind": "bundle", "os": "linux", "arch": "x86_64", "filename": "bundle.zip", "size": 100, "sha256": SHA_A}),
+            serde_json::json!({"id": "firmware-default", "kind": "firmware", "filename": "default.hex", "size": 10, "sha256": firmware_sha}),
+        ];
+        let mut set = vec!["bundle-linux", "firmware-default"];
+        if extra {
+            assets.push(serde_json::json!({"id": "bitwig-ext", "kind": "bitwig-extension", "filename": "ms.bwextension", "size": 5, "sha256": SHA_B}));
+            set.push("bitwig-ext");
+        }
+        let json = serde_json::json!({
+            "schema": 3,
+            "channel": "stable",
+            "tag": tag,
+            "published_at": "2026-02-02T05:14:21Z",
+            "repos": [{"id": "loader", "url": "https://example.invalid", "sha": loader_sha}],
+            "assets": assets,
+            "install_sets": [{"id": "default", "os": "linux", "arch": "x86_64", "assets": set}],
+        });
+        parse_manifest_json(json.to_string().as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn identical_manifests_have_empty_diff() {
+        let a = manifest(
+            "v1.0.0",
+            SHA_A,
+            false,
+            "1111111111111111111111111111111111111111",
+        );
+        assert!(diff_manifests(&a, &a).is_empty());
+    }
+
+    #[test]
+    fn diff_reports_assets_repos_and_install_sets() {
+        let a = manifest(
+            "v1.0.0",
+            SHA_A,
+            false,
+            "1111111111111111111111111111111111111111",
+        );
+        let b = manifest(
+            "v1.1.0",
+            SHA_B,
+            true,
+            "2222222222222222222222222222222222222222",
+        );
+        let diff = diff_manifests(&a, &b);
+
+        assert_eq!(diff.from_tag, "v1.0.0");
+        assert_eq!(diff.to_tag, "v1.1.0");
+        assert_eq!(diff.assets_added.len(), 1);
+        assert_eq!(diff.assets_added[0].id, "bitwig-ext");
+        assert!(diff.assets_removed.is_empty());
+        assert_eq!(diff.assets_changed.len(), 1);
+        assert_eq!(diff.assets_changed[0].id, "firmware-default");
+        assert_eq!(diff.repos.len(), 1);
+        assert_eq!(diff.repos[0].kind, ChangeKind::Changed);
+        assert!(diff.tooling.is_none());
+        assert_eq!(diff.install_sets.len(), 1);
+        assert_eq!(diff.install_sets[0].kind, ChangeKind::Changed);
+        assert_eq!(
+            diff.install_sets[0].added_assets,
+            vec!["bitwig-ext".to_string()]
+        );
+
+        // Bundle is unchanged; new firmware (10) + new extension (5).
+        assert_eq!(
+            install_set_update_size(&a, &b, "default", "linux", "x86_64").unwrap(),
+            15
+        );
+    }
+}
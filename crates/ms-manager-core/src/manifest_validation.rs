@@ -0,0 +1,524 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::channel::{SemVer, channel_for_tag};
+use crate::keyring::parse_signature_text;
+use crate::manifest::{
+    MAX_SUPPORTED_MANIFEST_SCHEMA, MIN_SUPPORTED_MANIFEST_SCHEMA, Manifest, ManifestAsset,
+};
+use crate::platform::{Arch, Os};
+use crate::{BITWIG_EXTENSION_KIND, Channel, ManifestChannel};
+
+/// Asset kinds the manager knows how to install.
+///
+/// Other kinds still install under `assets/<kind>/`, so they are reported as warnings only.
+pub const KNOWN_ASSET_KINDS: &[&str] = &["bundle", "firmware", BITWIG_EXTENSION_KIND];
+
+const KNOWN_OS: [Os; 3] = [Os::Windows, Os::Macos, Os::Linux];
+const KNOWN_ARCH: [Arch; 2] = [Arch::X86_64, Arch::Arm64];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ManifestDiagnostic {
+    pub severity: DiagnosticSeverity,
+    /// Stable machine-readable code, e.g. `asset_sha256_invalid`.
+    pub code: String,
+    /// JSON path of the offending value, e.g. `$.assets[2].sha256`.
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ManifestValidationReport {
+    pub diagnostics: Vec<ManifestDiagnostic>,
+}
+
+impl ManifestValidationReport {
+    /// `true` when there are no error-level diagnostics (warnings are allowed).
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ManifestDiagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == DiagnosticSeverity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ManifestDiagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == DiagnosticSeverity::Warning)
+    }
+
+    fn error(&mut self, code: &str, path: String, message: impl Into<String>) {
+        self.push(DiagnosticSeverity::Error, code, path, message.into());
+    }
+
+    fn warning(&mut self, code: &str, path: String, message: impl Into<String>) {
+        self.push(DiagnosticSeverity::Warning, code, path, message.into());
+    }
+
+    fn push(&mut self, severity: DiagnosticSeverity, code: &str, path: String, message: String) {
+        self.diagnostics.push(ManifestDiagnostic {
+            severity,
+            code: code.to_string(),
+            path,
+            message,
+        });
+    }
+}
+
+impl Manifest {
+    /// Check the whole manifest and report every structural problem at once.
+    ///
+    /// `parse_manifest_json` only enforces the schema range; this catches what would otherwise
+    /// fail later during install (dangling install set references, bad digests, ...).
+    pub fn validate(&self) -> ManifestValidationReport {
+        let mut report = ManifestValidationReport::default();
+
+        if !(MIN_SUPPORTED_MANIFEST_SCHEMA..=MAX_SUPPORTED_MANIFEST_SCHEMA).contains(&self.schema) {
+            report.error(
+                "schema_unsupported",
+                "$.schema".to_string(),
+                format!("unsupported manifest schema {}", self.schema),
+            );
+        }
+
+        match channel_for_tag(&self.tag) {
+            None => report.error(
+                "tag_invalid",
+                "$.tag".to_string(),
+                format!("tag {:?} is not a release tag (v + SemVer)", self.tag),
+            ),
+            Some(channel) if ManifestChannel::from(channel) != self.channel => report.error(
+                "tag_channel_mismatch",
+                "$.tag".to_string(),
+                format!(
+                    "tag {} belongs to channel {}, manifest says {}",
+                    self.tag,
+                    channel.as_str(),
+                    Channel::from(self.channel).as_str()
+                ),
+            ),
+            Some(_) => {}
+        }
+
+        if let Some(version) = &self.min_manager_version
+            && SemVer::parse(version).is_none()
+        {
+            report.error(
+                "min_manager_version_invalid",
+                "$.min_manager_version".to_string(),
+                format!("min_manager_version {version:?} is not a SemVer version"),
+            );
+        }
+        if self.schema < 4 {
+            let schema4_fields = [
+                ("$.notes", self.notes.is_some()),
+                ("$.min_manager_version", self.min_manager_version.is_some()),
+            ];
+            for (path, present) in schema4_fields {
+                if present {
+                    report.warning(
+                        "field_requires_schema_4",
+                        path.to_string(),
+                        format!("{path} needs schema 4; older managers ignore it"),
+                    );
+                }
+            }
+        }
+
+        if self.published_at.trim().is_empty() {
+            report.warning(
+                "published_at_missing",
+                "$.published_at".to_string(),
+                "published_at is empty",
+            );
+        }
+
+        self.validate_repos(&mut report);
+        self.validate_assets(&mut report);
+        self.validate_install_sets(&mut report);
+
+        report
+    }
+
+    fn validate_repos(&self, report: &mut ManifestValidationReport) {
+        let mut seen = BTreeSet::new();
+        for (i, repo) in self.repos.iter().enumerate() {
+            if !seen.insert(repo.id.as_str()) {
+                report.error(
+                    "repo_id_duplicate",
+                    format!("$.repos[{i}].id"),
+                    format!("duplicate repo id {:?}", repo.id),
+                );
+            }
+            if !is_lower_hex(&repo.sha, 40) {
+                report.error(
+                    "repo_sha_invalid",
+                    format!("$.repos[{i}].sha"),
+                    format!(
+                        "expected a 40-char lowercase hex commit sha, got {:?}",
+                        repo.sha
+                    ),
+                );
+            }
+            if repo.url.trim().is_empty() {
+                report.warning(
+                    "repo_url_missing",
+                    format!("$.repos[{i}].url"),
+                    "repo url is empty",
+                );
+            }
+        }
+
+        if let Some(tooling) = &self.tooling
+            && !is_lower_hex(&tooling.sha, 40)
+        {
+            report.error(
+                "tooling_sha_invalid",
+                "$.tooling.sha".to_string(),
+                format!(
+                    "expected a 40-char lowercase hex commit sha, got {:?}",
+                    tooling.sha
+                ),
+            );
+        }
+    }
+
+    fn validate_assets(&self, report: &mut ManifestValidationReport) {
+        let mut seen = BTreeSet::new();
+        for (i, asset) in self.assets.iter().enumerate() {
+            if asset.id.trim().is_empty() {
+                report.error(
+                    "asset_id_missing",
+                    format!("$.assets[{i}].id"),
+                    "asset id is empty",
+                );
+            } else if !seen.insert(asset.id.as_str()) {
+                report.error(
+                    "asset_id_duplicate",
+                    format!("$.assets[{i}].id"),
+                    format!("duplicate asset id {:?}", asset.id),
+                );
+            }
+
+            if !KNOWN_ASSET_KINDS.contains(&asset.kind.as_str()) {
+                report.warning(
+                    "asset_kind_unknown",
+                    format!("$.assets[{i}].kind"),
+                    format!("unknown asset kind {:?}", asset.kind),
+                );
+            }
+
+            if !is_plain_filename(&asset.filename) {
+                report.error(
+                    "asset_filename_invalid",
+                    format!("$.assets[{i}].filename"),
+                    format!(
+                        "filename {:?} must be a plain file name without path separators",
+                        asset.filename
+                    ),
+                );
+            }
+
+            if asset.size == 0 {
+                report.error(
+                    "asset_size_zero",
+                    format!("$.assets[{i}].size"),
+                    "asset size is 0",
+                );
+            }
+
+            if !is_lower_hex(&asset.sha256, 64) {
+                report.error(
+                    "asset_sha256_invalid",
+                    format!("$.assets[{i}].sha256"),
+                    format!(
+                        "expected a 64-char lowercase hex sha256, got {:?}",
+                        asset.sha256
+                    ),
+                );
+            }
+
+            if let Some(signature) = &asset.signature {
+                match parse_signature_text(signature) {
+                    Err(_) => report.error(
+                        "asset_signature_invalid",
+                        format!("$.assets[{i}].signature"),
+                        "asset signature is empty or malformed",
+                    ),
+                    Ok(sig) if sig.key_id.is_none() => report.warning(
+                        "asset_signature_unnamed",
+                        format!("$.assets[{i}].signature"),
+                        "asset signature does not name its key id",
+                    ),
+                    Ok(_) => {}
+                }
+            }
+
+            if let Some(url) = &asset.url
+                && !url.starts_with("https://")
+            {
+                report.error(
+                    "asset_url_invalid",
+                    format!("$.assets[{i}].url"),
+                    format!("asset url must be https, got {url:?}"),
+                );
+            }
+
+            validate_platform(
+                report,
+                &format!("$.assets[{i}]"),
+                asset.os.as_deref(),
+                asset.arch.as_deref(),
+            );
+        }
+    }
+
+    fn validate_install_sets(&self, report: &mut ManifestValidationReport) {
+        let mut assets = BTreeMap::new();
+        for asset in &self.assets {
+            assets.entry(asset.id.as_str()).or_insert(asset);
+        }
+        let mut seen = BTreeSet::new();
+
+        for (i, set) in self.install_sets.iter().enumerate() {
+            let path = format!("$.install_sets[{i}]");
+            if !seen.insert((set.id.as_str(), set.os.as_deref(), set.arch.as_deref())) {
+                report.error(
+                    "install_set_duplicate",
+                    format!("{path}.id"),
+                    format!(
+                        "duplicate install set {:?} for {}/{}",
+                        set.id,
+                        set.os.as_deref().unwrap_or("-"),
+                        set.arch.as_deref().unwrap_or("-")
+                    ),
+                );
+            }
+
+            if set.os.is_none() || set.arch.is_none() {
+                report.warning(
+                    "install_set_platform_missing",
+                    path.clone(),
+                    format!(
+                        "install set {:?} has no os/arch and can never be selected",
+                        set.id
+                    ),
+                );
+            }
+            validate_platform(report, &path, set.os.as_deref(), set.arch.as_deref());
+
+            if set.assets.is_empty() {
+                report.error(
+                    "install_set_empty",
+                    format!("{path}.assets"),
+                    format!("install set {:?} has no assets", set.id),
+                );
+                continue;
+            }
+
+            let mut members = BTreeSet::new();
+            let mut bundles = 0usize;
+            for (j, asset_id) in set.assets.iter().enumerate() {
+                let asset_path = format!("{path}.assets[{j}]");
+                if !members.insert(asset_id.as_str()) {
+                    report.warning(
+                        "install_set_asset_duplicate",
+                        asset_path.clone(),
+                        format!("asset {asset_id:?} is listed twice"),
+                    );
+                    continue;
+                }
+                let Some(asset) = assets.get(asset_id.as_str()) else {
+                    report.error(
+                        "install_set_unknown_asset",
+                        asset_path,
+                        format!("unknown asset id {asset_id:?}"),
+                    );
+                    continue;
+                };
+                if asset.kind == "bundle" {
+                    bundles += 1;
+                }
+                if !platform_matches(asset, set.os.as_deref(), set.arch.as_deref()) {
+                    report.error(
+                        "install_set_platform_mismatch",
+                        asset_path,
+                        format!(
+                            "asset {asset_id:?} targets {}/{}, install set targets {}/{}",
+                            asset.os.as_deref().unwrap_or("-"),
+                            asset.arch.as_deref().unwrap_or("-"),
+                            set.os.as_deref().unwrap_or("-"),
+                            set.arch.as_deref().unwrap_or("-")
+                        ),
+                    );
+                }
+            }
+
+            if bundles != 1 {
+                report.error(
+                    "install_set_bundle_count",
+                    format!("{path}.assets"),
+                    format!(
+                        "install set {:?} must contain exactly one bundle asset, found {bundles}",
+                        set.id
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn validate_platform(
+    report: &mut ManifestValidationReport,
+    path: &str,
+    os: Option<&str>,
+    arch: Option<&str>,
+) {
+    if let Some(os) = os
+        && !KNOWN_OS.iter().any(|known| known.as_str() == os)
+    {
+        report.warning(
+            "os_unknown",
+            format!("{path}.os"),
+            format!("unknown os {os:?}"),
+        );
+    }
+    if let Some(arch) = arch
+        && !KNOWN_ARCH.iter().any(|known| known.as_str() == arch)
+    {
+        report.warning(
+            "arch_unknown",
+            format!("{path}.arch"),
+            format!("unknown arch {arch:?}"),
+        );
+    }
+}
+
+/// Platform-neutral assets (no os/arch) fit any install set.
+fn platform_matches(asset: &ManifestAsset, os: Option<&str>, arch: Option<&str>) -> bool {
+    asset.os.as_deref().is_none_or(|v| Some(v) == os)
+        && asset.arch.as_deref().is_none_or(|v| Some(v) == arch)
+}
+
+fn is_lower_hex(value: &str, len: usize) -> bool {
+    value.len() == len
+        && value
+            .bytes()
+            .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+fn is_plain_filename(value: &str) -> bool {
+    !value.is_empty()
+        && value != "."
+        && value != ".."
+        && !value.contains(['/', '\\'])
+        && !value.contains('\0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_manifest_json;
+
+    const SHA: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+    fn manifest(assets: serde_json::Value, install_sets: serde_json::Value) -> Manifest {
+        let json = serde_json::json!({
+            "schema": 3,
+            "channel": "beta",
+            "tag": "v0.1.0-beta.1",
+            "published_at": "2026-02-02T05:14:21Z",
+            "repos": [{"id": "loader", "url": "https://example.invalid", "sha": "0000000000000000000000000000000000000000"}],
+            "assets": assets,
+            "install_sets": install_sets,
+        });
+        parse_manifest_json(json.to_string().as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn valid_manifest_has_no_diagnostics() {
+        let m = manifest(
+            serde_json::json!([
+                {"id": "bundle-linux", "kind": "bundle", "os": "linux", "arch": "x86_64", "filename": "bundle.zip", "size": 1, "sha256": SHA},
+                {"id": "firmware-default", "kind": "firmware", "filename": "default.hex", "size": 1, "sha256": SHA}
+            ]),
+            serde_json::json!([
+                {"id": "default", "os": "linux", "arch": "x86_64", "assets": ["bundle-linux", "firmware-default"]}
+            ]),
+        );
+        let report = m.validate();
+        assert!(report.diagnostics.is_empty(), "{report:?}");
+        assert!(report.is_valid());
+    }
+
+    #[test]
+    fn reports_every_problem_with_its_path() {
+        let m = manifest(
+            serde_json::json!([
+                {"id": "bundle-linux", "kind": "bundle", "os": "linux", "arch": "x86_64", "filename": "bundle.zip", "size": 0, "sha256": "ABC"},
+                {"id": "bundle-linux", "kind": "plugin", "filename": "../x", "size": 1, "sha256": SHA}
+            ]),
+            serde_json::json!([
+                {"id": "default", "os": "linux", "arch": "x86_64", "assets": ["bundle-linux", "missing"]}
+            ]),
+        );
+        let report = m.validate();
+        let found = report
+            .diagnostics
+            .iter()
+            .map(|d| (d.code.as_str(), d.path.as_str()))
+            .collect::<Vec<_>>();
+
+        for expected in [
+            ("asset_size_zero", "$.assets[0].size"),
+            ("asset_sha256_invalid", "$.assets[0].sha256"),
+            ("asset_id_duplicate", "$.assets[1].id"),
+            ("asset_kind_unknown", "$.assets[1].kind"),
+            ("asset_filename_invalid", "$.assets[1].filename"),
+            ("install_set_unknown_asset", "$.install_sets[0].assets[1]"),
+        ] {
+            assert!(
+                found.contains(&expected),
+                "missing {expected:?} in {found:?}"
+            );
+        }
+        assert!(!report.is_valid());
+        assert_eq!(report.warnings().count(), 1);
+    }
+
+    #[test]
+    fn install_set_requires_matching_platform_and_one_bundle() {
+        let m = manifest(
+            serde_json::json!([
+                {"id": "bundle-windows", "kind": "bundle", "os": "windows", "arch": "x86_64", "filename": "bundle.zip", "size": 1, "sha256": SHA}
+            ]),
+            serde_json::json!([
+                {"id": "default", "os": "linux", "arch": "x86_64", "assets": ["bundle-windows"]},
+                {"id": "bitwig", "os": "windows", "arch": "x86_64", "assets": []}
+            ]),
+        );
+        let codes = m
+            .validate()
+            .errors()
+            .map(|d| d.code.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            vec![
+                "install_set_platform_mismatch".to_string(),
+                "install_set_empty".to_string()
+            ]
+        );
+    }
+}
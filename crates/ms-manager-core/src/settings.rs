@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::{CoreError, DistributionSource, MANAGER_REPO_SLUG, Result};
+
 pub const SETTINGS_SCHEMA: u32 = 1;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
@@ -16,6 +18,228 @@ pub struct Settings {
     pub payload_root_override: Option<String>,
     #[serde(default)]
     pub tab_order: Vec<String>,
+    #[serde(default)]
+    pub distribution_source: DistributionSource,
+    #[serde(default)]
+    pub downloads: DownloadSettings,
+    #[serde(default)]
+    pub network: NetworkSettings,
+    #[serde(default)]
+    pub update_check: UpdateCheckSettings,
+    /// Where ms-manager looks for its own releases.
+    #[serde(default = "default_app_update_source")]
+    pub app_update_source: DistributionSource,
+}
+
+pub fn default_app_update_source() -> DistributionSource {
+    DistributionSource::Github {
+        repo: MANAGER_REPO_SLUG.to_string(),
+    }
+}
+
+/// How release assets are fetched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DownloadSettings {
+    /// Assets downloaded at the same time.
+    #[serde(default = "default_download_concurrency")]
+    pub concurrency: u32,
+    /// Cap on the combined download rate, in KiB/s; `None` is unlimited.
+    #[serde(default)]
+    pub bandwidth_limit_kibps: Option<u32>,
+}
+
+pub const MAX_DOWNLOAD_CONCURRENCY: u32 = 8;
+
+fn default_download_concurrency() -> u32 {
+    3
+}
+
+impl Default for DownloadSettings {
+    fn default() -> Self {
+        Self {
+            concurrency: default_download_concurrency(),
+            bandwidth_limit_kibps: None,
+        }
+    }
+}
+
+impl DownloadSettings {
+    /// Clamp out-of-range values instead of rejecting them; a zero cap means unlimited.
+    pub fn normalized(self) -> Self {
+        Self {
+            concurrency: self.concurrency.clamp(1, MAX_DOWNLOAD_CONCURRENCY),
+            bandwidth_limit_kibps: self.bandwidth_limit_kibps.filter(|kibps| *kibps > 0),
+        }
+    }
+
+    pub fn bandwidth_limit_bytes_per_sec(&self) -> Option<u64> {
+        self.bandwidth_limit_kibps
+            .map(|kibps| u64::from(kibps) * 1024)
+    }
+}
+
+/// Background check for newer releases on the channels bridge instances follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct UpdateCheckSettings {
+    #[serde(default = "default_update_check_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_update_check_interval_mins")]
+    pub interval_mins: u32,
+}
+
+pub const MIN_UPDATE_CHECK_INTERVAL_MINS: u32 = 15;
+pub const MAX_UPDATE_CHECK_INTERVAL_MINS: u32 = 7 * 24 * 60;
+
+fn default_update_check_enabled() -> bool {
+    true
+}
+
+fn default_update_check_interval_mins() -> u32 {
+    6 * 60
+}
+
+impl Default for UpdateCheckSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_update_check_enabled(),
+            interval_mins: default_update_check_interval_mins(),
+        }
+    }
+}
+
+impl UpdateCheckSettings {
+    /// Clamp the interval to [`MIN_UPDATE_CHECK_INTERVAL_MINS`]..=[`MAX_UPDATE_CHECK_INTERVAL_MINS`].
+    pub fn normalized(self) -> Self {
+        Self {
+            enabled: self.enabled,
+            interval_mins: self.interval_mins.clamp(
+                MIN_UPDATE_CHECK_INTERVAL_MINS,
+                MAX_UPDATE_CHECK_INTERVAL_MINS,
+            ),
+        }
+    }
+}
+
+/// How the shared HTTP client reaches release sources.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct NetworkSettings {
+    /// Proxy for every request; `None` follows the `HTTPS_PROXY`/`HTTP_PROXY` environment.
+    #[serde(default)]
+    pub proxy: Option<ProxySettings>,
+    /// PEM files with extra root certificates to trust, e.g. a TLS-inspecting proxy's CA.
+    #[serde(default)]
+    pub extra_ca_certificates: Vec<String>,
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u32,
+    /// Longest wait for the next chunk of a response, not for the whole transfer.
+    #[serde(default = "default_read_timeout_secs")]
+    pub read_timeout_secs: u32,
+    /// Never touch the network: releases come from imported bundles, assets from the cache.
+    #[serde(default)]
+    pub offline: bool,
+    /// Personal access token sent to the GitHub API only, for its higher rate limit.
+    #[serde(default)]
+    pub github_token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ProxySettings {
+    /// `http://` or `https://` proxy URL.
+    pub url: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Stored in plain text in `settings.json`, like the rest of the settings.
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+pub const MAX_NETWORK_TIMEOUT_SECS: u32 = 600;
+
+fn default_connect_timeout_secs() -> u32 {
+    15
+}
+
+fn default_read_timeout_secs() -> u32 {
+    60
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            extra_ca_certificates: Vec::new(),
+            connect_timeout_secs: default_connect_timeout_secs(),
+            read_timeout_secs: default_read_timeout_secs(),
+            offline: false,
+            github_token: None,
+        }
+    }
+}
+
+impl NetworkSettings {
+    /// Trim values, drop empty ones and clamp timeouts to 1..=[`MAX_NETWORK_TIMEOUT_SECS`].
+    pub fn normalized(self) -> Self {
+        let non_empty = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        Self {
+            proxy: self
+                .proxy
+                .map(|proxy| ProxySettings {
+                    url: proxy.url.trim().to_string(),
+                    username: non_empty(proxy.username),
+                    password: proxy.password.filter(|p| !p.is_empty()),
+                })
+                .filter(|proxy| !proxy.url.is_empty()),
+            extra_ca_certificates: self
+                .extra_ca_certificates
+                .into_iter()
+                .map(|path| path.trim().to_string())
+                .filter(|path| !path.is_empty())
+                .collect(),
+            connect_timeout_secs: self.connect_timeout_secs.clamp(1, MAX_NETWORK_TIMEOUT_SECS),
+            read_timeout_secs: self.read_timeout_secs.clamp(1, MAX_NETWORK_TIMEOUT_SECS),
+            offline: self.offline,
+            github_token: non_empty(self.github_token),
+        }
+    }
+
+    /// Keep the stored secrets that `self` leaves out, for updates from a view that never sees
+    /// them. An empty string still clears one. The proxy password is only kept for the same
+    /// proxy URL, so it is never sent to a different host.
+    pub fn with_stored_secrets(mut self, stored: &NetworkSettings) -> Self {
+        if self.github_token.is_none() {
+            self.github_token = stored.github_token.clone();
+        }
+        if let (Some(proxy), Some(stored_proxy)) = (&mut self.proxy, &stored.proxy)
+            && proxy.password.is_none()
+            && proxy.url.trim() == stored_proxy.url
+        {
+            proxy.password = stored_proxy.password.clone();
+        }
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| Err(CoreError::InvalidNetworkSettings(reason));
+        if let Some(proxy) = &self.proxy {
+            let Some((scheme, host)) = proxy.url.split_once("://") else {
+                return invalid(format!("proxy url {} has no scheme", proxy.url));
+            };
+            if scheme != "http" && scheme != "https" {
+                return invalid(format!("proxy scheme must be http or https, got {scheme}"));
+            }
+            if host.trim_end_matches('/').is_empty() {
+                return invalid("proxy url has no host".to_string());
+            }
+            if proxy.password.is_some() && proxy.username.is_none() {
+                return invalid("proxy password needs a username".to_string());
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Default for Settings {
@@ -24,6 +248,130 @@ impl Default for Settings {
             schema: SETTINGS_SCHEMA,
             payload_root_override: None,
             tab_order: Vec::new(),
+            distribution_source: DistributionSource::default(),
+            downloads: DownloadSettings::default(),
+            network: NetworkSettings::default(),
+            update_check: UpdateCheckSettings::default(),
+            app_update_source: default_app_update_source(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_settings_are_normalized_and_validated() {
+        let network = NetworkSettings {
+            proxy: Some(ProxySettings {
+                url: " http://proxy.lab:3128 ".to_string(),
+                username: Some("  ".to_string()),
+                password: None,
+            }),
+            extra_ca_certificates: vec![" /etc/lab-ca.pem ".to_string(), String::new()],
+            connect_timeout_secs: 0,
+            read_timeout_secs: 100_000,
+            offline: false,
+            github_token: Some(" ghp_x ".to_string()),
+        }
+        .normalized();
+        assert_eq!(network.proxy.as_ref().unwrap().url, "http://proxy.lab:3128");
+        assert_eq!(network.proxy.as_ref().unwrap().username, None);
+        assert_eq!(network.extra_ca_certificates, ["/etc/lab-ca.pem"]);
+        assert_eq!(network.connect_timeout_secs, 1);
+        assert_eq!(network.read_timeout_secs, MAX_NETWORK_TIMEOUT_SECS);
+        assert_eq!(network.github_token.as_deref(), Some("ghp_x"));
+        assert!(network.validate().is_ok());
+
+        for url in ["proxy.lab:3128", "socks5://proxy.lab", "http://"] {
+            let network = NetworkSettings {
+                proxy: Some(ProxySettings {
+                    url: url.to_string(),
+                    username: None,
+                    password: None,
+                }),
+                ..NetworkSettings::default()
+            };
+            assert!(network.validate().is_err(), "{url}");
+        }
+    }
+
+    #[test]
+    fn settings_without_network_section_get_defaults() {
+        let settings: Settings = serde_json::from_str(r#"{"schema": 1}"#).unwrap();
+        assert_eq!(settings.network, NetworkSettings::default());
+        assert!(!settings.network.offline);
+        assert_eq!(settings.update_check, UpdateCheckSettings::default());
+    }
+
+    #[test]
+    fn update_check_interval_is_clamped() {
+        let short = UpdateCheckSettings {
+            enabled: true,
+            interval_mins: 1,
+        };
+        assert_eq!(
+            short.normalized().interval_mins,
+            MIN_UPDATE_CHECK_INTERVAL_MINS
+        );
+        let long = UpdateCheckSettings {
+            enabled: false,
+            interval_mins: u32::MAX,
+        };
+        assert_eq!(
+            long.normalized().interval_mins,
+            MAX_UPDATE_CHECK_INTERVAL_MINS
+        );
+        assert!(!long.normalized().enabled);
+    }
+
+    #[test]
+    fn omitted_secrets_are_kept_and_empty_ones_cleared() {
+        let stored = NetworkSettings {
+            proxy: Some(ProxySettings {
+                url: "http://proxy.local:3128".to_string(),
+                username: Some("studio".to_string()),
+                password: Some("secret".to_string()),
+            }),
+            github_token: Some("ghp_token".to_string()),
+            ..NetworkSettings::default()
+        };
+
+        let kept = NetworkSettings {
+            proxy: Some(ProxySettings {
+                password: None,
+                ..stored.proxy.clone().unwrap()
+            }),
+            github_token: None,
+            ..stored.clone()
+        }
+        .with_stored_secrets(&stored)
+        .normalized();
+        assert_eq!(kept, stored);
+
+        let cleared = NetworkSettings {
+            proxy: Some(ProxySettings {
+                password: Some(String::new()),
+                ..stored.proxy.clone().unwrap()
+            }),
+            github_token: Some(String::new()),
+            ..stored.clone()
+        }
+        .with_stored_secrets(&stored)
+        .normalized();
+        assert_eq!(cleared.proxy.unwrap().password, None);
+        assert_eq!(cleared.github_token, None);
+
+        let moved = NetworkSettings {
+            proxy: Some(ProxySettings {
+                url: "http://other.local:3128".to_string(),
+                username: Some("studio".to_string()),
+                password: None,
+            }),
+            ..NetworkSettings::default()
         }
+        .with_stored_secrets(&stored);
+        assert_eq!(moved.proxy.unwrap().password, None);
     }
 }
@@ -0,0 +1,209 @@
+use std::fmt;
+
+use crate::{Channel, CoreError, Result, SemVer, parse_tag_for_channel};
+
+type Triple = (u64, u64, u64);
+
+/// A version range an instance follows instead of an exact tag, e.g. `~0.2`, `^0.3`,
+/// `<0.4.0` or `>=0.2.1, <0.4`.
+///
+/// Comparators are separated by commas or spaces and must all match. Versions may be partial
+/// (`0.2` stands for every `0.2.x`) and take an optional `v` prefix. Only
+/// `MAJOR.MINOR.PATCH` is compared: the prerelease part of a tag picks its channel, not its
+/// place in the range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionRange {
+    raw: String,
+    /// Inclusive lower and exclusive upper bound of each comparator.
+    bounds: Vec<(Option<Triple>, Option<Triple>)>,
+}
+
+impl VersionRange {
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = |reason: String| CoreError::InvalidVersionRange(format!("{value}: {reason}"));
+
+        let mut tokens = Vec::new();
+        let mut pending_op: Option<&str> = None;
+        for token in value
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|token| !token.is_empty())
+        {
+            if is_operator(token) {
+                if pending_op.is_some() {
+                    return Err(invalid(format!("operator {token} has no version")));
+                }
+                pending_op = Some(token);
+                continue;
+            }
+            tokens.push(match pending_op.take() {
+                Some(op) => format!("{op}{token}"),
+                None => token.to_string(),
+            });
+        }
+        if let Some(op) = pending_op {
+            return Err(invalid(format!("operator {op} has no version")));
+        }
+        if tokens.is_empty() {
+            return Err(invalid("empty range".to_string()));
+        }
+
+        let bounds = tokens
+            .iter()
+            .map(|token| {
+                parse_comparator(token).ok_or_else(|| invalid(format!("bad comparator {token}")))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            raw: tokens.join(", "),
+            bounds,
+        })
+    }
+
+    pub fn matches(&self, version: &SemVer) -> bool {
+        let triple = (version.major, version.minor, version.patch);
+        self.bounds.iter().all(|(lower, upper)| {
+            lower.is_none_or(|lower| triple >= lower) && upper.is_none_or(|upper| triple < upper)
+        })
+    }
+
+    /// Highest tag of `channel` among `tags` that falls in the range.
+    pub fn highest_match<'a>(&self, channel: Channel, tags: &'a [String]) -> Option<&'a str> {
+        tags.iter()
+            .filter_map(|tag| Some((tag.as_str(), parse_tag_for_channel(channel, tag)?)))
+            .filter(|(_, version)| self.matches(version))
+            .max_by(|(_, a), (_, b)| a.cmp_precedence(b))
+            .map(|(tag, _)| tag)
+    }
+}
+
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+const OPERATORS: [&str; 7] = [">=", "<=", ">", "<", "=", "~", "^"];
+
+fn is_operator(token: &str) -> bool {
+    OPERATORS.contains(&token)
+}
+
+fn parse_comparator(token: &str) -> Option<(Option<Triple>, Option<Triple>)> {
+    let op = OPERATORS
+        .into_iter()
+        .find(|op| token.starts_with(op))
+        .unwrap_or("=");
+    let version = token.strip_prefix(op).unwrap_or(token);
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let parts = version
+        .split('.')
+        .map(|part| {
+            if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            part.parse::<u64>().ok()
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+
+    let (major, minor, patch) = (
+        parts[0],
+        parts.get(1).copied().unwrap_or(0),
+        parts.get(2).copied().unwrap_or(0),
+    );
+    let low = (major, minor, patch);
+    // First version past every version `low` stands for when read with `len` components; `None`
+    // when that version would not fit in a `u64`.
+    let bump = |len: usize| match len {
+        1 => Some((major.checked_add(1)?, 0, 0)),
+        2 => Some((major, minor.checked_add(1)?, 0)),
+        _ => Some((major, minor, patch.checked_add(1)?)),
+    };
+    let past = || bump(parts.len());
+    Some(match op {
+        "=" => (Some(low), Some(past()?)),
+        ">=" => (Some(low), None),
+        ">" => (Some(past()?), None),
+        "<" => (None, Some(low)),
+        "<=" => (None, Some(past()?)),
+        "~" if parts.len() == 1 => (Some(low), Some(bump(1)?)),
+        "~" => (Some(low), Some(bump(2)?)),
+        // `^`: changes left of the first non-zero component are breaking.
+        _ if major > 0 || parts.len() == 1 => (Some(low), Some(bump(1)?)),
+        _ if minor > 0 || parts.len() == 2 => (Some(low), Some(bump(2)?)),
+        _ => (Some(low), Some(bump(3)?)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(range: &str, version: &str) -> bool {
+        VersionRange::parse(range)
+            .unwrap()
+            .matches(&SemVer::parse(version).unwrap())
+    }
+
+    #[test]
+    fn tilde_caret_and_comparators_follow_cargo_semantics() {
+        assert!(matches("~0.2", "0.2.9"));
+        assert!(!matches("~0.2", "0.3.0"));
+        assert!(matches("~1.2.3", "1.2.7"));
+        assert!(!matches("~1.2.3", "1.2.2"));
+        assert!(matches("^0.3", "0.3.4"));
+        assert!(!matches("^0.3", "0.4.0"));
+        assert!(matches("^1.2", "1.9.0"));
+        assert!(!matches("^1.2", "2.0.0"));
+        assert!(!matches("^0.0.3", "0.0.4"));
+        assert!(matches("<0.4.0", "0.3.9"));
+        assert!(!matches("<0.4.0", "0.4.0"));
+        assert!(matches("<=0.4", "0.4.9"));
+        assert!(!matches(">0.4", "0.4.9"));
+        assert!(matches("0.2", "0.2.5"));
+        assert!(matches(">=0.2.1, < 0.4", "0.3.0-beta.2"));
+        assert!(!matches(">=0.2.1 <0.4", "0.2.0"));
+    }
+
+    #[test]
+    fn parse_rejects_malformed_ranges() {
+        for range in [
+            "",
+            "~",
+            "^x.1",
+            "<0.4.0.1",
+            ">= ,",
+            "0.-1",
+            "~~0.2",
+            "^18446744073709551615",
+            "~0.18446744073709551615",
+            "<=0.0.18446744073709551615",
+        ] {
+            assert!(VersionRange::parse(range).is_err(), "{range}");
+        }
+        assert!(VersionRange::parse(">=18446744073709551615").is_ok());
+        assert_eq!(
+            VersionRange::parse(" >=0.2.1 ,<0.4 ").unwrap().to_string(),
+            ">=0.2.1, <0.4"
+        );
+    }
+
+    #[test]
+    fn highest_match_stays_within_channel_and_range() {
+        let tags = ["v0.2.1", "v0.2.4", "v0.3.0", "v0.2.5-beta.1"].map(String::from);
+        let range = VersionRange::parse("~0.2").unwrap();
+        assert_eq!(range.highest_match(Channel::Stable, &tags), Some("v0.2.4"));
+        assert_eq!(
+            range.highest_match(Channel::Beta, &tags),
+            Some("v0.2.5-beta.1")
+        );
+        assert_eq!(
+            VersionRange::parse("^1")
+                .unwrap()
+                .highest_match(Channel::Stable, &tags),
+            None
+        );
+    }
+}
@@ -41,6 +41,16 @@ impl From<ms_manager_core::CoreError> for ApiError {
         match err {
             CoreError::Signature => ApiError::new("manifest_sig_invalid", err.to_string()),
             CoreError::PublicKey => ApiError::new("public_key_invalid", err.to_string()),
+            CoreError::UnknownKeyId(_) => ApiError::new("signing_key_unknown", err.to_string()),
+            CoreError::RevokedKey(_) => ApiError::new("signing_key_revoked", err.to_string()),
+            CoreError::KeyNotTrustedForChannel { .. } => {
+                ApiError::new("signing_key_untrusted", err.to_string())
+            }
+            CoreError::RevocationListJson(_)
+            | CoreError::UnsupportedRevocationSchema(_)
+            | CoreError::RevocationListRejected(_) => {
+                ApiError::new("revocation_list_invalid", err.to_string())
+            }
             CoreError::UnsupportedSchema(_) => {
                 ApiError::new("manifest_schema_unsupported", err.to_string())
             }
@@ -56,6 +66,30 @@ impl From<ms_manager_core::CoreError> for ApiError {
             }
             CoreError::Base64(_) => ApiError::new("base64_invalid", err.to_string()),
             CoreError::InvalidChannel(_) => ApiError::new("invalid_channel", err.to_string()),
+            CoreError::ManagerTooOld {
+                ref tag,
+                ref required,
+                ref current,
+            } => ApiError::new("manager_update_required", err.to_string()).with_details(
+                serde_json::json!({"tag": tag, "required": required, "current": current}),
+            ),
+            CoreError::InvalidDistributionSource(_) => {
+                ApiError::new("distribution_source_invalid", err.to_string())
+            }
+            CoreError::InvalidNetworkSettings(_) => {
+                ApiError::new("network_settings_invalid", err.to_string())
+            }
+            CoreError::InvalidVersionRange(_) => {
+                ApiError::new("version_range_invalid", err.to_string())
+            }
+            CoreError::AppUpdateJson(_)
+            | CoreError::UnsupportedAppUpdateSchema(_)
+            | CoreError::InvalidAppUpdate(_) => {
+                ApiError::new("app_update_manifest_invalid", err.to_string())
+            }
+            CoreError::InvalidFirmwareTarget(_) => {
+                ApiError::new("firmware_target_invalid", err.to_string())
+            }
         }
     }
 }
@@ -1,13 +1,15 @@
-use std::time::Duration;
-
+use ms_manager_core::{AppUpdateManifest, DistributionSource};
 use serde::Deserialize;
-use tauri::AppHandle;
+use tauri::{AppHandle, State};
 
 use crate::api_error::{ApiError, ApiResult};
-use crate::models::{AppUpdateInfo, AppUpdateStatus};
+use crate::layout::PayloadLayout;
+use crate::models::{AppUpdateInfo, AppUpdatePending, AppUpdateStatus};
+use crate::services::app_update::{self, AppInstall};
+use crate::services::distribution;
+use crate::services::http_client::HttpClient;
+use crate::state::AppState;
 
-const RELEASES_API_URL: &str =
-    "https://api.github.com/repos/petitechose-midi-studio/ms-manager/releases/latest";
 const RELEASES_PAGE_URL: &str =
     "https://github.com/petitechose-midi-studio/ms-manager/releases/latest";
 
@@ -23,45 +25,180 @@ fn normalize_tag(tag: &str) -> String {
     tag.strip_prefix('v').unwrap_or(tag).to_string()
 }
 
-async fn fetch_latest_release() -> Result<GitHubRelease, reqwest::Error> {
-    reqwest::Client::builder()
-        .user_agent("ms-manager")
-        .timeout(Duration::from_secs(10))
-        .build()?
-        .get(RELEASES_API_URL)
-        .send()
-        .await?
-        .error_for_status()?
-        .json::<GitHubRelease>()
-        .await
+/// Latest release from the GitHub API, for sources that do not publish a signed app update
+/// manifest yet. Such releases can only be installed by hand.
+async fn fetch_latest_release(client: &HttpClient, repo: &str) -> ApiResult<GitHubRelease> {
+    let url = format!("https://api.github.com/repos/{repo}/releases/latest");
+    let (status, body) = client
+        .get_cached(&url, Some("application/vnd.github+json"))
+        .await?;
+    if !status.is_success() {
+        return Err(ApiError::new("http_status", format!("GET {url}: {status}")));
+    }
+    serde_json::from_slice(&body)
+        .map_err(|e| ApiError::new("json_parse_failed", format!("parse {url}: {e}")))
+}
+
+fn release_page_url(source: &DistributionSource, tag: &str) -> String {
+    match source {
+        DistributionSource::Github { repo } => {
+            format!("https://github.com/{repo}/releases/tag/{tag}")
+        }
+        DistributionSource::Https { .. } | DistributionSource::Directory { .. } => {
+            source.asset_url_for_tag(tag, "")
+        }
+    }
+}
+
+fn pending(layout: &PayloadLayout, install: Option<&AppInstall>) -> AppUpdatePending {
+    let (staged_version, rollback_version) = install
+        .map(|install| app_update::pending_versions(layout, install))
+        .unwrap_or_default();
+    AppUpdatePending {
+        staged_version,
+        rollback_version,
+    }
+}
+
+fn unsupported_install() -> ApiError {
+    ApiError::new(
+        "app_update_unsupported_install",
+        "this ms-manager install cannot update itself; download the new release instead",
+    )
+}
+
+async fn latest_app_update(state: &AppState) -> ApiResult<Option<AppUpdateManifest>> {
+    let client = state.http();
+    let keyring = distribution::load_keyring(
+        &client,
+        &state.layout_get(),
+        &state.distribution_source_get(),
+    )
+    .await;
+    app_update::fetch_app_update(&client, &state.settings_get().app_update_source, &keyring).await
 }
 
 #[tauri::command]
-pub async fn app_update_check(app: AppHandle) -> ApiResult<AppUpdateStatus> {
+pub async fn app_update_check(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> ApiResult<AppUpdateStatus> {
     let current_version = app.package_info().version.to_string();
+    let install = app_update::detect_install();
+    let mut status = AppUpdateStatus {
+        current_version: current_version.clone(),
+        available: false,
+        update: None,
+        error: None,
+        self_update: false,
+        pending: pending(&state.layout_get(), install.as_ref()),
+    };
+    if state.settings_get().network.offline {
+        status.error = Some("offline mode: app update check skipped".to_string());
+        return Ok(status);
+    }
 
-    match fetch_latest_release().await {
-        Ok(release) => {
-            let latest_version = normalize_tag(&release.tag_name);
-            Ok(AppUpdateStatus {
-                current_version: current_version.clone(),
-                available: latest_version != current_version,
-                update: Some(AppUpdateInfo {
-                    version: latest_version,
-                    pub_date: release.published_at,
-                    notes: release.body,
-                    url: release.html_url,
-                }),
-                error: None,
-            })
+    let source = state.settings_get().app_update_source;
+    match latest_app_update(&state).await {
+        Ok(Some(manifest)) => {
+            let platform = ms_manager_core::Platform::current()?;
+            status.available = manifest.is_newer_than(&current_version);
+            status.self_update = install.as_ref().is_some_and(|install| {
+                manifest
+                    .package_for(install.kind, platform.os.as_str(), platform.arch.as_str())
+                    .is_some()
+            });
+            status.update = Some(AppUpdateInfo {
+                url: release_page_url(&source, &manifest.tag()),
+                version: manifest.version,
+                pub_date: manifest.published_at,
+                notes: manifest.notes,
+            });
+        }
+        Ok(None) => {
+            let DistributionSource::Github { repo } = &source else {
+                status.error = Some("app update source has no update manifest".to_string());
+                return Ok(status);
+            };
+            match fetch_latest_release(&state.http(), repo).await {
+                Ok(release) => {
+                    let latest_version = normalize_tag(&release.tag_name);
+                    status.available = latest_version != current_version;
+                    status.update = Some(AppUpdateInfo {
+                        version: latest_version,
+                        pub_date: release.published_at,
+                        notes: release.body,
+                        url: release.html_url,
+                    });
+                }
+                Err(e) => {
+                    status.error =
+                        Some(format!("failed to check latest app release: {}", e.message))
+                }
+            }
+        }
+        Err(e) => {
+            status.error = Some(format!("failed to check latest app release: {}", e.message));
         }
-        Err(e) => Ok(AppUpdateStatus {
-            current_version,
-            available: false,
-            update: None,
-            error: Some(format!("failed to check latest app release: {e}")),
-        }),
     }
+    Ok(status)
+}
+
+/// Download, verify and stage the latest release; it replaces this install on the next start.
+#[tauri::command]
+pub async fn app_update_install(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> ApiResult<AppUpdatePending> {
+    let install = app_update::detect_install().ok_or_else(unsupported_install)?;
+    let current_version = app.package_info().version.to_string();
+    let manifest = latest_app_update(&state).await?.ok_or_else(|| {
+        ApiError::new(
+            "app_update_unavailable",
+            "the app update source has no signed update manifest",
+        )
+    })?;
+    if !manifest.is_newer_than(&current_version) {
+        return Err(ApiError::new(
+            "app_up_to_date",
+            format!("ms-manager {current_version} is up to date"),
+        ));
+    }
+
+    let client = state.http();
+    let layout = state.layout_get();
+    let keyring =
+        distribution::load_keyring(&client, &layout, &state.distribution_source_get()).await;
+    app_update::stage_update(
+        &client,
+        &layout,
+        &keyring,
+        &state.settings_get().app_update_source,
+        &install,
+        &manifest,
+    )
+    .await?;
+    Ok(pending(&layout, Some(&install)))
+}
+
+/// Stage the version kept by the last update; it comes back on the next start.
+#[tauri::command]
+pub fn app_update_rollback(state: State<'_, AppState>) -> ApiResult<AppUpdatePending> {
+    let install = app_update::detect_install().ok_or_else(unsupported_install)?;
+    let layout = state.layout_get();
+    app_update::stage_rollback(&layout, &install)?;
+    Ok(pending(&layout, Some(&install)))
+}
+
+/// Swap in the staged version and restart into it.
+#[tauri::command]
+pub fn app_update_restart(app: AppHandle, state: State<'_, AppState>) -> ApiResult<()> {
+    let install = app_update::detect_install().ok_or_else(unsupported_install)?;
+    let current_version = app.package_info().version.to_string();
+    app_update::apply_staged_update(&state.layout_get(), &install, &current_version)?;
+    app_update::relaunch(&install)?;
+    app.exit(0);
+    Ok(())
 }
 
 #[tauri::command]
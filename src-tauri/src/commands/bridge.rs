@@ -15,6 +15,7 @@ pub async fn bridge_status_get(state: State<'_, AppState>) -> ApiResult<BridgeSt
         installed.as_ref(),
         &state.bridge_instances_get(),
         &state.controller_state_get(),
+        &state.update_check_get(),
     )
     .await)
 }
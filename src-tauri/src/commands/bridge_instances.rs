@@ -4,7 +4,9 @@ use crate::api_error::{ApiError, ApiResult};
 use crate::models::{
     BridgeInstanceArtifactSourceSetRequest, BridgeInstanceBindRequest,
     BridgeInstanceBindingResponse, BridgeInstanceInstalledReleaseSetRequest,
-    BridgeInstanceNameSetRequest, BridgeInstanceTargetSetRequest, BridgeInstancesResponse,
+    BridgeInstanceNameSetRequest, BridgeInstanceTargetSetRequest,
+    BridgeInstanceUpdatePolicySetRequest, BridgeInstanceVersionRangeSetRequest,
+    BridgeInstancesResponse,
 };
 use crate::services::bridge_instances;
 use crate::state::AppState;
@@ -107,6 +109,26 @@ pub fn bridge_instance_installed_release_set(
     Ok(BridgeInstancesResponse { state })
 }
 
+#[tauri::command]
+pub fn bridge_instance_version_range_set(
+    state: State<'_, AppState>,
+    request: BridgeInstanceVersionRangeSetRequest,
+) -> ApiResult<BridgeInstancesResponse> {
+    let state =
+        state.bridge_instance_set_version_range(&request.instance_id, request.version_range)?;
+    Ok(BridgeInstancesResponse { state })
+}
+
+#[tauri::command]
+pub fn bridge_instance_update_policy_set(
+    state: State<'_, AppState>,
+    request: BridgeInstanceUpdatePolicySetRequest,
+) -> ApiResult<BridgeInstancesResponse> {
+    let state =
+        state.bridge_instance_set_update_policy(&request.instance_id, request.update_policy)?;
+    Ok(BridgeInstancesResponse { state })
+}
+
 #[tauri::command]
 pub fn bridge_instance_name_set(
     state: State<'_, AppState>,
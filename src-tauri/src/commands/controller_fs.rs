@@ -4,9 +4,8 @@ use tauri::{AppHandle, Emitter, State};
 use crate::api_error::{ApiError, ApiResult};
 use crate::commands::local_fs::resolve_local_storage_path;
 use crate::services::controller_fs::{
-    BridgeBinaryClient, ControllerFsClient, ControllerFsError, FsCapabilities, FsListEntry,
-    DEFAULT_BRIDGE_CONTROL_PORT, DEFAULT_CONTROL_TIMEOUT, DEFAULT_READ_PIPELINE_WINDOW,
-    FS_RPC_MAX_CHUNK_SIZE,
+    client_for_port, resolve_control_port, ControllerFsClient, ControllerFsError, FsCapabilities,
+    FsListEntry,
 };
 use crate::state::AppState;
 
@@ -229,78 +228,13 @@ pub(crate) fn controller_fs_client(
     instance_id: Option<String>,
     control_port: Option<u16>,
 ) -> ApiResult<ControllerFsClient> {
-    let control_port = resolve_control_port(state, instance_id.as_deref(), control_port)?;
-    ControllerFsClient::new(
-        BridgeBinaryClient::new(control_port).with_timeout(DEFAULT_CONTROL_TIMEOUT),
+    let control_port = resolve_control_port(
+        &state.bridge_instances_get(),
+        instance_id.as_deref(),
+        control_port,
     )
-    .with_chunk_size(FS_RPC_MAX_CHUNK_SIZE)
-    .map_err(controller_fs_error)?
-    .with_read_pipeline_window(DEFAULT_READ_PIPELINE_WINDOW)
-    .map_err(controller_fs_error)
-}
-
-fn resolve_control_port(
-    state: &AppState,
-    instance_id: Option<&str>,
-    control_port: Option<u16>,
-) -> ApiResult<u16> {
-    if control_port == Some(0) {
-        return Err(ApiError::new(
-            "controller_fs_control_port_invalid",
-            "bridge control port cannot be 0",
-        ));
-    }
-
-    let bridge_state = state.bridge_instances_get();
-    if let Some(instance_id) = instance_id {
-        let instance = bridge_state
-            .instances
-            .iter()
-            .find(|instance| instance.instance_id == instance_id)
-            .ok_or_else(|| {
-                ApiError::new(
-                    "controller_fs_instance_missing",
-                    format!("bridge instance not found: {instance_id}"),
-                )
-            })?;
-        if let Some(requested_port) = control_port {
-            if requested_port != instance.control_port {
-                return Err(ApiError::new(
-                    "controller_fs_endpoint_mismatch",
-                    format!(
-                        "bridge instance {instance_id} uses control port {}, not {requested_port}",
-                        instance.control_port
-                    ),
-                ));
-            }
-        }
-        return Ok(instance.control_port);
-    }
-
-    if let Some(port) = control_port {
-        return Ok(port);
-    }
-
-    if bridge_state.instances.is_empty() {
-        return Ok(DEFAULT_BRIDGE_CONTROL_PORT);
-    }
-
-    let enabled: Vec<_> = bridge_state
-        .instances
-        .iter()
-        .filter(|instance| instance.enabled)
-        .collect();
-    if enabled.len() == 1 {
-        return Ok(enabled[0].control_port);
-    }
-    if bridge_state.instances.len() == 1 {
-        return Ok(bridge_state.instances[0].control_port);
-    }
-
-    Err(ApiError::new(
-        "controller_fs_instance_required",
-        "multiple bridge instances are configured; provide instance_id or control_port",
-    ))
+    .map_err(controller_fs_error)?;
+    client_for_port(control_port).map_err(controller_fs_error)
 }
 
 pub(crate) fn controller_fs_error(err: ControllerFsError) -> ApiError {
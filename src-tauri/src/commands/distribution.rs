@@ -1,18 +1,121 @@
 use tauri::State;
 
-use ms_manager_core::{asset_url_for_tag, select_install_set_assets, Channel, Platform};
+use ms_manager_core::{
+    diff_manifests, firmware_targets, install_set_update_size, Channel, Platform,
+};
 
-use crate::api_error::{ApiError, ApiResult};
-use crate::models::{AssetPlan, InstallPlan};
+use crate::api_error::ApiResult;
+use crate::models::{
+    FirmwareTargetsRequest, FirmwareTargetsResponse, InstallPlan, LatestRelease,
+    LatestReleaseRequest, ReleaseDiffRequest, ReleaseDiffResponse,
+};
 use crate::services::distribution;
 use crate::state::AppState;
 
+#[tauri::command]
+pub async fn release_diff(
+    request: ReleaseDiffRequest,
+    state: State<'_, AppState>,
+) -> ApiResult<ReleaseDiffResponse> {
+    let layout = state.layout_get();
+    let source = state.distribution_source_get();
+    let from = distribution::resolve_available_manifest(
+        &state.http(),
+        &layout,
+        &source,
+        request.from_channel,
+        Some(&request.from_tag),
+    )
+    .await?
+    .manifest;
+    let to = distribution::resolve_available_manifest(
+        &state.http(),
+        &layout,
+        &source,
+        request.to_channel,
+        Some(&request.to_tag),
+    )
+    .await?
+    .manifest;
+
+    let download_size = match request.profile.as_deref().filter(|p| !p.is_empty()) {
+        Some(profile) => {
+            let platform = Platform::current()?;
+            Some(install_set_update_size(
+                &from,
+                &to,
+                profile,
+                platform.os.as_str(),
+                platform.arch.as_str(),
+            )?)
+        }
+        None => None,
+    };
+
+    Ok(ReleaseDiffResponse {
+        diff: diff_manifests(&from, &to),
+        download_size,
+    })
+}
+
+/// Newest release of a channel, or the last one verified when the source is unreachable.
+#[tauri::command]
+pub async fn latest_release_get(
+    request: LatestReleaseRequest,
+    state: State<'_, AppState>,
+) -> ApiResult<LatestRelease> {
+    let layout = state.layout_get();
+    let source = state.distribution_source_get();
+    let available = distribution::resolve_available_manifest(
+        &state.http(),
+        &layout,
+        &source,
+        request.channel,
+        None,
+    )
+    .await?;
+    Ok(LatestRelease {
+        channel: request.channel,
+        tag: available.tag,
+        notes: available.manifest.notes,
+        stale: available.stale_fetched_at_ms.is_some(),
+        fetched_at_ms: available.stale_fetched_at_ms,
+    })
+}
+
+/// Firmware targets the newest release of a channel offers on this platform.
+#[tauri::command]
+pub async fn firmware_targets_get(
+    request: FirmwareTargetsRequest,
+    state: State<'_, AppState>,
+) -> ApiResult<FirmwareTargetsResponse> {
+    let available = distribution::resolve_available_manifest(
+        &state.http(),
+        &state.layout_get(),
+        &state.distribution_source_get(),
+        request.channel,
+        None,
+    )
+    .await?;
+    let platform = Platform::current()?;
+    Ok(FirmwareTargetsResponse {
+        channel: request.channel,
+        targets: firmware_targets(
+            &available.manifest,
+            platform.os.as_str(),
+            platform.arch.as_str(),
+        ),
+        tag: available.tag,
+    })
+}
+
 #[tauri::command]
 pub async fn list_channel_tags(
     channel: Channel,
     state: State<'_, AppState>,
 ) -> ApiResult<Vec<String>> {
-    distribution::list_tags_for_channel(&state.http, channel).await
+    let source = state.distribution_source_get();
+    distribution::list_tags_for_channel(&state.http(), &source, channel).await
 }
 
 pub(crate) async fn plan_install_internal(
@@ -21,55 +124,7 @@ pub(crate) async fn plan_install_internal(
     tag: Option<&str>,
     state: &AppState,
 ) -> ApiResult<InstallPlan> {
-    let out = match tag {
-        Some(t) => distribution::resolve_manifest_for_tag(&state.http, channel, t).await?,
-        None => distribution::resolve_latest_manifest(&state.http, channel).await?,
-    };
-    if !out.available {
-        return Err(ApiError::new(
-            "no_release_available",
-            out.message.unwrap_or_else(|| "no releases".to_string()),
-        ));
-    }
-
-    let manifest = out
-        .manifest
-        .ok_or_else(|| ApiError::new("internal_error", "missing manifest"))?;
-    let tag = out
-        .tag
-        .ok_or_else(|| ApiError::new("internal_error", "missing tag"))?;
-
-    if profile.is_empty() {
-        return Err(ApiError::new("invalid_profile", "profile cannot be empty"));
-    }
-
-    let platform = Platform::current()?;
-    let assets = select_install_set_assets(
-        &manifest,
-        profile,
-        platform.os.as_str(),
-        platform.arch.as_str(),
-    )?;
-
-    let plans = assets
-        .into_iter()
-        .map(|a| AssetPlan {
-            id: a.id,
-            kind: a.kind,
-            filename: a.filename.clone(),
-            sha256: a.sha256,
-            size: a.size,
-            url: a
-                .url
-                .unwrap_or_else(|| asset_url_for_tag(&tag, &a.filename)),
-        })
-        .collect::<Vec<_>>();
-
-    Ok(InstallPlan {
-        channel,
-        tag,
-        profile: profile.to_string(),
-        platform,
-        assets: plans,
-    })
+    let layout = state.layout_get();
+    let source = state.distribution_source_get();
+    distribution::plan_install(&state.http(), &layout, &source, channel, profile, tag).await
 }
@@ -9,7 +9,7 @@ use crate::state::AppState;
 pub async fn workspace_firmware_profiles(
     target: ms_manager_core::FirmwareTarget,
 ) -> ApiResult<Vec<workspace_firmware::WorkspaceFirmwareProfile>> {
-    workspace_firmware::profiles(target).await
+    workspace_firmware::profiles(&target).await
 }
 
 #[tauri::command]
@@ -18,7 +18,7 @@ pub async fn build_workspace_firmware(
     build_profile: String,
     app: tauri::AppHandle,
 ) -> ApiResult<workspace_firmware::WorkspaceFirmwareProfile> {
-    workspace_firmware::build(&app, target, &build_profile).await
+    workspace_firmware::build(&app, &target, &build_profile).await
 }
 
 #[tauri::command]
@@ -54,7 +54,7 @@ pub async fn flash_bridge_instance(
                     "Select a development firmware profile before flashing.",
                 )
             })?;
-        let mut profile = workspace_firmware::profile(binding.target, profile_id).await?;
+        let mut profile = workspace_firmware::profile(&binding.target, profile_id).await?;
         if profile.artifact_ready && profile.source_dirty {
             flash::emit_flash_message(
                 &app,
@@ -70,7 +70,7 @@ pub async fn flash_bridge_instance(
                 FlashMessageLevel::Info,
                 format!("Firmware artifact missing; building {profile_id}..."),
             );
-            profile = match workspace_firmware::build(&app, binding.target, profile_id).await {
+            profile = match workspace_firmware::build(&app, &binding.target, profile_id).await {
                 Ok(profile) => profile,
                 Err(error) => {
                     flash::emit_flash_done(&app, false);
@@ -83,7 +83,7 @@ pub async fn flash_bridge_instance(
                 format!("Build complete: {}", profile.id),
             );
         }
-        Some((profile.artifact_path, profile.id))
+        Some((profile.artifact_path, Some(profile.id)))
     } else {
         None
     };
@@ -1,16 +1,115 @@
 use tauri::Emitter;
 use tauri::State;
 
-use ms_manager_core::{compare_tags, Channel, InstallState, INSTALL_STATE_SCHEMA};
+use ms_manager_core::{BridgeInstanceBinding, InstallState, VersionRange};
 
 use crate::api_error::{ApiError, ApiResult};
 use crate::commands::distribution::plan_install_internal;
-use crate::models::InstallEvent;
-use crate::services::{assets, bridge_ctl, install};
-use crate::state::AppState;
+use crate::models::{
+    AssetCacheVerifyRequest, AssetCacheVerifyResponse, InstallEvent, InstallHistoryResponse,
+    InstalledVersionRequest, VersionAuditReport,
+};
+use crate::services::{
+    assets, bridge_ctl, distribution, install, install_history, native_sim, version_audit,
+};
+use crate::state::{AppState, InstallGuard};
 
 const INSTALL_EVENT: &str = "ms-manager://install";
 
+/// Re-check a cached asset from its content and stored signature alone.
+#[tauri::command]
+pub async fn asset_cache_verify(
+    state: State<'_, AppState>,
+    request: AssetCacheVerifyRequest,
+) -> ApiResult<AssetCacheVerifyResponse> {
+    let layout = state.layout_get();
+    let source = state.distribution_source_get();
+    let keyring = distribution::load_keyring(&state.http(), &layout, &source).await;
+    let key_id = tauri::async_runtime::spawn_blocking(move || {
+        assets::verify_cached_asset(
+            &layout,
+            &keyring,
+            request.sha256.trim(),
+            request.filename.trim(),
+        )
+    })
+    .await
+    .map_err(|e| ApiError::new("internal_error", format!("asset verify task failed: {e}")))??;
+    Ok(AssetCacheVerifyResponse { key_id })
+}
+
+#[tauri::command]
+pub fn install_history_get(state: State<'_, AppState>) -> ApiResult<InstallHistoryResponse> {
+    let entries = install_history::read(&state.layout_get())?;
+    Ok(InstallHistoryResponse { entries })
+}
+
+/// Switch back to the previously active release without reinstalling it.
+#[tauri::command]
+pub async fn install_rollback(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> ApiResult<InstallState> {
+    let _ = app.emit(
+        INSTALL_EVENT,
+        InstallEvent::Applying {
+            step: "rollback".to_string(),
+        },
+    );
+    let next = install_history::rollback(&state).await?;
+    let _ = app.emit(
+        INSTALL_EVENT,
+        InstallEvent::Done {
+            tag: next.tag.clone(),
+            profile: next.profile.clone(),
+        },
+    );
+    Ok(next)
+}
+
+/// Compare an installed version's files with the inventory written at install time.
+#[tauri::command]
+pub async fn installed_version_audit(
+    state: State<'_, AppState>,
+    request: InstalledVersionRequest,
+) -> ApiResult<VersionAuditReport> {
+    let layout = state.layout_get();
+    let tag = installed_version_tag(&state, request.tag)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        version_audit::audit_installed_version(&layout, &tag)
+    })
+    .await
+    .map_err(|e| ApiError::new("internal_error", format!("version audit task failed: {e}")))?
+}
+
+/// Rebuild an installed version from the cached assets it was installed from.
+#[tauri::command]
+pub async fn installed_version_repair(
+    state: State<'_, AppState>,
+    request: InstalledVersionRequest,
+) -> ApiResult<VersionAuditReport> {
+    let _install = state.install_lock().await?;
+    let layout = state.layout_get();
+    let tag = installed_version_tag(&state, request.tag)?;
+    let source = state.distribution_source_get();
+    let keyring = distribution::load_keyring(&state.http(), &layout, &source).await;
+    bridge_ctl::shutdown_enabled(&state.bridge_instances_get()).await;
+    version_audit::repair_installed_version(&layout, &keyring, &tag).await
+}
+
+fn installed_version_tag(state: &AppState, tag: Option<String>) -> ApiResult<String> {
+    match tag
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+    {
+        Some(tag) => Ok(tag),
+        None => state
+            .install_state_get()
+            .map(|installed| installed.tag)
+            .ok_or_else(|| ApiError::new("install_missing", "no release is installed")),
+    }
+}
+
 #[tauri::command]
 pub async fn install_bridge_instance(
     instance_id: String,
@@ -36,6 +135,42 @@ pub async fn install_bridge_instance(
         ));
     }
 
+    // A pin or a range is user intent and may go below the active release.
+    let allow_downgrade =
+        binding.installed_pinned_tag.is_some() || binding.installed_version_range.is_some();
+    let tag = match (binding.installed_channel, &binding.installed_version_range) {
+        (Some(channel), Some(range)) => Some(
+            distribution::resolve_tag_in_range(
+                &state.http(),
+                &state.distribution_source_get(),
+                channel,
+                &VersionRange::parse(range)?,
+            )
+            .await?,
+        ),
+        _ => binding.installed_pinned_tag.clone(),
+    };
+    let install = state.install_lock().await?;
+    install_release_for_binding(
+        &binding,
+        tag.as_deref(),
+        allow_downgrade,
+        &app,
+        &state,
+        &install,
+    )
+    .await
+}
+
+/// Install `tag` (the channel's latest when `None`) for an instance and pin the instance to it.
+pub(crate) async fn install_release_for_binding(
+    binding: &BridgeInstanceBinding,
+    tag: Option<&str>,
+    allow_downgrade: bool,
+    app: &tauri::AppHandle,
+    state: &AppState,
+    install: &InstallGuard<'_>,
+) -> ApiResult<InstallState> {
     let channel = binding.installed_channel.ok_or_else(|| {
         ApiError::new(
             "bridge_instance_install_invalid",
@@ -44,145 +179,26 @@ pub async fn install_bridge_instance(
     })?;
 
     let profile = binding.target.profile_id().to_string();
-    let plan = plan_install_internal(
+    let plan = plan_install_internal(channel, &profile, tag, state).await?;
+    let events = app.clone();
+    let installed = install::install_release(
+        state,
         channel,
-        &profile,
-        binding.installed_pinned_tag.as_deref(),
-        &state,
+        plan.clone(),
+        allow_downgrade,
+        false,
+        install,
+        move |event| {
+            let _ = events.emit(INSTALL_EVENT, event);
+        },
     )
     .await?;
-    let allow_downgrade = binding.installed_pinned_tag.is_some();
-    let installed =
-        install_from_plan(channel, plan.clone(), allow_downgrade, false, &app, &state).await?;
     let _ = state.bridge_instance_set_installed_release(
         &binding.instance_id,
         channel,
         Some(plan.tag),
     )?;
+    // The supervisor restarts the simulator from the new release.
+    native_sim::stop(&binding.instance_id);
     Ok(installed)
 }
-
-async fn install_from_plan(
-    channel: Channel,
-    plan: crate::models::InstallPlan,
-    allow_downgrade: bool,
-    activate_current: bool,
-    app: &tauri::AppHandle,
-    state: &AppState,
-) -> ApiResult<InstallState> {
-    let layout = state.layout_get();
-
-    // Anti-rollback: default update path must never auto-downgrade.
-    // Explicit pinning is treated as user intent, and may downgrade.
-    if !allow_downgrade {
-        if let Some(installed) = state.install_state_get() {
-            if installed.channel == channel {
-                let ord = compare_tags(channel, &plan.tag, &installed.tag).ok_or_else(|| {
-                    ApiError::new(
-                        "tag_invalid",
-                        format!(
-                            "cannot compare tags for channel {}: {} vs {}",
-                            channel.as_str(),
-                            plan.tag,
-                            installed.tag
-                        ),
-                    )
-                })?;
-
-                if ord.is_lt() {
-                    return Err(ApiError::new(
-                        "downgrade_refused",
-                        format!(
-                            "refusing downgrade: installed {} -> target {}",
-                            installed.tag, plan.tag
-                        ),
-                    ));
-                }
-            }
-        }
-    }
-
-    let _ = app.emit(
-        INSTALL_EVENT,
-        InstallEvent::Begin {
-            channel,
-            tag: plan.tag.clone(),
-            profile: plan.profile.clone(),
-            assets_total: plan.assets.len(),
-        },
-    );
-
-    let mut cached = Vec::with_capacity(plan.assets.len());
-    for (i, a) in plan.assets.iter().enumerate() {
-        let _ = app.emit(
-            INSTALL_EVENT,
-            InstallEvent::Downloading {
-                index: i + 1,
-                total: plan.assets.len(),
-                asset_id: a.id.clone(),
-                filename: a.filename.clone(),
-            },
-        );
-        let p = assets::ensure_asset_cached(&state.http, &layout, a).await?;
-        cached.push(assets::CachedAsset {
-            plan: a.clone(),
-            path: p,
-        });
-    }
-
-    let _ = app.emit(
-        INSTALL_EVENT,
-        InstallEvent::Applying {
-            step: "extract_and_stage".to_string(),
-        },
-    );
-
-    if activate_current {
-        shutdown_active_bridges(state).await;
-    }
-
-    let installed = install::apply_install(&layout, &plan, &cached, activate_current).await?;
-
-    let next = InstallState {
-        schema: INSTALL_STATE_SCHEMA,
-        channel,
-        profile: installed.profile,
-        tag: installed.tag,
-    };
-    let next = if activate_current {
-        state.install_state_set(next)?
-    } else {
-        next
-    };
-
-    let _ = app.emit(
-        INSTALL_EVENT,
-        InstallEvent::Done {
-            tag: next.tag.clone(),
-            profile: next.profile.clone(),
-        },
-    );
-
-    Ok(next)
-}
-
-async fn shutdown_active_bridges(state: &AppState) {
-    let mut ports = state
-        .bridge_instances_get()
-        .instances
-        .into_iter()
-        .filter(|binding| binding.enabled)
-        .map(|binding| binding.control_port)
-        .collect::<Vec<_>>();
-
-    ports.sort_unstable();
-    ports.dedup();
-
-    for port in ports {
-        let _ = bridge_ctl::send_command(port, "shutdown", std::time::Duration::from_secs(2)).await;
-    }
-
-    if !state.bridge_instances_get().instances.is_empty() {
-        tokio::time::sleep(std::time::Duration::from_millis(400)).await;
-    }
-}
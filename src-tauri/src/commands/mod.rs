@@ -8,6 +8,7 @@ pub mod flash;
 pub mod install;
 pub mod local_fs;
 pub mod midi;
+pub mod offline_bundle;
 pub mod payload;
 pub mod project_migration;
 pub mod settings;
@@ -0,0 +1,71 @@
+use std::path::Path;
+
+use tauri::State;
+
+use ms_manager_core::Platform;
+
+use crate::api_error::{ApiError, ApiResult};
+use crate::models::{
+    ReleaseBundleExportReport, ReleaseBundleExportRequest, ReleaseBundleImportReport,
+    ReleaseBundleImportRequest,
+};
+use crate::services::{distribution, offline_bundle};
+use crate::state::AppState;
+
+#[tauri::command]
+pub async fn release_bundle_import(
+    state: State<'_, AppState>,
+    request: ReleaseBundleImportRequest,
+) -> ApiResult<ReleaseBundleImportReport> {
+    let layout = state.layout_get();
+    let source = state.distribution_source_get();
+    let keyring = distribution::load_keyring(&state.http(), &layout, &source).await;
+    offline_bundle::import_bundle(&layout, keyring, Path::new(request.path.trim())).await
+}
+
+#[tauri::command]
+pub async fn release_bundle_export(
+    state: State<'_, AppState>,
+    request: ReleaseBundleExportRequest,
+) -> ApiResult<ReleaseBundleExportReport> {
+    let layout = state.layout_get();
+    let tag = request.tag.trim();
+
+    // Prefer a previously imported manifest so exports work offline too.
+    let mut signed = distribution::fetch_signed_manifest_for_tag(
+        &state.http(),
+        &layout,
+        &offline_bundle::offline_source(&layout),
+        request.channel,
+        tag,
+    )
+    .await?;
+    if signed.is_none() {
+        let source = state.distribution_source_get();
+        signed = distribution::fetch_signed_manifest_for_tag(
+            &state.http(),
+            &layout,
+            &source,
+            request.channel,
+            tag,
+        )
+        .await?;
+    }
+    let signed = signed.ok_or_else(|| {
+        ApiError::new("release_not_found", format!("release {tag} not found"))
+            .with_details(serde_json::json!({ "tag": tag }))
+    })?;
+
+    let platform = match request.platform {
+        Some(platform) => platform,
+        None => Platform::current()?,
+    };
+    offline_bundle::export_bundle(
+        &layout,
+        signed,
+        request.profiles,
+        platform,
+        Path::new(request.path.trim()),
+    )
+    .await
+}
@@ -3,10 +3,33 @@ use tauri::State;
 use crate::api_error::{ApiError, ApiResult};
 use crate::commands::status::status_get_internal;
 use crate::layout::PayloadLayout;
-use crate::models::Status;
-use crate::services::payload;
+use crate::models::{PayloadGcReport, PayloadGcRequest, Status};
+use crate::services::{payload, payload_gc};
 use crate::state::AppState;
 
+/// Remove installed versions and cached assets that are no longer referenced.
+#[tauri::command]
+pub async fn payload_gc(
+    state: State<'_, AppState>,
+    request: PayloadGcRequest,
+) -> ApiResult<PayloadGcReport> {
+    let _install = state.install_lock().await?;
+    let layout = state.layout_get();
+    let installed = state.install_state_get();
+    let bindings = state.bridge_instances_get();
+    tauri::async_runtime::spawn_blocking(move || {
+        payload_gc::collect_garbage(
+            &layout,
+            installed.as_ref(),
+            &bindings,
+            request.keep_per_channel,
+            request.dry_run,
+        )
+    })
+    .await
+    .map_err(|e| ApiError::new("internal_error", format!("payload gc task failed: {e}")))?
+}
+
 #[tauri::command]
 pub fn path_open(path: String) -> ApiResult<()> {
     let path = std::path::PathBuf::from(normalize_path_string(path.trim()));
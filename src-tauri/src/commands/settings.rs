@@ -1,9 +1,15 @@
 use std::collections::HashSet;
 
+use ms_manager_core::NetworkSettings;
 use tauri::State;
 
 use crate::api_error::ApiResult;
-use crate::models::{TabOrderResponse, TabOrderSetRequest};
+use crate::models::{
+    DistributionSourceResponse, DistributionSourceSetRequest, DownloadSettingsResponse,
+    DownloadSettingsSetRequest, NetworkSettingsResponse, NetworkSettingsSetRequest,
+    NetworkSettingsView, ProxySettingsView, TabOrderResponse, TabOrderSetRequest,
+    UpdateCheckSettingsResponse, UpdateCheckSettingsSetRequest,
+};
 use crate::state::AppState;
 
 #[tauri::command]
@@ -43,3 +49,81 @@ pub fn tab_order_set(
         tab_order: settings.tab_order,
     })
 }
+
+#[tauri::command]
+pub fn distribution_source_set(
+    state: State<'_, AppState>,
+    request: DistributionSourceSetRequest,
+) -> ApiResult<DistributionSourceResponse> {
+    let settings = state.settings_set_distribution_source(request.source)?;
+    Ok(DistributionSourceResponse {
+        source: settings.distribution_source,
+    })
+}
+
+/// Where ms-manager looks for its own releases, e.g. a local directory standing in for them.
+#[tauri::command]
+pub fn app_update_source_set(
+    state: State<'_, AppState>,
+    request: DistributionSourceSetRequest,
+) -> ApiResult<DistributionSourceResponse> {
+    let settings = state.settings_set_app_update_source(request.source)?;
+    Ok(DistributionSourceResponse {
+        source: settings.app_update_source,
+    })
+}
+
+#[tauri::command]
+pub fn download_settings_set(
+    state: State<'_, AppState>,
+    request: DownloadSettingsSetRequest,
+) -> ApiResult<DownloadSettingsResponse> {
+    let settings = state.settings_set_downloads(request.downloads)?;
+    Ok(DownloadSettingsResponse {
+        downloads: settings.downloads,
+    })
+}
+
+/// Apply proxy, CA, timeout and offline settings; the shared HTTP client is rebuilt right away.
+///
+/// The webview never sees the stored proxy password or GitHub token: leaving one out keeps it,
+/// an empty string clears it.
+#[tauri::command]
+pub fn network_settings_set(
+    state: State<'_, AppState>,
+    request: NetworkSettingsSetRequest,
+) -> ApiResult<NetworkSettingsResponse> {
+    let network = request
+        .network
+        .with_stored_secrets(&state.settings_get().network);
+    let settings = state.settings_set_network(network)?;
+    Ok(NetworkSettingsResponse {
+        network: network_settings_view(&settings.network),
+    })
+}
+
+pub(crate) fn network_settings_view(network: &NetworkSettings) -> NetworkSettingsView {
+    NetworkSettingsView {
+        proxy: network.proxy.as_ref().map(|proxy| ProxySettingsView {
+            url: proxy.url.clone(),
+            username: proxy.username.clone(),
+            password_set: proxy.password.is_some(),
+        }),
+        extra_ca_certificates: network.extra_ca_certificates.clone(),
+        connect_timeout_secs: network.connect_timeout_secs,
+        read_timeout_secs: network.read_timeout_secs,
+        offline: network.offline,
+        github_token_set: network.github_token.is_some(),
+    }
+}
+
+#[tauri::command]
+pub fn update_check_settings_set(
+    state: State<'_, AppState>,
+    request: UpdateCheckSettingsSetRequest,
+) -> ApiResult<UpdateCheckSettingsResponse> {
+    let settings = state.settings_set_update_check(request.update_check)?;
+    Ok(UpdateCheckSettingsResponse {
+        update_check: settings.update_check,
+    })
+}
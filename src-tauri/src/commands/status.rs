@@ -1,6 +1,7 @@
 use tauri::State;
 
 use crate::api_error::ApiResult;
+use crate::commands::settings::network_settings_view;
 use crate::models::Status;
 use crate::services::artifact_resolver;
 use crate::services::bridge_status;
@@ -22,6 +23,7 @@ pub(crate) async fn status_get_internal(state: &AppState) -> ApiResult<Status> {
 
     let bindings = state.bridge_instances_get();
     let controller_state = state.controller_state_get();
+    let update_check = state.update_check_get();
     let bridge_layout = layout.clone();
     let bridge_installed = installed.clone();
     let bridge_task = tauri::async_runtime::spawn(async move {
@@ -30,6 +32,7 @@ pub(crate) async fn status_get_internal(state: &AppState) -> ApiResult<Status> {
             bridge_installed.as_ref(),
             &bindings,
             &controller_state,
+            &update_check,
         )
         .await
     });
@@ -51,9 +54,14 @@ pub(crate) async fn status_get_internal(state: &AppState) -> ApiResult<Status> {
             .map(|path| path.display().to_string()),
         artifact_message: artifact_health.message,
         tab_order: settings.tab_order,
+        distribution_source: settings.distribution_source,
+        downloads: settings.downloads,
+        network: network_settings_view(&settings.network),
+        update_check: settings.update_check,
         platform: ms_manager_core::Platform::current()?,
         payload_root: layout.root().display().to_string(),
         device,
         bridge,
+        recovery: state.payload_recovery_get(),
     })
 }
@@ -1,7 +1,13 @@
 use std::path::{Path, PathBuf};
 
+use ms_manager_core::Channel;
+
 use crate::api_error::{ApiError, ApiResult};
 
+/// Manager bookkeeping files kept at the top of each `versions/<tag>` directory.
+pub const VERSION_ASSETS_FILE: &str = ".assets.json";
+pub const VERSION_INVENTORY_FILE: &str = ".inventory.json";
+
 #[derive(Debug, Clone)]
 pub struct PayloadLayout {
     root: PathBuf,
@@ -45,6 +51,21 @@ impl PayloadLayout {
         self.state_dir().join("state.json")
     }
 
+    /// Intent record of the install step in progress; absent when none is.
+    pub fn install_journal_file(&self) -> PathBuf {
+        self.state_dir().join("install_journal.json")
+    }
+
+    /// Held by whichever process is writing to the payload; see `services::payload_lock`.
+    pub fn install_lock_file(&self) -> PathBuf {
+        self.state_dir().join("install.lock")
+    }
+
+    /// Append-only log of activated releases, one JSON object per line.
+    pub fn install_history_file(&self) -> PathBuf {
+        self.state_dir().join("install_history.jsonl")
+    }
+
     pub fn controller_state_file(&self) -> PathBuf {
         self.state_dir().join("controller.json")
     }
@@ -53,6 +74,15 @@ impl PayloadLayout {
         self.state_dir().join("bridge_instances.json")
     }
 
+    /// Self-update bookkeeping: the staged ms-manager and the one kept for rollback.
+    pub fn app_update_state_file(&self) -> PathBuf {
+        self.state_dir().join("app_update.json")
+    }
+
+    pub fn revoked_keys_file(&self) -> PathBuf {
+        self.state_dir().join("revoked_keys.json")
+    }
+
     pub fn cache_dir(&self) -> PathBuf {
         self.root.join("cache")
     }
@@ -65,6 +95,16 @@ impl PayloadLayout {
         self.ux_recordings_dir().join("index.json")
     }
 
+    /// Storage and log of the native simulator behind a `native_sim` instance.
+    pub fn native_sim_dir(&self, instance_id: &str) -> PathBuf {
+        self.root.join("native-sim").join(instance_id)
+    }
+
+    /// Conditional-request cache of release index and manifest responses.
+    pub fn http_cache_dir(&self) -> PathBuf {
+        self.cache_dir().join("http")
+    }
+
     pub fn asset_cache_dir(&self) -> PathBuf {
         self.cache_dir().join("assets")
     }
@@ -74,13 +114,59 @@ impl PayloadLayout {
         self.asset_cache_dir().join(sha256).join(filename)
     }
 
+    /// Detached asset signature stored next to the cached file.
+    pub fn asset_signature_path(&self, sha256: &str, filename: &str) -> PathBuf {
+        self.asset_cache_dir()
+            .join(sha256)
+            .join(format!("{filename}.sig"))
+    }
+
+    /// Last verified manifest of `channel`, for when the distribution source is unreachable.
+    pub fn channel_manifest_cache_file(&self, channel: Channel) -> PathBuf {
+        self.cache_dir()
+            .join("manifests")
+            .join(format!("{}.json", channel.as_str()))
+    }
+
+    /// Signed manifests seeded from offline bundles, laid out as a directory distribution source.
+    pub fn offline_releases_dir(&self) -> PathBuf {
+        self.cache_dir().join("releases")
+    }
+
     pub fn version_dir(&self, tag: &str) -> PathBuf {
         self.versions_dir().join(tag)
     }
 
+    /// Record of the cached assets a version was installed from.
+    pub fn version_assets_file(&self, tag: &str) -> PathBuf {
+        self.version_dir(tag).join(VERSION_ASSETS_FILE)
+    }
+
+    /// Per-file sha256 inventory of an installed version.
+    pub fn version_inventory_file(&self, tag: &str) -> PathBuf {
+        self.version_dir(tag).join(VERSION_INVENTORY_FILE)
+    }
+
     pub fn version_staging_dir(&self, tag: &str) -> PathBuf {
         self.versions_dir().join(format!("{tag}.staging"))
     }
+
+    /// Scratch directory used while a version is rebuilt by a repair.
+    pub fn version_repair_dir(&self, tag: &str) -> PathBuf {
+        self.versions_dir().join(format!("{tag}.repair"))
+    }
+
+    /// Where the damaged copy is moved while a repair swaps in the rebuilt one.
+    pub fn version_replaced_dir(&self, tag: &str) -> PathBuf {
+        self.versions_dir().join(format!("{tag}.old"))
+    }
+}
+
+/// Whether a `versions/` entry is one of the scratch directories above rather than a version.
+pub fn is_version_scratch_dir(name: &str) -> bool {
+    [".staging", ".repair", ".old"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
 }
 
 fn parse_payload_root_override(s: &str) -> ApiResult<PathBuf> {
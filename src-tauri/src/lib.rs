@@ -1,13 +1,18 @@
-mod api_error;
+pub mod api_error;
+#[cfg(feature = "desktop")]
 mod commands;
-mod layout;
-mod models;
-mod services;
-mod state;
-mod storage;
+pub mod layout;
+pub mod models;
+pub mod services;
+pub mod state;
+pub mod storage;
+#[cfg(test)]
+mod test_support;
 
+#[cfg(feature = "desktop")]
 use tauri::Manager;
 
+#[cfg(feature = "desktop")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let app = tauri::Builder::default()
@@ -18,6 +23,7 @@ pub fn run() {
 
             let state = state::AppState::load(&app.handle())?;
             app.manage(state);
+            services::startup::apply_staged_app_update(app.handle());
 
             services::startup::spawn_autostart_install();
             services::tray::install(app)?;
@@ -34,8 +40,14 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .invoke_handler(tauri::generate_handler![
             commands::app_update::app_update_check,
+            commands::app_update::app_update_install,
             commands::app_update::app_update_open_latest,
+            commands::app_update::app_update_restart,
+            commands::app_update::app_update_rollback,
+            commands::distribution::latest_release_get,
+            commands::distribution::firmware_targets_get,
             commands::distribution::list_channel_tags,
+            commands::distribution::release_diff,
             commands::bridge::bridge_status_get,
             commands::bridge::bridge_log_open,
             commands::bridge_instances::bridge_instances_get,
@@ -46,6 +58,8 @@ pub fn run() {
             commands::bridge_instances::bridge_instance_artifact_source_set,
             commands::bridge_instances::bridge_instance_installed_release_set,
             commands::bridge_instances::bridge_instance_name_set,
+            commands::bridge_instances::bridge_instance_update_policy_set,
+            commands::bridge_instances::bridge_instance_version_range_set,
             commands::controller_fs::controller_fs_capabilities_get,
             commands::controller_fs::controller_fs_list,
             commands::controller_fs::controller_fs_mkdir,
@@ -57,7 +71,12 @@ pub fn run() {
             commands::flash::build_workspace_firmware,
             commands::flash::flash_bridge_instance,
             commands::flash::workspace_firmware_profiles,
+            commands::install::asset_cache_verify,
             commands::install::install_bridge_instance,
+            commands::install::install_history_get,
+            commands::install::install_rollback,
+            commands::install::installed_version_audit,
+            commands::install::installed_version_repair,
             commands::local_fs::local_fs_delete,
             commands::local_fs::local_fs_list,
             commands::local_fs::local_fs_mkdir,
@@ -65,11 +84,19 @@ pub fn run() {
             commands::midi::midi_inventory_get,
             commands::payload::file_copy_to_clipboard,
             commands::payload::path_open,
+            commands::payload::payload_gc,
             commands::payload::payload_root_relocate,
             commands::payload::url_open,
             commands::project_migration::project_migration_inspect,
             commands::project_migration::project_migration_migrate,
             commands::settings::tab_order_set,
+            commands::settings::distribution_source_set,
+            commands::settings::app_update_source_set,
+            commands::settings::download_settings_set,
+            commands::settings::network_settings_set,
+            commands::settings::update_check_settings_set,
+            commands::offline_bundle::release_bundle_import,
+            commands::offline_bundle::release_bundle_export,
             commands::status::status_get,
             commands::step_preset::step_preset_inspect,
             commands::step_preset::step_preset_rename,
@@ -88,6 +115,7 @@ pub fn run() {
     app.run(|app_handle, event| {
         if let tauri::RunEvent::Exit = event {
             services::ux_recorder::close_all_sessions(app_handle, "app_exit");
+            services::native_sim::stop_all();
         }
     });
 }
@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
 
 use ms_manager_core::{
-    ArtifactSource, BridgeInstanceBinding, BridgeInstancesState, Channel, FirmwareTarget,
-    InstallState, LastFlashed, Platform,
+    ArtifactSource, BridgeApp, BridgeInstanceBinding, BridgeInstancesState, BridgeMode, Channel,
+    DistributionSource, DownloadSettings, FirmwareTarget, FirmwareTargetInfo, InstallHistoryEntry,
+    InstallState, LastFlashed, ManifestDiff, NetworkSettings, Platform, UpdateCheckSettings,
+    UpdatePolicy, UpdatePolicyMode,
 };
 
 #[derive(Debug, Clone, Serialize)]
@@ -10,10 +12,13 @@ pub struct BridgeInstanceStatus {
     pub instance_id: String,
     pub display_name: Option<String>,
     pub configured_serial: String,
+    pub app: BridgeApp,
+    pub mode: BridgeMode,
     pub target: FirmwareTarget,
     pub artifact_source: ArtifactSource,
     pub installed_channel: Option<Channel>,
     pub installed_pinned_tag: Option<String>,
+    pub installed_version_range: Option<String>,
     pub artifacts_ready: bool,
     pub artifact_message: Option<String>,
     pub enabled: bool,
@@ -25,6 +30,10 @@ pub struct BridgeInstanceStatus {
     pub connected_serial: Option<String>,
     pub message: Option<String>,
     pub last_flashed: Option<LastFlashed>,
+    /// Latest release of the instance's channel found by the background update check.
+    pub latest_tag: Option<String>,
+    pub update_available: bool,
+    pub update_policy: UpdatePolicyMode,
     pub artifact_location_path: Option<String>,
     pub host_udp_port: u16,
     pub control_port: u16,
@@ -50,6 +59,8 @@ pub struct AssetPlan {
     pub sha256: String,
     pub size: u64,
     pub url: String,
+    pub description: Option<String>,
+    pub signature: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -59,6 +70,8 @@ pub struct InstallPlan {
     pub profile: String,
     pub platform: Platform,
     pub assets: Vec<AssetPlan>,
+    /// Release notes (markdown) from the manifest, if any.
+    pub notes: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -69,10 +82,16 @@ pub struct Status {
     pub artifact_config_path: Option<String>,
     pub artifact_message: Option<String>,
     pub tab_order: Vec<String>,
+    pub distribution_source: DistributionSource,
+    pub downloads: DownloadSettings,
+    pub network: NetworkSettingsView,
+    pub update_check: UpdateCheckSettings,
     pub platform: Platform,
     pub payload_root: String,
     pub device: DeviceStatus,
     pub bridge: BridgeStatus,
+    /// Cleanup done after an interrupted install, if any was needed.
+    pub recovery: Option<PayloadRecoveryReport>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -111,6 +130,19 @@ pub struct BridgeInstanceInstalledReleaseSetRequest {
     pub pinned_tag: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct BridgeInstanceVersionRangeSetRequest {
+    pub instance_id: String,
+    /// `None` goes back to the pinned tag, or the latest release when there is none.
+    pub version_range: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BridgeInstanceUpdatePolicySetRequest {
+    pub instance_id: String,
+    pub update_policy: UpdatePolicy,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BridgeInstanceNameSetRequest {
     pub instance_id: String,
@@ -132,6 +164,348 @@ pub struct TabOrderResponse {
     pub tab_order: Vec<String>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct LatestReleaseRequest {
+    pub channel: Channel,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatestRelease {
+    pub channel: Channel,
+    pub tag: String,
+    pub notes: Option<String>,
+    /// The source was unreachable; this is the channel's last verified manifest.
+    pub stale: bool,
+    /// When the stale manifest was fetched (unix ms).
+    pub fetched_at_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FirmwareTargetsRequest {
+    pub channel: Channel,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FirmwareTargetsResponse {
+    pub channel: Channel,
+    pub tag: String,
+    pub targets: Vec<FirmwareTargetInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseDiffRequest {
+    pub from_channel: Channel,
+    pub from_tag: String,
+    pub to_channel: Channel,
+    pub to_tag: String,
+    /// When set, `download_size` is computed for this install set on the current platform.
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseDiffResponse {
+    pub diff: ManifestDiff,
+    pub download_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetCacheVerifyRequest {
+    pub sha256: String,
+    pub filename: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetCacheVerifyResponse {
+    /// Key id of the stored detached signature; `None` when the asset was published unsigned.
+    pub key_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseBundleImportRequest {
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleInstallSet {
+    pub id: String,
+    pub os: Option<String>,
+    pub arch: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseBundleImportReport {
+    pub channel: Channel,
+    pub tag: String,
+    pub assets_imported: Vec<String>,
+    /// Install sets whose assets are all cached now, i.e. installable offline.
+    pub install_sets: Vec<BundleInstallSet>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseBundleExportRequest {
+    pub channel: Channel,
+    pub tag: String,
+    pub profiles: Vec<String>,
+    /// Defaults to the current platform.
+    #[serde(default)]
+    pub platform: Option<Platform>,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseBundleExportReport {
+    pub channel: Channel,
+    pub tag: String,
+    pub path: String,
+    pub assets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallHistoryResponse {
+    /// Oldest first.
+    pub entries: Vec<InstallHistoryEntry>,
+}
+
+/// Install step recorded in the install journal before it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallJournalPhase {
+    /// Building `versions/<tag>.staging`.
+    Stage,
+    /// Renaming the staging dir to `versions/<tag>`.
+    Promote,
+    /// Re-pointing `current`.
+    SwitchCurrent,
+    /// Building a repaired copy in `versions/<tag>.repair`.
+    RepairStage,
+    /// Swapping a repaired copy in for `versions/<tag>`.
+    RepairSwap,
+}
+
+/// What the payload recovery pass found and did when the payload state was loaded.
+#[derive(Debug, Clone, Serialize)]
+pub struct PayloadRecoveryReport {
+    /// Step that was cut short, when the journal still recorded one.
+    pub interrupted: Option<InstallJournalPhase>,
+    pub interrupted_tag: Option<String>,
+    /// Leftover scratch directories that were deleted.
+    pub removed_dirs: Vec<String>,
+    /// Versions moved back from `<tag>.old` after a repair was cut short.
+    pub restored_versions: Vec<String>,
+    /// Release `current` was re-pointed to.
+    pub current_restored: Option<String>,
+    pub failed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InstalledVersionRequest {
+    /// Defaults to the active release.
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionAuditReport {
+    pub tag: String,
+    pub ok: bool,
+    pub files_checked: usize,
+    pub missing: Vec<String>,
+    pub modified: Vec<String>,
+    /// Files that were not part of the install.
+    pub extra: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PayloadGcRequest {
+    /// Report what would be removed without deleting anything.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Newest installed versions kept per channel, on top of every referenced version.
+    #[serde(default = "default_gc_keep_per_channel")]
+    pub keep_per_channel: usize,
+}
+
+pub const DEFAULT_GC_KEEP_PER_CHANNEL: usize = 2;
+
+fn default_gc_keep_per_channel() -> usize {
+    DEFAULT_GC_KEEP_PER_CHANNEL
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GcKeepReason {
+    Current,
+    InstallState,
+    /// Release `install_rollback` would switch back to.
+    RollbackTarget,
+    Pinned {
+        instance_id: String,
+    },
+    ChannelResolved {
+        instance_id: String,
+    },
+    Retention,
+    /// The directory name is not a release tag; it is never collected.
+    UnknownTag,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GcKeptVersion {
+    pub tag: String,
+    pub reasons: Vec<GcKeepReason>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GcRemovedEntry {
+    /// Version tag or asset sha256.
+    pub name: String,
+    pub path: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GcFailure {
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PayloadGcReport {
+    pub dry_run: bool,
+    pub kept_versions: Vec<GcKeptVersion>,
+    pub removed_versions: Vec<GcRemovedEntry>,
+    pub removed_blobs: Vec<GcRemovedEntry>,
+    pub failed: Vec<GcFailure>,
+    /// Bytes freed, or that would be freed on a dry run.
+    pub bytes_freed: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistributionSourceSetRequest {
+    pub source: DistributionSource,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DistributionSourceResponse {
+    pub source: DistributionSource,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DownloadSettingsSetRequest {
+    pub downloads: DownloadSettings,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadSettingsResponse {
+    pub downloads: DownloadSettings,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateCheckSettingsSetRequest {
+    pub update_check: UpdateCheckSettings,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateCheckSettingsResponse {
+    pub update_check: UpdateCheckSettings,
+}
+
+/// Result of the last background update check.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateCheckState {
+    pub checked_at_ms: Option<u64>,
+    pub channels: Vec<ChannelLatestTag>,
+    /// Highest tag within each range followed by an instance.
+    pub ranges: Vec<RangeLatestTag>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelLatestTag {
+    pub channel: Channel,
+    pub tag: Option<String>,
+    /// The source was unreachable and `tag` comes from the last verified manifest.
+    pub stale: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RangeLatestTag {
+    pub channel: Channel,
+    pub range: String,
+    pub tag: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstanceUpdateAvailability {
+    pub instance_id: String,
+    /// Release the instance runs: its pinned tag, else the active installed one.
+    pub current_tag: Option<String>,
+    pub latest_tag: Option<String>,
+    pub update_available: bool,
+    pub update_policy: UpdatePolicyMode,
+}
+
+/// Payload of the update check event, emitted after every background check.
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateCheckEvent {
+    pub check: UpdateCheckState,
+    pub instances: Vec<InstanceUpdateAvailability>,
+}
+
+/// Outcome of an update the instance's policy applied without user action.
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoUpdateEvent {
+    pub instance_id: String,
+    pub tag: String,
+    pub installed: bool,
+    pub flashed: bool,
+    pub error: Option<String>,
+}
+
+/// Details of an `http_rate_limited` error.
+#[derive(Debug, Clone, Serialize)]
+pub struct RateLimitedDetails {
+    pub url: String,
+    pub status: u16,
+    /// From `Retry-After`, else from `X-RateLimit-Reset`.
+    pub retry_after_secs: u64,
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// Unix seconds at which the limit window resets.
+    pub reset_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NetworkSettingsSetRequest {
+    pub network: NetworkSettings,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkSettingsResponse {
+    pub network: NetworkSettingsView,
+}
+
+/// Network settings as shown to the webview; the proxy password and GitHub token stay in the
+/// backend, only whether they are set is reported.
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkSettingsView {
+    pub proxy: Option<ProxySettingsView>,
+    pub extra_ca_certificates: Vec<String>,
+    pub connect_timeout_secs: u32,
+    pub read_timeout_secs: u32,
+    pub offline: bool,
+    pub github_token_set: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProxySettingsView {
+    pub url: String,
+    pub username: Option<String>,
+    pub password_set: bool,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct AppUpdateInfo {
     pub version: String,
@@ -146,6 +520,16 @@ pub struct AppUpdateStatus {
     pub available: bool,
     pub update: Option<AppUpdateInfo>,
     pub error: Option<String>,
+    /// The update can be installed in place (AppImage or tarball install with a package).
+    pub self_update: bool,
+    pub pending: AppUpdatePending,
+}
+
+/// Versions of this install waiting to be swapped in on the next start, or kept for rollback.
+#[derive(Debug, Clone, Serialize)]
+pub struct AppUpdatePending {
+    pub staged_version: Option<String>,
+    pub rollback_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -249,6 +633,7 @@ pub enum InstallEvent {
         tag: String,
         profile: String,
         assets_total: usize,
+        notes: Option<String>,
     },
     Downloading {
         index: usize,
@@ -256,6 +641,13 @@ pub enum InstallEvent {
         asset_id: String,
         filename: String,
     },
+    /// Combined byte progress of all asset downloads of the install.
+    DownloadProgress {
+        bytes_done: u64,
+        bytes_total: u64,
+        bytes_per_sec: u64,
+        eta_ms: Option<u64>,
+    },
     Applying {
         step: String,
     },
@@ -0,0 +1,542 @@
+use std::path::{Path, PathBuf};
+
+use ms_manager_core::{
+    channel_for_tag, verify_app_update, AppPackageKind, AppUpdateManifest, DistributionSource,
+    Keyring, Platform, APP_UPDATE_MANIFEST_FILE,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::api_error::{ApiError, ApiResult};
+use crate::layout::PayloadLayout;
+use crate::models::AssetPlan;
+use crate::services::assets::ensure_asset_cached;
+use crate::services::distribution;
+use crate::services::download::DownloadTracker;
+use crate::services::http_client::HttpClient;
+use crate::storage::{read_json_optional, write_json_atomic};
+
+/// Marks a directory extracted from a release tarball. Only such directories are replaced in
+/// place; installs owned by a package manager are left to it.
+pub const TARBALL_INSTALL_MARKER: &str = ".ms-manager-tarball";
+
+const APP_UPDATE_RECORD_SCHEMA: u32 = 1;
+
+/// A running ms-manager that can replace itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppInstall {
+    pub kind: AppPackageKind,
+    /// The AppImage file, or the directory the tarball was extracted to.
+    pub target: PathBuf,
+    /// What to start once an update has been swapped in.
+    pub executable: PathBuf,
+}
+
+/// A copy of ms-manager sitting next to the install it can replace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StagedApp {
+    pub version: String,
+    pub kind: AppPackageKind,
+    pub target: PathBuf,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AppUpdateRecord {
+    schema: u32,
+    /// Swapped in on the next start.
+    #[serde(default)]
+    staged: Option<StagedApp>,
+    /// The version replaced by the last update, kept for rollback.
+    #[serde(default)]
+    previous: Option<StagedApp>,
+}
+
+/// How the running app was installed, when it is an AppImage or an extracted tarball on Linux.
+pub fn detect_install() -> Option<AppInstall> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    if let Some(appimage) = std::env::var_os("APPIMAGE")
+        .map(PathBuf::from)
+        .filter(|path| path.is_file())
+    {
+        return Some(AppInstall {
+            kind: AppPackageKind::Appimage,
+            target: appimage.clone(),
+            executable: appimage,
+        });
+    }
+    let exe = std::env::current_exe().ok()?;
+    let dir = exe.parent()?;
+    dir.join(TARBALL_INSTALL_MARKER)
+        .is_file()
+        .then(|| AppInstall {
+            kind: AppPackageKind::Tarball,
+            target: dir.to_path_buf(),
+            executable: exe.clone(),
+        })
+}
+
+/// Fetch and verify the newest app update manifest; `None` when the source does not publish one.
+pub async fn fetch_app_update(
+    client: &HttpClient,
+    source: &DistributionSource,
+    keyring: &Keyring,
+) -> ApiResult<Option<AppUpdateManifest>> {
+    let manifest_url = source.latest_release_file_url(APP_UPDATE_MANIFEST_FILE);
+    let sig_url = format!("{manifest_url}.sig");
+
+    let (status, bytes) = distribution::fetch_bytes(client, &manifest_url).await?;
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(distribution::http_status_error(&manifest_url, status));
+    }
+    let (status, sig_text) = distribution::fetch_text(client, &sig_url).await?;
+    if !status.is_success() {
+        return Err(distribution::http_status_error(&sig_url, status));
+    }
+
+    Ok(Some(verify_app_update(keyring, &bytes, &sig_text)?))
+}
+
+/// Download the package of `manifest` that fits `install`, check it against the signed sha256
+/// and stage it next to the install, ready to be swapped in on the next start.
+pub async fn stage_update(
+    client: &HttpClient,
+    layout: &PayloadLayout,
+    keyring: &Keyring,
+    source: &DistributionSource,
+    install: &AppInstall,
+    manifest: &AppUpdateManifest,
+) -> ApiResult<StagedApp> {
+    let platform = Platform::current()?;
+    let package = manifest
+        .package_for(install.kind, platform.os.as_str(), platform.arch.as_str())
+        .ok_or_else(|| {
+            ApiError::new(
+                "app_update_no_package",
+                format!(
+                    "ms-manager {} has no {} package for {}/{}",
+                    manifest.version,
+                    install.kind.as_str(),
+                    platform.os.as_str(),
+                    platform.arch.as_str()
+                ),
+            )
+        })?;
+    let tag = manifest.tag();
+    let channel = channel_for_tag(&tag).ok_or_else(|| {
+        ApiError::new(
+            "app_update_manifest_invalid",
+            format!("no channel for {tag}"),
+        )
+    })?;
+
+    // The manifest signature covers the sha256, which the download is checked against.
+    let plan = AssetPlan {
+        id: format!("ms-manager-{}", install.kind.as_str()),
+        kind: install.kind.as_str().to_string(),
+        filename: package.filename.clone(),
+        sha256: package.sha256.clone(),
+        size: package.size,
+        url: package
+            .url
+            .clone()
+            .unwrap_or_else(|| source.asset_url_for_tag(&tag, &package.filename)),
+        description: None,
+        signature: None,
+    };
+    let cached = ensure_asset_cached(
+        client,
+        layout,
+        keyring,
+        channel,
+        &plan,
+        &DownloadTracker::silent(),
+    )
+    .await?;
+
+    let staged_path = sibling(&install.target, "staged");
+    let install_for_task = install.clone();
+    let staged_for_task = staged_path.clone();
+    tokio::task::spawn_blocking(move || {
+        unpack_package(&install_for_task, &cached, &staged_for_task)
+    })
+    .await
+    .map_err(|e| ApiError::new("internal_error", format!("staging task failed: {e}")))??;
+
+    let staged = StagedApp {
+        version: manifest.version.clone(),
+        kind: install.kind,
+        target: install.target.clone(),
+        path: staged_path,
+    };
+    let mut record = read_record(layout);
+    record.staged = Some(staged.clone());
+    write_record(layout, &record)?;
+    Ok(staged)
+}
+
+/// Stage the version kept by the last update, so the next start swaps back to it.
+pub fn stage_rollback(layout: &PayloadLayout, install: &AppInstall) -> ApiResult<StagedApp> {
+    let mut record = read_record(layout);
+    let previous = record
+        .previous
+        .clone()
+        .filter(|previous| previous.target == install.target && previous.path.exists())
+        .ok_or_else(|| {
+            ApiError::new(
+                "app_rollback_unavailable",
+                "no previous ms-manager version is kept",
+            )
+        })?;
+    record.staged = Some(previous.clone());
+    write_record(layout, &record)?;
+    Ok(previous)
+}
+
+/// Versions staged for `install` and kept for its rollback.
+pub fn pending_versions(
+    layout: &PayloadLayout,
+    install: &AppInstall,
+) -> (Option<String>, Option<String>) {
+    let record = read_record(layout);
+    let version_for = |app: Option<StagedApp>| {
+        app.filter(|app| app.target == install.target && app.path.exists())
+            .map(|app| app.version)
+    };
+    (version_for(record.staged), version_for(record.previous))
+}
+
+/// Swap the staged app in for `install` and keep the replaced one for rollback.
+///
+/// Returns the version swapped in, or `None` when nothing is staged for this install. The
+/// running process keeps its open files, so the caller restarts into `install.executable`.
+pub fn apply_staged_update(
+    layout: &PayloadLayout,
+    install: &AppInstall,
+    current_version: &str,
+) -> ApiResult<Option<String>> {
+    let mut record = read_record(layout);
+    let Some(staged) = record
+        .staged
+        .take()
+        .filter(|staged| staged.target == install.target && staged.kind == install.kind)
+    else {
+        return Ok(None);
+    };
+    if !staged.path.exists() {
+        write_record(layout, &record)?;
+        return Err(ApiError::new(
+            "app_update_staged_missing",
+            format!("staged ms-manager {} is gone", staged.version),
+        )
+        .with_details(serde_json::json!({ "path": staged.path.display().to_string() })));
+    }
+
+    // Stage the replaced copy aside first: when the staged one is the rollback copy, it sits
+    // where the replaced one is about to be kept.
+    let target = &install.target;
+    let previous = sibling(target, "previous");
+    let replaced = sibling(target, "replaced");
+    remove_path(&replaced)?;
+    rename(target, &replaced)?;
+    if let Err(err) = rename(&staged.path, target) {
+        let _ = rename(&replaced, target);
+        return Err(err);
+    }
+    remove_path(&previous)?;
+    rename(&replaced, &previous)?;
+
+    record.previous = Some(StagedApp {
+        version: current_version.to_string(),
+        kind: install.kind,
+        target: target.clone(),
+        path: previous,
+    });
+    write_record(layout, &record)?;
+    Ok(Some(staged.version))
+}
+
+/// Start `install` again with the current arguments; the caller exits right after.
+pub fn relaunch(install: &AppInstall) -> ApiResult<()> {
+    std::process::Command::new(&install.executable)
+        .args(std::env::args_os().skip(1))
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| {
+            ApiError::new(
+                "app_relaunch_failed",
+                format!("start {}: {e}", install.executable.display()),
+            )
+        })
+}
+
+fn unpack_package(install: &AppInstall, package: &Path, dest: &Path) -> ApiResult<()> {
+    remove_path(dest)?;
+    match install.kind {
+        AppPackageKind::Appimage => {
+            std::fs::copy(package, dest).map_err(|e| {
+                ApiError::new(
+                    "io_copy_failed",
+                    format!("copy {} -> {}: {e}", package.display(), dest.display()),
+                )
+            })?;
+            set_executable(dest)
+        }
+        AppPackageKind::Tarball => {
+            let file = std::fs::File::open(package).map_err(|e| {
+                ApiError::new("io_read_failed", format!("open {}: {e}", package.display()))
+            })?;
+            let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+            if let Err(e) = archive.unpack(dest) {
+                let _ = remove_path(dest);
+                return Err(ApiError::new(
+                    "app_update_package_invalid",
+                    format!("extract {}: {e}", package.display()),
+                ));
+            }
+
+            // A tarball without the executable would leave nothing to restart into.
+            let exe_name = install.executable.file_name().unwrap_or_default();
+            if !dest.join(exe_name).is_file() {
+                let _ = remove_path(dest);
+                return Err(ApiError::new(
+                    "app_update_package_invalid",
+                    format!(
+                        "{} does not contain {}",
+                        package.display(),
+                        Path::new(exe_name).display()
+                    ),
+                ));
+            }
+            let marker = dest.join(TARBALL_INSTALL_MARKER);
+            std::fs::write(&marker, b"").map_err(|e| {
+                ApiError::new(
+                    "io_write_failed",
+                    format!("write {}: {e}", marker.display()),
+                )
+            })
+        }
+    }
+}
+
+/// `<target>.<suffix>`, next to `target` so swaps are renames within one filesystem.
+fn sibling(target: &Path, suffix: &str) -> PathBuf {
+    let mut name = target.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{suffix}"));
+    target.with_file_name(name)
+}
+
+fn read_record(layout: &PayloadLayout) -> AppUpdateRecord {
+    read_json_optional::<AppUpdateRecord>(&layout.app_update_state_file())
+        .ok()
+        .flatten()
+        .filter(|record| record.schema == APP_UPDATE_RECORD_SCHEMA)
+        .unwrap_or_default()
+}
+
+fn write_record(layout: &PayloadLayout, record: &AppUpdateRecord) -> ApiResult<()> {
+    write_json_atomic(
+        &layout.app_update_state_file(),
+        &AppUpdateRecord {
+            schema: APP_UPDATE_RECORD_SCHEMA,
+            ..record.clone()
+        },
+    )
+}
+
+fn rename(from: &Path, to: &Path) -> ApiResult<()> {
+    std::fs::rename(from, to).map_err(|e| {
+        ApiError::new(
+            "io_rename_failed",
+            format!("rename {} -> {}: {e}", from.display(), to.display()),
+        )
+    })
+}
+
+fn remove_path(path: &Path) -> ApiResult<()> {
+    let result = match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => std::fs::remove_dir_all(path),
+        Ok(_) => std::fs::remove_file(path),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => Err(e),
+    };
+    result.map_err(|e| {
+        ApiError::new(
+            "io_remove_failed",
+            format!("remove {}: {e}", path.display()),
+        )
+    })
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> ApiResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .map_err(|e| ApiError::new("io_write_failed", format!("chmod {}: {e}", path.display())))
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> ApiResult<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::engine::general_purpose::STANDARD as B64;
+    use base64::Engine;
+    use ed25519_dalek::{Signer, SigningKey};
+    use ms_manager_core::{sha256_hex, Channel, TrustedKey};
+
+    use super::*;
+    use crate::services::http_client::build_client;
+    use crate::test_support::TestRoot;
+
+    /// Publish a signed release of `version` with one package in a directory source.
+    fn publish(
+        release_root: &Path,
+        key: &SigningKey,
+        version: &str,
+        kind: AppPackageKind,
+        filename: &str,
+        package: &[u8],
+    ) {
+        let platform = Platform::current().unwrap();
+        let tag_dir = release_root.join(format!("v{version}"));
+        std::fs::create_dir_all(&tag_dir).unwrap();
+        std::fs::write(tag_dir.join(filename), package).unwrap();
+        let manifest = serde_json::to_vec(&serde_json::json!({
+            "schema": 1,
+            "version": version,
+            "packages": [{
+                "kind": kind,
+                "os": platform.os.as_str(),
+                "arch": platform.arch.as_str(),
+                "filename": filename,
+                "size": package.len(),
+                "sha256": sha256_hex(package),
+            }],
+        }))
+        .unwrap();
+        let signature = format!("app:{}", B64.encode(key.sign(&manifest).to_bytes()));
+        std::fs::write(release_root.join(APP_UPDATE_MANIFEST_FILE), &manifest).unwrap();
+        std::fs::write(
+            release_root.join(format!("{APP_UPDATE_MANIFEST_FILE}.sig")),
+            signature,
+        )
+        .unwrap();
+    }
+
+    fn keyring(key: &SigningKey) -> Keyring {
+        Keyring::new(vec![TrustedKey {
+            id: "app".to_string(),
+            public_key_b64: B64.encode(key.verifying_key().to_bytes()),
+            channels: Channel::ALL.to_vec(),
+        }])
+    }
+
+    #[test]
+    fn appimage_update_is_staged_swapped_and_rolled_back() {
+        let root = TestRoot::new("app-update");
+        let release_root = root.path().join("releases");
+        let key = SigningKey::from_bytes(&[7; 32]);
+        publish(
+            &release_root,
+            &key,
+            "9.0.0",
+            AppPackageKind::Appimage,
+            "ms-manager-9.0.0.AppImage",
+            b"new app",
+        );
+        let layout = root.layout();
+        let source = DistributionSource::Directory {
+            path: release_root.display().to_string(),
+        };
+        let target = root.path().join("ms-manager.AppImage");
+        std::fs::write(&target, b"old app").unwrap();
+        let install = AppInstall {
+            kind: AppPackageKind::Appimage,
+            target: target.clone(),
+            executable: target.clone(),
+        };
+        let client = HttpClient::new(build_client(&Default::default()).unwrap());
+        let keyring = keyring(&key);
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let manifest = runtime
+            .block_on(fetch_app_update(&client, &source, &keyring))
+            .unwrap()
+            .unwrap();
+        assert!(manifest.is_newer_than("1.0.0"));
+        runtime
+            .block_on(stage_update(
+                &client, &layout, &keyring, &source, &install, &manifest,
+            ))
+            .unwrap();
+        assert_eq!(
+            pending_versions(&layout, &install),
+            (Some("9.0.0".to_string()), None)
+        );
+        assert_eq!(std::fs::read(&target).unwrap(), b"old app");
+
+        let applied = apply_staged_update(&layout, &install, "1.0.0").unwrap();
+        assert_eq!(applied.as_deref(), Some("9.0.0"));
+        assert_eq!(std::fs::read(&target).unwrap(), b"new app");
+        assert_eq!(
+            pending_versions(&layout, &install),
+            (None, Some("1.0.0".to_string()))
+        );
+        assert_eq!(
+            apply_staged_update(&layout, &install, "9.0.0").unwrap(),
+            None
+        );
+
+        stage_rollback(&layout, &install).unwrap();
+        let applied = apply_staged_update(&layout, &install, "9.0.0").unwrap();
+        assert_eq!(applied.as_deref(), Some("1.0.0"));
+        assert_eq!(std::fs::read(&target).unwrap(), b"old app");
+        assert_eq!(
+            pending_versions(&layout, &install),
+            (None, Some("9.0.0".to_string()))
+        );
+    }
+
+    #[test]
+    fn tarball_without_the_executable_is_not_staged() {
+        let root = TestRoot::new("app-update");
+        let package = root.path().join("ms-manager.tar.gz");
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            std::fs::File::create(&package).unwrap(),
+            flate2::Compression::default(),
+        ));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(3);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "ms-manager", &b"new"[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let install = |exe: &str| AppInstall {
+            kind: AppPackageKind::Tarball,
+            target: root.path().join("app"),
+            executable: root.path().join("app").join(exe),
+        };
+        let dest = root.path().join("app.staged");
+        let err = unpack_package(&install("ms-manager-bin"), &package, &dest).unwrap_err();
+        assert_eq!(err.code, "app_update_package_invalid");
+        assert!(!dest.exists());
+
+        unpack_package(&install("ms-manager"), &package, &dest).unwrap();
+        assert_eq!(std::fs::read(dest.join("ms-manager")).unwrap(), b"new");
+        assert!(dest.join(TARBALL_INSTALL_MARKER).is_file());
+    }
+}
@@ -11,11 +11,11 @@ use crate::services::artifact_paths::{
 use crate::services::installed_artifacts::{
     installed_artifact_health, installed_artifact_health_for_binding, installed_core_file_tool_exe,
     installed_firmware_for_profile, installed_loader_exe, installed_loader_exe_for_tag,
-    installed_oc_bridge_exe, installed_oc_bridge_exe_for_tag, installed_version_dir_for_binding,
-    resolve_installed_tag,
+    installed_native_sim_exe_for_tag, installed_oc_bridge_exe, installed_oc_bridge_exe_for_tag,
+    installed_version_dir_for_binding, resolve_installed_tag,
 };
 use crate::services::workspace_artifacts::{
-    load_workspace_artifacts, workspace_artifact_health, workspace_artifact_health_for_target,
+    load_workspace_artifacts, workspace_artifact_health, workspace_artifact_health_for_binding,
 };
 
 pub const DEV_ARTIFACTS_SCHEMA: u32 = 1;
@@ -48,7 +48,7 @@ pub fn artifact_health_for_binding(
         ArtifactSource::Installed => {
             installed_artifact_health_for_binding(layout, installed, binding)
         }
-        ArtifactSource::Workspace => workspace_artifact_health_for_target(binding.target),
+        ArtifactSource::Workspace => workspace_artifact_health_for_binding(binding),
     }
 }
 
@@ -151,7 +151,7 @@ pub fn resolve_firmware_for_binding(
             installed_firmware_for_profile(layout, tag, binding.target.profile_id())
         }
         ArtifactSource::Workspace => {
-            resolve_firmware_for_target(layout, installed, binding.artifact_source, binding.target)
+            resolve_firmware_for_target(layout, installed, binding.artifact_source, &binding.target)
         }
     }
 }
@@ -165,9 +165,11 @@ pub fn artifact_location_for_binding(
         ArtifactSource::Installed => installed_version_dir_for_binding(layout, installed, binding),
         ArtifactSource::Workspace => load_workspace_artifacts()
             .ok()
-            .map(|workspace| match binding.target {
-                FirmwareTarget::Standalone => workspace.firmware_standalone,
-                FirmwareTarget::Bitwig => workspace.firmware_bitwig,
+            .and_then(|workspace| {
+                workspace
+                    .firmware_for(&binding.target)
+                    .ok()
+                    .map(|(_, path)| path.clone())
             })
             .map(|path| {
                 path.parent()
@@ -187,6 +189,22 @@ pub fn artifact_location_for_binding(
     }
 }
 
+/// Simulator executable a `native_sim` instance runs, from the same source as its bridge.
+pub fn resolve_native_sim_exe_for_binding(
+    layout: &PayloadLayout,
+    binding: &BridgeInstanceBinding,
+) -> ApiResult<PathBuf> {
+    match binding.artifact_source {
+        ArtifactSource::Installed => {
+            let path =
+                installed_native_sim_exe_for_tag(layout, binding.installed_pinned_tag.as_deref());
+            ensure_file_exists("native_sim_exe", &path)?;
+            Ok(path)
+        }
+        ArtifactSource::Workspace => load_workspace_artifacts()?.native_sim().cloned(),
+    }
+}
+
 pub fn ui_path_string(path: &std::path::Path) -> String {
     format_ui_path_string(path)
 }
@@ -195,7 +213,7 @@ pub fn resolve_firmware_for_target(
     layout: &PayloadLayout,
     installed: Option<&InstallState>,
     artifact_source: ArtifactSource,
-    target: FirmwareTarget,
+    target: &FirmwareTarget,
 ) -> ApiResult<PathBuf> {
     match artifact_source {
         ArtifactSource::Installed => {
@@ -206,23 +224,61 @@ pub fn resolve_firmware_for_target(
         }
         ArtifactSource::Workspace => {
             let workspace = load_workspace_artifacts()?;
-            let (key, path) = match target {
-                FirmwareTarget::Standalone => {
-                    ("firmware_standalone", workspace.firmware_standalone)
-                }
-                FirmwareTarget::Bitwig => ("firmware_bitwig", workspace.firmware_bitwig),
-            };
-            ensure_file_exists(key, &path)?;
-            Ok(path)
+            let (key, path) = workspace.firmware_for(target)?;
+            ensure_file_exists(key, path)?;
+            Ok(path.clone())
         }
     }
 }
 
+/// Extension the instance's host loads: the workspace build, or the directory a release
+/// installs it to. `None` when the host needs no extension.
 #[allow(dead_code)]
-pub fn resolve_bitwig_extension() -> ApiResult<PathBuf> {
-    let path = load_workspace_artifacts()?.bitwig_extension;
-    ensure_file_exists("bitwig_extension", &path)?;
-    Ok(path)
+pub fn resolve_host_extension(
+    layout: &PayloadLayout,
+    installed: Option<&InstallState>,
+    binding: &BridgeInstanceBinding,
+) -> ApiResult<Option<PathBuf>> {
+    let descriptor = binding.app.descriptor();
+    let Some(kind) = descriptor.extension_asset_kind else {
+        return Ok(None);
+    };
+    match binding.artifact_source {
+        ArtifactSource::Installed => {
+            let dir = descriptor.extension_install_dir.ok_or_else(|| {
+                ApiError::new(
+                    "internal_error",
+                    format!("no install location for {kind} extensions"),
+                )
+            })?;
+            let path = installed_version_dir_for_binding(layout, installed, binding).join(dir);
+            if !path.is_dir() {
+                return Err(ApiError::new(
+                    "artifact_missing",
+                    format!(
+                        "{} extension is not installed: {}",
+                        descriptor.label,
+                        path.display()
+                    ),
+                ));
+            }
+            Ok(Some(path))
+        }
+        ArtifactSource::Workspace => {
+            let workspace = load_workspace_artifacts()?;
+            let (key, path) = workspace.extension_for(kind).ok_or_else(|| {
+                ApiError::new(
+                    "artifact_missing",
+                    format!(
+                        "workspace builds do not provide the {} extension",
+                        descriptor.label
+                    ),
+                )
+            })?;
+            ensure_file_exists(key, path)?;
+            Ok(Some(path.clone()))
+        }
+    }
 }
 
 #[cfg(test)]
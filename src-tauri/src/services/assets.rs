@@ -1,12 +1,21 @@
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
+use ms_manager_core::{local_path_for_location, Channel, Keyring};
+use reqwest::header::{HeaderMap, HeaderName, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
 use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use tokio::io::AsyncWriteExt;
 
 use crate::api_error::{ApiError, ApiResult};
 use crate::layout::PayloadLayout;
 use crate::models::AssetPlan;
+use crate::services::download::DownloadTracker;
+
+/// How often an in-flight download records a resume checkpoint.
+const RESUME_CHECKPOINT_BYTES: u64 = 4 * 1024 * 1024;
 
 #[derive(Debug, Clone)]
 pub struct CachedAsset {
@@ -14,10 +23,92 @@ pub struct CachedAsset {
     pub path: PathBuf,
 }
 
+/// Fetch every asset of a plan into the cache, up to `concurrency` at a time.
+///
+/// Results keep the order of `assets`. The first failure aborts the remaining downloads; their
+/// partial files stay behind and are resumed by the next attempt.
+pub async fn cache_assets(
+    client: &reqwest::Client,
+    layout: &PayloadLayout,
+    keyring: &Keyring,
+    channel: Channel,
+    assets: &[AssetPlan],
+    concurrency: usize,
+    progress: Arc<DownloadTracker>,
+) -> ApiResult<Vec<CachedAsset>> {
+    let assets: Arc<[AssetPlan]> = assets.into();
+    let next = Arc::new(AtomicUsize::new(0));
+    let mut workers = tokio::task::JoinSet::new();
+    for _ in 0..concurrency.clamp(1, assets.len().max(1)) {
+        let (client, layout, keyring) = (client.clone(), layout.clone(), keyring.clone());
+        let (assets, next, progress) = (assets.clone(), next.clone(), progress.clone());
+        workers.spawn(async move {
+            let mut cached = Vec::new();
+            loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(asset) = assets.get(index) else {
+                    return Ok::<_, ApiError>(cached);
+                };
+                progress.started(index + 1, &asset.id, &asset.filename);
+                let path =
+                    ensure_asset_cached(&client, &layout, &keyring, channel, asset, &progress)
+                        .await?;
+                cached.push((
+                    index,
+                    CachedAsset {
+                        plan: asset.clone(),
+                        path,
+                    },
+                ));
+            }
+        });
+    }
+
+    let mut cached = Vec::with_capacity(assets.len());
+    while let Some(joined) = workers.join_next().await {
+        let done = joined
+            .map_err(|e| ApiError::new("internal_error", format!("download task failed: {e}")))??;
+        cached.extend(done);
+    }
+    progress.finish();
+    cached.sort_by_key(|(index, _)| *index);
+    Ok(cached.into_iter().map(|(_, asset)| asset).collect())
+}
+
+/// Offline mode: fail up front when an asset would have to be downloaded.
+///
+/// Only the cache entry's presence is checked here; `ensure_asset_cached` still verifies it.
+pub fn ensure_available_offline(layout: &PayloadLayout, assets: &[AssetPlan]) -> ApiResult<()> {
+    let missing = assets
+        .iter()
+        .filter(|asset| local_path_for_location(&asset.url).is_none())
+        .filter(|asset| {
+            !layout
+                .asset_cache_path(&asset.sha256, &asset.filename)
+                .is_file()
+        })
+        .map(|asset| asset.filename.clone())
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(ApiError::new(
+        "offline_assets_missing",
+        format!(
+            "offline mode: not cached yet: {}; import a release bundle or go online",
+            missing.join(", ")
+        ),
+    )
+    .with_details(serde_json::json!({ "assets": missing })))
+}
+
 pub async fn ensure_asset_cached(
     client: &reqwest::Client,
     layout: &PayloadLayout,
+    keyring: &Keyring,
+    channel: Channel,
     asset: &AssetPlan,
+    progress: &DownloadTracker,
 ) -> ApiResult<PathBuf> {
     if asset.sha256.is_empty() {
         return Err(ApiError::new("asset_invalid", "missing sha256"));
@@ -37,6 +128,14 @@ pub async fn ensure_asset_cached(
             if meta.len() == asset.size {
                 let got = sha256_file_hex(&dest)?;
                 if got == asset.sha256 {
+                    verify_plan_signature(keyring, channel, asset)?;
+                    write_asset_signature(
+                        layout,
+                        &asset.sha256,
+                        &asset.filename,
+                        asset.signature.as_deref(),
+                    )?;
+                    progress.skip(asset.size);
                     return Ok(dest);
                 }
             }
@@ -59,12 +158,9 @@ pub async fn ensure_asset_cached(
         )
     })?;
 
+    // A `.download` left by an interrupted fetch is resumed when its checkpoint still matches.
     let tmp = dest.with_extension("download");
-    if tmp.exists() {
-        let _ = std::fs::remove_file(&tmp);
-    }
-
-    download_verify_to_file(client, &asset.url, asset.size, &asset.sha256, &tmp).await?;
+    download_verify_to_file(client, keyring, channel, asset, &tmp, progress).await?;
 
     // Best-effort: remove any previous file.
     if dest.exists() {
@@ -76,50 +172,280 @@ pub async fn ensure_asset_cached(
             format!("rename {} -> {}: {e}", tmp.display(), dest.display()),
         )
     })?;
+    write_asset_signature(
+        layout,
+        &asset.sha256,
+        &asset.filename,
+        asset.signature.as_deref(),
+    )?;
 
     Ok(dest)
 }
 
+/// Re-verify a cached asset on its own: its content must still hash to `sha256`, and when a
+/// signature sidecar was stored next to it, that signature must come from an active key.
+///
+/// Returns the signing key id, or `None` for assets that were published without a signature.
+/// `sha256` and `filename` may come from the webview, so they must name an entry inside the cache.
+pub fn verify_cached_asset(
+    layout: &PayloadLayout,
+    keyring: &Keyring,
+    sha256: &str,
+    filename: &str,
+) -> ApiResult<Option<String>> {
+    validate_asset_request(sha256, filename)?;
+    let path = layout.asset_cache_path(sha256, filename);
+    let size = std::fs::metadata(&path)
+        .map_err(|e| ApiError::new("io_read_failed", format!("stat {}: {e}", path.display())))?
+        .len();
+    let got = sha256_file_hex(&path)?;
+    if got != sha256 {
+        return Err(ApiError::new(
+            "asset_sha256_mismatch",
+            format!("cached sha256 {got}, expected {sha256}"),
+        )
+        .with_details(serde_json::json!({ "path": path.display().to_string() })));
+    }
+
+    let sig_path = layout.asset_signature_path(sha256, filename);
+    let signature = match std::fs::read_to_string(&sig_path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(ApiError::new(
+                "io_read_failed",
+                format!("read {}: {e}", sig_path.display()),
+            ))
+        }
+    };
+    verify_asset_signature(keyring, None, filename, size, sha256, Some(&signature))
+}
+
+fn validate_asset_request(sha256: &str, filename: &str) -> ApiResult<()> {
+    let sha256_ok = sha256.len() == 64
+        && sha256
+            .bytes()
+            .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+    if !sha256_ok {
+        return Err(ApiError::new(
+            "asset_request_invalid",
+            format!("sha256 must be 64 lowercase hex characters, got {sha256:?}"),
+        ));
+    }
+
+    let mut components = Path::new(filename).components();
+    let plain = !filename.contains(['/', '\\'])
+        && matches!(components.next(), Some(std::path::Component::Normal(_)))
+        && components.next().is_none();
+    if !plain {
+        return Err(ApiError::new(
+            "asset_request_invalid",
+            format!("filename must be a plain file name, got {filename:?}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Check an optional detached asset signature against the keyring.
+pub(crate) fn verify_asset_signature(
+    keyring: &Keyring,
+    channel: Option<Channel>,
+    filename: &str,
+    size: u64,
+    sha256: &str,
+    signature: Option<&str>,
+) -> ApiResult<Option<String>> {
+    let Some(signature) = signature else {
+        return Ok(None);
+    };
+    keyring
+        .verify_asset(channel, filename, size, sha256, signature)
+        .map(Some)
+        .map_err(|e| {
+            ApiError::new("asset_signature_invalid", format!("asset {filename}: {e}"))
+                .with_details(serde_json::json!({ "filename": filename, "sha256": sha256 }))
+        })
+}
+
+/// Store the asset signature next to the cached file so it can be re-verified later.
+pub(crate) fn write_asset_signature(
+    layout: &PayloadLayout,
+    sha256: &str,
+    filename: &str,
+    signature: Option<&str>,
+) -> ApiResult<()> {
+    let Some(signature) = signature else {
+        return Ok(());
+    };
+    let path = layout.asset_signature_path(sha256, filename);
+    std::fs::write(&path, signature.trim())
+        .map_err(|e| ApiError::new("io_write_failed", format!("write {}: {e}", path.display())))
+}
+
+fn verify_plan_signature(keyring: &Keyring, channel: Channel, asset: &AssetPlan) -> ApiResult<()> {
+    verify_asset_signature(
+        keyring,
+        Some(channel),
+        &asset.filename,
+        asset.size,
+        &asset.sha256,
+        asset.signature.as_deref(),
+    )?;
+    Ok(())
+}
+
 async fn download_verify_to_file(
+    client: &reqwest::Client,
+    keyring: &Keyring,
+    channel: Channel,
+    asset: &AssetPlan,
+    dest: &Path,
+    progress: &DownloadTracker,
+) -> ApiResult<()> {
+    match local_path_for_location(&asset.url) {
+        Some(local) => {
+            discard_partial(dest);
+            copy_verify_to_file(Path::new(local), asset.size, &asset.sha256, dest).await?;
+            progress.skip(asset.size);
+        }
+        None => {
+            fetch_verify_to_file(
+                client,
+                &asset.url,
+                asset.size,
+                &asset.sha256,
+                dest,
+                progress,
+            )
+            .await?
+        }
+    }
+
+    // The signature covers the digest, so this runs once the content is known to match it.
+    if let Err(err) = verify_plan_signature(keyring, channel, asset) {
+        let _ = tokio::fs::remove_file(dest).await;
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Checkpoint of an interrupted download, stored next to its `.download` file.
+///
+/// `partial_sha256` covers the first `received` bytes, so a resumed download can rebuild the
+/// running hash and reject a partial file that was torn or modified in the meantime.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ResumeState {
+    url: String,
+    size: u64,
+    sha256: String,
+    /// `ETag` or `Last-Modified` of the original response, sent back as `If-Range`.
+    validator: Option<String>,
+    received: u64,
+    partial_sha256: String,
+}
+
+struct Resume {
+    state: ResumeState,
+    hasher: Sha256,
+}
+
+async fn fetch_verify_to_file(
     client: &reqwest::Client,
     url: &str,
     expected_size: u64,
     expected_sha256: &str,
     dest: &Path,
+    progress: &DownloadTracker,
 ) -> ApiResult<()> {
-    let res = client
-        .get(url)
-        .send()
-        .await
-        .map_err(|e| ApiError::new("http_request_failed", format!("GET {url}: {e}")))?;
+    let mut resume = load_resume(dest, url, expected_size, expected_sha256);
 
-    let status = res.status();
-    if !status.is_success() {
-        return Err(http_status_error(url, status));
-    }
+    let (res, resumed) = loop {
+        let mut req = client.get(url);
+        if let Some(resume) = &resume {
+            req = req.header(RANGE, format!("bytes={}-", resume.state.received));
+            if let Some(validator) = &resume.state.validator {
+                req = req.header(IF_RANGE, validator);
+            }
+        }
+        let res = req
+            .send()
+            .await
+            .map_err(|e| ApiError::new("http_request_failed", format!("GET {url}: {e}")))?;
 
-    let mut file = tokio::fs::File::create(dest)
-        .await
-        .map_err(|e| ApiError::new("io_write_failed", format!("create {}: {e}", dest.display())))?;
+        let status = res.status();
+        match resume.take() {
+            Some(resume)
+                if status == StatusCode::PARTIAL_CONTENT
+                    && content_range_start(res.headers()) == Some(resume.state.received) =>
+            {
+                break (res, Some(resume));
+            }
+            // A full response means the server ignored the range or the resource changed.
+            Some(_) if status == StatusCode::OK => {
+                discard_partial(dest);
+                break (res, None);
+            }
+            // Unusable range answer (416, mismatched Content-Range): start over without it.
+            Some(_) => discard_partial(dest),
+            None if status.is_success() => break (res, None),
+            None => return Err(http_status_error(url, status)),
+        }
+    };
 
-    let mut hasher = Sha256::new();
-    let mut written: u64 = 0;
+    let (mut file, mut state, mut hasher) = match resumed {
+        Some(Resume { state, hasher }) => {
+            progress.skip(state.received);
+            (open_partial(dest, state.received).await?, state, hasher)
+        }
+        None => {
+            let file = tokio::fs::File::create(dest).await.map_err(|e| {
+                ApiError::new("io_write_failed", format!("create {}: {e}", dest.display()))
+            })?;
+            let state = ResumeState {
+                url: url.to_string(),
+                size: expected_size,
+                sha256: expected_sha256.to_string(),
+                validator: range_validator(res.headers()),
+                received: 0,
+                partial_sha256: String::new(),
+            };
+            (file, state, Sha256::new())
+        }
+    };
 
+    let mut written = state.received;
+    let mut checkpoint_at = written.saturating_add(RESUME_CHECKPOINT_BYTES);
     let mut res = res;
-    while let Some(chunk) = res
-        .chunk()
-        .await
-        .map_err(|e| ApiError::new("http_read_failed", format!("read {url}: {e}")))?
-    {
+    loop {
+        let chunk = match res.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(e) => {
+                // Keep what arrived so the next attempt only asks for the rest.
+                file.flush().await.ok();
+                write_checkpoint(dest, &mut state, written, &hasher);
+                return Err(ApiError::new(
+                    "http_read_failed",
+                    format!("read {url}: {e}"),
+                ));
+            }
+        };
         hasher.update(&chunk);
         file.write_all(&chunk).await.map_err(|e| {
             ApiError::new("io_write_failed", format!("write {}: {e}", dest.display()))
         })?;
         written = written.saturating_add(chunk.len() as u64);
+        progress.transferred(chunk.len() as u64).await;
+        if written >= checkpoint_at {
+            file.flush().await.ok();
+            write_checkpoint(dest, &mut state, written, &hasher);
+            checkpoint_at = written.saturating_add(RESUME_CHECKPOINT_BYTES);
+        }
     }
 
     file.flush().await.ok();
     drop(file);
+    let _ = std::fs::remove_file(resume_state_path(dest));
 
     if expected_size != 0 && written != expected_size {
         let _ = tokio::fs::remove_file(dest).await;
@@ -141,7 +467,143 @@ async fn download_verify_to_file(
     Ok(())
 }
 
-fn sha256_file_hex(path: &Path) -> ApiResult<String> {
+fn resume_state_path(partial: &Path) -> PathBuf {
+    let mut name = partial.as_os_str().to_owned();
+    name.push(".resume.json");
+    PathBuf::from(name)
+}
+
+/// Remove a partial download and its checkpoint.
+fn discard_partial(partial: &Path) {
+    let _ = std::fs::remove_file(partial);
+    let _ = std::fs::remove_file(resume_state_path(partial));
+}
+
+/// Pick up an interrupted download of the same asset, or clear whatever is left of it.
+fn load_resume(partial: &Path, url: &str, size: u64, sha256: &str) -> Option<Resume> {
+    let resume = std::fs::read(resume_state_path(partial))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<ResumeState>(&bytes).ok())
+        .filter(|state| {
+            state.url == url
+                && state.size == size
+                && state.sha256 == sha256
+                && state.received > 0
+                && (size == 0 || state.received < size)
+        })
+        .and_then(|state| {
+            let hasher = sha256_prefix(partial, state.received)?;
+            (digest_hex_lower(hasher.clone().finalize()) == state.partial_sha256)
+                .then_some(Resume { state, hasher })
+        });
+    if resume.is_none() {
+        discard_partial(partial);
+    }
+    resume
+}
+
+/// Hash the first `len` bytes of `path`, or `None` when the file is shorter or unreadable.
+fn sha256_prefix(path: &Path, len: u64) -> Option<Sha256> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut reader = std::io::Read::take(file, len);
+    let mut hasher = Sha256::new();
+    let read = std::io::copy(&mut reader, &mut hasher).ok()?;
+    (read == len).then_some(hasher)
+}
+
+/// Best-effort: a missing checkpoint only costs a full download next time.
+fn write_checkpoint(partial: &Path, state: &mut ResumeState, received: u64, hasher: &Sha256) {
+    if received == 0 {
+        return;
+    }
+    state.received = received;
+    state.partial_sha256 = digest_hex_lower(hasher.clone().finalize());
+    if let Ok(bytes) = serde_json::to_vec(state) {
+        let _ = std::fs::write(resume_state_path(partial), bytes);
+    }
+}
+
+/// Open a partial download for appending, dropping anything past the last checkpoint.
+async fn open_partial(partial: &Path, received: u64) -> ApiResult<tokio::fs::File> {
+    let io_err = |e: std::io::Error| {
+        ApiError::new(
+            "io_write_failed",
+            format!("open {}: {e}", partial.display()),
+        )
+    };
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .open(partial)
+        .map_err(io_err)?;
+    file.set_len(received).map_err(io_err)?;
+    drop(file);
+    tokio::fs::OpenOptions::new()
+        .append(true)
+        .open(partial)
+        .await
+        .map_err(io_err)
+}
+
+/// Start offset of a `Content-Range: bytes <start>-<end>/<total>` header.
+fn content_range_start(headers: &HeaderMap) -> Option<u64> {
+    let value = headers.get(CONTENT_RANGE)?.to_str().ok()?;
+    let range = value.trim().strip_prefix("bytes ")?;
+    let (start, _) = range.split_once('-')?;
+    start.trim().parse().ok()
+}
+
+/// `If-Range` needs a strong validator; weak ETags never match a range request.
+fn range_validator(headers: &HeaderMap) -> Option<String> {
+    let header = |name: HeaderName| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    header(ETAG)
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header(LAST_MODIFIED))
+}
+
+/// Local-directory counterpart of `fetch_verify_to_file`.
+async fn copy_verify_to_file(
+    src: &Path,
+    expected_size: u64,
+    expected_sha256: &str,
+    dest: &Path,
+) -> ApiResult<()> {
+    tokio::fs::copy(src, dest).await.map_err(|e| {
+        ApiError::new(
+            "io_copy_failed",
+            format!("copy {} -> {}: {e}", src.display(), dest.display()),
+        )
+    })?;
+
+    let written = tokio::fs::metadata(dest)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0);
+    if expected_size != 0 && written != expected_size {
+        let _ = tokio::fs::remove_file(dest).await;
+        return Err(ApiError::new(
+            "asset_size_mismatch",
+            format!("copied {written} bytes, expected {expected_size}"),
+        ));
+    }
+
+    let got = sha256_file_hex(dest)?;
+    if got != expected_sha256 {
+        let _ = tokio::fs::remove_file(dest).await;
+        return Err(ApiError::new(
+            "asset_sha256_mismatch",
+            format!("copied sha256 {got}, expected {expected_sha256}"),
+        ));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn sha256_file_hex(path: &Path) -> ApiResult<String> {
     let mut f = std::fs::File::open(path)
         .map_err(|e| ApiError::new("io_read_failed", format!("open {}: {e}", path.display())))?;
 
@@ -161,7 +623,7 @@ fn sha256_file_hex(path: &Path) -> ApiResult<String> {
     Ok(digest_hex_lower(hasher.finalize()))
 }
 
-fn digest_hex_lower(digest: impl AsRef<[u8]>) -> String {
+pub(crate) fn digest_hex_lower(digest: impl AsRef<[u8]>) -> String {
     const LUT: &[u8; 16] = b"0123456789abcdef";
     let b = digest.as_ref();
     let mut out = String::with_capacity(b.len() * 2);
@@ -176,3 +638,344 @@ fn http_status_error(url: &str, status: StatusCode) -> ApiError {
     ApiError::new("http_status", format!("GET {url}: {status}"))
         .with_details(serde_json::json!({"url": url, "status": status.as_u16()}))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tokio::io::AsyncReadExt;
+    use tokio::net::{TcpListener, TcpStream};
+
+    use super::*;
+    use crate::services::download::DownloadEvent;
+    use crate::test_support::TestRoot;
+
+    #[test]
+    fn asset_request_must_name_an_entry_inside_the_cache() {
+        let sha = "a".repeat(64);
+        let upper = "A".repeat(64);
+        assert!(validate_asset_request(&sha, "bundle.zip").is_ok());
+
+        for (sha256, filename) in [
+            (sha.as_str(), "../bundle.zip"),
+            (sha.as_str(), "nested/bundle.zip"),
+            (sha.as_str(), "nested\\bundle.zip"),
+            (sha.as_str(), ".."),
+            (sha.as_str(), ""),
+            (sha.as_str(), "/etc/passwd"),
+            (&sha[..63], "bundle.zip"),
+            (upper.as_str(), "bundle.zip"),
+        ] {
+            let err = validate_asset_request(sha256, filename).unwrap_err();
+            assert_eq!(err.code, "asset_request_invalid", "{sha256:?} {filename:?}");
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    enum Reply {
+        Full,
+        /// Announce the whole body but close the connection after this many bytes.
+        CutAfter(usize),
+        /// Answer range requests with the full body, like a server without range support.
+        IgnoreRange,
+    }
+
+    #[test]
+    fn interrupted_download_resumes_with_range() {
+        run_async(async {
+            let body = test_body();
+            let (url, seen) = serve(body.clone(), vec![Reply::CutAfter(4000), Reply::Full]).await;
+            let root = TestRoot::new("assets-resume");
+            let dest = root.path().join("asset.download");
+            let sha = digest_hex_lower(Sha256::digest(&body));
+
+            let err = fetch_verify_to_file(
+                &client(),
+                &url,
+                body.len() as u64,
+                &sha,
+                &dest,
+                &DownloadTracker::silent(),
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(err.code, "http_read_failed");
+            assert_eq!(std::fs::metadata(&dest).unwrap().len(), 4000);
+            assert!(resume_state_path(&dest).exists());
+
+            fetch_verify_to_file(
+                &client(),
+                &url,
+                body.len() as u64,
+                &sha,
+                &dest,
+                &DownloadTracker::silent(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(std::fs::read(&dest).unwrap(), body);
+            assert!(!resume_state_path(&dest).exists());
+            assert_eq!(
+                *seen.lock().unwrap(),
+                vec![None, Some("bytes=4000-".to_string())]
+            );
+        });
+    }
+
+    #[test]
+    fn server_ignoring_ranges_falls_back_to_full_download() {
+        run_async(async {
+            let body = test_body();
+            let (url, seen) = serve(
+                body.clone(),
+                vec![Reply::CutAfter(4000), Reply::IgnoreRange],
+            )
+            .await;
+            let root = TestRoot::new("assets-ignore-range");
+            let dest = root.path().join("asset.download");
+            let sha = digest_hex_lower(Sha256::digest(&body));
+
+            assert!(fetch_verify_to_file(
+                &client(),
+                &url,
+                body.len() as u64,
+                &sha,
+                &dest,
+                &DownloadTracker::silent()
+            )
+            .await
+            .is_err());
+            fetch_verify_to_file(
+                &client(),
+                &url,
+                body.len() as u64,
+                &sha,
+                &dest,
+                &DownloadTracker::silent(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(std::fs::read(&dest).unwrap(), body);
+            assert_eq!(seen.lock().unwrap()[1].as_deref(), Some("bytes=4000-"));
+        });
+    }
+
+    #[test]
+    fn torn_partial_is_discarded_before_resuming() {
+        run_async(async {
+            let body = test_body();
+            let (url, seen) = serve(body.clone(), vec![Reply::CutAfter(4000), Reply::Full]).await;
+            let root = TestRoot::new("assets-torn");
+            let dest = root.path().join("asset.download");
+            let sha = digest_hex_lower(Sha256::digest(&body));
+
+            assert!(fetch_verify_to_file(
+                &client(),
+                &url,
+                body.len() as u64,
+                &sha,
+                &dest,
+                &DownloadTracker::silent()
+            )
+            .await
+            .is_err());
+            let mut partial = std::fs::read(&dest).unwrap();
+            partial[10] ^= 0xff;
+            std::fs::write(&dest, partial).unwrap();
+
+            fetch_verify_to_file(
+                &client(),
+                &url,
+                body.len() as u64,
+                &sha,
+                &dest,
+                &DownloadTracker::silent(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(std::fs::read(&dest).unwrap(), body);
+            assert_eq!(*seen.lock().unwrap(), vec![None, None]);
+        });
+    }
+
+    #[test]
+    fn cache_assets_downloads_concurrently_and_keeps_plan_order() {
+        run_async(async {
+            let body = test_body();
+            let (url, _) = serve(body.clone(), vec![Reply::Full; 3]).await;
+            let root = TestRoot::new("assets-cache-assets");
+            let layout = root.layout();
+            let sha = digest_hex_lower(Sha256::digest(&body));
+            let plan = (0..3)
+                .map(|i| AssetPlan {
+                    id: format!("asset-{i}"),
+                    kind: "bundle".to_string(),
+                    filename: format!("asset-{i}.bin"),
+                    sha256: sha.clone(),
+                    size: body.len() as u64,
+                    url: url.clone(),
+                    description: None,
+                    signature: None,
+                })
+                .collect::<Vec<_>>();
+
+            let events = Arc::new(Mutex::new(Vec::new()));
+            let sink = events.clone();
+            let progress = Arc::new(DownloadTracker::new(
+                3 * body.len() as u64,
+                None,
+                move |event| sink.lock().unwrap().push(event),
+            ));
+            let cached = cache_assets(
+                &client(),
+                &layout,
+                &Keyring::default(),
+                Channel::Stable,
+                &plan,
+                2,
+                progress,
+            )
+            .await
+            .unwrap();
+
+            let ids = cached
+                .iter()
+                .map(|c| c.plan.id.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(ids, ["asset-0", "asset-1", "asset-2"]);
+            let events = events.lock().unwrap();
+            let started = events
+                .iter()
+                .filter(|e| matches!(e, DownloadEvent::Started { .. }))
+                .count();
+            assert_eq!(started, 3);
+            let Some(DownloadEvent::Progress(last)) = events.last() else {
+                panic!("missing final progress event");
+            };
+            assert_eq!(last.bytes_done, 3 * body.len() as u64);
+            assert_eq!(last.bytes_total, 3 * body.len() as u64);
+        });
+    }
+
+    #[test]
+    fn offline_mode_needs_remote_assets_in_the_cache() {
+        let root = TestRoot::new("assets-offline");
+        let layout = root.layout();
+        let asset = |filename: &str, url: String| AssetPlan {
+            id: filename.to_string(),
+            kind: "firmware".to_string(),
+            filename: filename.to_string(),
+            sha256: "ab".repeat(32),
+            size: 1,
+            url,
+            description: None,
+            signature: None,
+        };
+        let local = format!("file://{}", root.path().join("local.hex").display());
+        let plan = vec![
+            asset(
+                "cached.hex",
+                "https://example.invalid/cached.hex".to_string(),
+            ),
+            asset(
+                "remote.hex",
+                "https://example.invalid/remote.hex".to_string(),
+            ),
+            asset("local.hex", local),
+        ];
+        let cached = layout.asset_cache_path(&plan[0].sha256, &plan[0].filename);
+        std::fs::create_dir_all(cached.parent().unwrap()).unwrap();
+        std::fs::write(&cached, "x").unwrap();
+
+        let err = ensure_available_offline(&layout, &plan).unwrap_err();
+        assert_eq!(err.code, "offline_assets_missing");
+        assert_eq!(
+            err.details.unwrap()["assets"],
+            serde_json::json!(["remote.hex"])
+        );
+        assert!(ensure_available_offline(&layout, &plan[..1]).is_ok());
+    }
+
+    fn test_body() -> Vec<u8> {
+        (0..10_000u32).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn client() -> reqwest::Client {
+        reqwest::Client::builder().no_proxy().build().unwrap()
+    }
+
+    /// Minimal HTTP/1.1 stand-in: one connection per reply, recording each request's `Range`.
+    async fn serve(
+        body: Vec<u8>,
+        replies: Vec<Reply>,
+    ) -> (String, Arc<Mutex<Vec<Option<String>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/asset.bin", listener.local_addr().unwrap());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        tokio::spawn(async move {
+            for reply in replies {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let range = read_range_header(&mut stream).await;
+                log.lock().unwrap().push(range.clone());
+
+                let start = match (reply, range) {
+                    (Reply::IgnoreRange, _) | (_, None) => None,
+                    (_, Some(range)) => range
+                        .strip_prefix("bytes=")
+                        .and_then(|r| r.strip_suffix('-'))
+                        .and_then(|r| r.parse::<usize>().ok()),
+                };
+                let (head, slice) = match start {
+                    Some(start) => (
+                        format!(
+                            "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {start}-{}/{}\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n",
+                            body.len() - start,
+                            body.len() - 1,
+                            body.len()
+                        ),
+                        &body[start..],
+                    ),
+                    None => (
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n",
+                            body.len()
+                        ),
+                        &body[..],
+                    ),
+                };
+                let len = match reply {
+                    Reply::CutAfter(n) => n.min(slice.len()),
+                    Reply::Full | Reply::IgnoreRange => slice.len(),
+                };
+                stream.write_all(head.as_bytes()).await.unwrap();
+                stream.write_all(&slice[..len]).await.unwrap();
+                let _ = stream.shutdown().await;
+            }
+        });
+        (url, seen)
+    }
+
+    async fn read_range_header(stream: &mut TcpStream) -> Option<String> {
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            let mut byte = [0u8; 1];
+            stream.read_exact(&mut byte).await.unwrap();
+            head.push(byte[0]);
+        }
+        String::from_utf8(head).unwrap().lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("range")
+                .then(|| value.trim().to_string())
+        })
+    }
+
+    fn run_async(future: impl std::future::Future<Output = ()>) {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(future);
+    }
+}
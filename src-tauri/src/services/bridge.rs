@@ -11,7 +11,9 @@ use tauri::Manager;
 
 use crate::layout::PayloadLayout;
 use crate::models::DeviceTargetKind;
-use crate::services::{artifact_resolver, bridge_ctl, bridge_instances, device, process};
+use crate::services::{
+    artifact_resolver, bridge_ctl, bridge_instances, device, native_sim, process,
+};
 use crate::state::AppState;
 
 const SUPERVISOR_START_DELAY: Duration = Duration::from_millis(300);
@@ -79,7 +81,7 @@ async fn auto_bind_single_serial_target(
         &controller_serial,
         controller_vid,
         controller_pid,
-        FirmwareTarget::Bitwig,
+        FirmwareTarget::bitwig(),
         ArtifactSource::Installed,
         Some(ms_manager_core::Channel::Stable),
         None,
@@ -113,12 +115,28 @@ fn single_serial_target(targets: &[crate::models::DeviceTarget]) -> Option<(Stri
 }
 
 async fn ensure_enabled_instances_running(layout: &PayloadLayout, bindings: &BridgeInstancesState) {
-    for binding in bindings.instances.iter().filter(|binding| binding.enabled) {
+    let enabled = bindings
+        .instances
+        .iter()
+        .filter(|binding| binding.enabled)
+        .collect::<Vec<_>>();
+    native_sim::stop_others(
+        &enabled
+            .iter()
+            .map(|binding| binding.instance_id.as_str())
+            .collect(),
+    );
+    for binding in enabled {
         ensure_bridge_instance_running(layout, binding).await;
     }
 }
 
 async fn ensure_bridge_instance_running(layout: &PayloadLayout, binding: &BridgeInstanceBinding) {
+    // A native_sim bridge talks to its simulator instead of a USB serial port. A failed start is
+    // kept by `native_sim` and shown in the instance status.
+    if native_sim::ensure_running(layout, binding).is_err() {
+        return;
+    }
     if bridge_instance_ready(binding, STATUS_TIMEOUT).await {
         return;
     }
@@ -321,7 +339,7 @@ async fn bridge_spawn_daemon(
 }
 
 fn daemon_args(binding: &BridgeInstanceBinding) -> Vec<String> {
-    vec![
+    let mut args = vec![
         "--daemon".to_string(),
         "--instance-id".to_string(),
         binding.instance_id.clone(),
@@ -333,7 +351,20 @@ fn daemon_args(binding: &BridgeInstanceBinding) -> Vec<String> {
         binding.control_port.to_string(),
         "--daemon-log-broadcast-port".to_string(),
         binding.log_broadcast_port.to_string(),
-    ]
+    ];
+    if binding.mode == BridgeMode::NativeSim {
+        args.push("--native-sim-port".to_string());
+        args.push(bridge_instances::native_sim_port(binding).to_string());
+    }
+    args.extend(
+        binding
+            .app
+            .descriptor()
+            .daemon_args
+            .iter()
+            .map(|arg| arg.to_string()),
+    );
+    args
 }
 
 async fn bridge_wait_ready(binding: &BridgeInstanceBinding, timeout: Duration) -> bool {
@@ -355,7 +386,7 @@ async fn bridge_wait_ready(binding: &BridgeInstanceBinding, timeout: Duration) -
 mod tests {
     use super::*;
     use crate::models::DeviceTarget;
-    use ms_manager_core::Channel;
+    use ms_manager_core::{Channel, UpdatePolicy};
 
     fn binding() -> BridgeInstanceBinding {
         BridgeInstanceBinding {
@@ -366,14 +397,16 @@ mod tests {
             controller_serial: "17081760".to_string(),
             controller_vid: 0x16C0,
             controller_pid: 0x0489,
-            target: FirmwareTarget::Bitwig,
+            target: FirmwareTarget::bitwig(),
             artifact_source: ArtifactSource::Installed,
             installed_channel: Some(Channel::Stable),
             installed_pinned_tag: None,
+            installed_version_range: None,
             host_udp_port: 9000,
             control_port: 7999,
             log_broadcast_port: 9999,
             enabled: true,
+            update_policy: UpdatePolicy::default(),
         }
     }
 
@@ -398,6 +431,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn native_sim_daemon_args_point_the_bridge_at_the_simulator() {
+        let mut sim = binding();
+        sim.mode = BridgeMode::NativeSim;
+        sim.control_port = 8003;
+        let args = daemon_args(&sim);
+        assert_eq!(args[args.len() - 2..], ["--native-sim-port", "8504"]);
+        assert!(args.iter().any(|arg| arg == "--serial-number"));
+    }
+
+    #[test]
+    fn daemon_args_end_with_host_app_args() {
+        let mut midi_host = binding();
+        midi_host.app = BridgeApp::MidiHost;
+        let args = daemon_args(&midi_host);
+        assert_eq!(args[args.len() - 2..], ["--host", "midi"]);
+        assert_eq!(args[..args.len() - 2], daemon_args(&binding())[..]);
+    }
+
     #[test]
     fn single_serial_target_requires_exactly_one_serial_device() {
         let target = DeviceTarget {
@@ -1,6 +1,7 @@
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::time::Duration;
 
+use ms_manager_core::BridgeInstancesState;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 
@@ -13,6 +14,30 @@ struct Request<'a> {
     cmd: &'a str,
 }
 
+/// Ask every enabled bridge instance to shut down and stop native simulators, then give them a
+/// moment to release files.
+pub async fn shutdown_enabled(instances: &BridgeInstancesState) {
+    super::native_sim::stop_all();
+
+    let mut ports = instances
+        .instances
+        .iter()
+        .filter(|binding| binding.enabled)
+        .map(|binding| binding.control_port)
+        .collect::<Vec<_>>();
+
+    ports.sort_unstable();
+    ports.dedup();
+
+    for port in ports {
+        let _ = send_command(port, "shutdown", Duration::from_secs(2)).await;
+    }
+
+    if !instances.instances.is_empty() {
+        tokio::time::sleep(Duration::from_millis(400)).await;
+    }
+}
+
 pub async fn send_command(
     port: u16,
     cmd: &str,
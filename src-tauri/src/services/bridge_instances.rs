@@ -1,6 +1,6 @@
 use ms_manager_core::{
     ArtifactSource, BridgeApp, BridgeInstanceBinding, BridgeInstancesState, BridgeMode, Channel,
-    FirmwareTarget,
+    FirmwareTarget, UpdatePolicy,
 };
 
 pub const HARDWARE_HOST_UDP_PORT_START: u16 = 9000;
@@ -9,11 +9,13 @@ pub const WASM_HOST_UDP_PORT_START: u16 = 9200;
 pub const HOST_UDP_PORT_RANGE: u16 = 8;
 pub const CONTROL_PORT_START: u16 = 7999;
 pub const LOG_BROADCAST_PORT_START: u16 = 9999;
+/// Ports the native simulators of `native_sim` instances listen on for their bridge.
+pub const NATIVE_SIM_PORT_START: u16 = 8500;
 
 pub fn derive_instance_id(app: &BridgeApp, mode: &BridgeMode, controller_serial: &str) -> String {
     format!(
         "{}-{}-{}",
-        app_slug(app),
+        app.descriptor().id,
         mode_slug(mode),
         controller_serial.trim()
     )
@@ -67,6 +69,7 @@ fn allocate_control_ports(state: &BridgeInstancesState) -> Result<(u16, u16), St
     Err("no free bridge control/log port pair available".to_string())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn build_binding(
     state: &BridgeInstancesState,
     app: BridgeApp,
@@ -106,17 +109,18 @@ pub fn build_binding(
         artifact_source,
         installed_channel,
         installed_pinned_tag,
+        installed_version_range: None,
         host_udp_port,
         control_port,
         log_broadcast_port,
         enabled: true,
+        update_policy: UpdatePolicy::default(),
     })
 }
 
-fn app_slug(app: &BridgeApp) -> &'static str {
-    match app {
-        BridgeApp::Bitwig => "bitwig",
-    }
+/// Port of the instance's native simulator, paired with its control port.
+pub fn native_sim_port(binding: &BridgeInstanceBinding) -> u16 {
+    NATIVE_SIM_PORT_START + binding.control_port.saturating_sub(CONTROL_PORT_START)
 }
 
 fn mode_slug(mode: &BridgeMode) -> &'static str {
@@ -140,7 +144,7 @@ mod tests {
             "17081760",
             0x16C0,
             0x0489,
-            FirmwareTarget::Bitwig,
+            FirmwareTarget::bitwig(),
             ArtifactSource::Installed,
             Some(Channel::Stable),
             None,
@@ -153,6 +157,34 @@ mod tests {
         assert_eq!(binding.log_broadcast_port, 9999);
     }
 
+    #[test]
+    fn midi_host_binding_sits_next_to_bitwig_for_the_same_controller() {
+        let mut state = BridgeInstancesState::default();
+        for app in [BridgeApp::Bitwig, BridgeApp::MidiHost] {
+            let binding = build_binding(
+                &state,
+                app,
+                BridgeMode::Hardware,
+                "17081760",
+                0x16C0,
+                0x0489,
+                FirmwareTarget::standalone(),
+                ArtifactSource::Installed,
+                Some(Channel::Stable),
+                None,
+            )
+            .unwrap();
+            state.instances.push(binding);
+        }
+
+        assert_eq!(
+            state.instances[1].instance_id,
+            "midi-host-hardware-17081760"
+        );
+        assert_eq!(state.instances[1].host_udp_port, 9001);
+        state.validate().unwrap();
+    }
+
     #[test]
     fn allocate_ports_skips_used_hardware_host_and_control_ports() {
         let state = BridgeInstancesState {
@@ -165,14 +197,16 @@ mod tests {
                 controller_serial: "17081760".to_string(),
                 controller_vid: 0x16C0,
                 controller_pid: 0x0489,
-                target: FirmwareTarget::Bitwig,
+                target: FirmwareTarget::bitwig(),
                 artifact_source: ArtifactSource::Installed,
                 installed_channel: Some(Channel::Stable),
                 installed_pinned_tag: None,
+                installed_version_range: None,
                 host_udp_port: 9000,
                 control_port: 7999,
                 log_broadcast_port: 9999,
                 enabled: true,
+                update_policy: UpdatePolicy::default(),
             }],
         };
 
@@ -192,14 +226,16 @@ mod tests {
                 controller_serial: "17081760".to_string(),
                 controller_vid: 0x16C0,
                 controller_pid: 0x0489,
-                target: FirmwareTarget::Bitwig,
+                target: FirmwareTarget::bitwig(),
                 artifact_source: ArtifactSource::Installed,
                 installed_channel: Some(Channel::Stable),
                 installed_pinned_tag: None,
+                installed_version_range: None,
                 host_udp_port: 9000,
                 control_port: 7999,
                 log_broadcast_port: 9999,
                 enabled: true,
+                update_policy: UpdatePolicy::default(),
             }],
         };
 
@@ -66,6 +66,39 @@ pub fn kill_oc_bridge_daemons(exe_path: &Path) -> usize {
     killed
 }
 
+/// Kill simulators started from `exe_path` for `instance_id`.
+pub fn kill_native_sims(exe_path: &Path, instance_id: &str) -> usize {
+    let exe_norm = norm_path(exe_path);
+
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessRefreshKind::new()
+            .with_exe(UpdateKind::Always)
+            .with_cmd(UpdateKind::Always),
+    );
+
+    let mut killed = 0;
+    for proc_ in sys.processes().values() {
+        let Some(exe) = proc_.exe() else {
+            continue;
+        };
+        if norm_path(exe) != exe_norm {
+            continue;
+        }
+        if !proc_
+            .cmd()
+            .windows(2)
+            .any(|pair| pair[0] == "--instance-id" && pair[1] == instance_id)
+        {
+            continue;
+        }
+        if proc_.kill() {
+            killed += 1;
+        }
+    }
+    killed
+}
+
 pub fn kill_all_oc_bridge_daemons() -> usize {
     let mut sys = System::new();
     sys.refresh_processes_specifics(
@@ -75,7 +108,7 @@ pub fn kill_all_oc_bridge_daemons() -> usize {
     );
 
     let mut killed = 0;
-    for (_pid, proc_) in sys.processes() {
+    for proc_ in sys.processes().values() {
         let name = proc_.name().to_ascii_lowercase();
         if name != "oc-bridge" && name != "oc-bridge.exe" {
             continue;
@@ -1,8 +1,10 @@
-use ms_manager_core::{BridgeInstanceBinding, BridgeInstancesState, ControllerState, InstallState};
+use ms_manager_core::{
+    BridgeInstanceBinding, BridgeInstancesState, BridgeMode, ControllerState, InstallState,
+};
 
 use crate::layout::PayloadLayout;
-use crate::models::{BridgeInstanceStatus, BridgeStatus};
-use crate::services::{artifact_resolver, bridge_ctl};
+use crate::models::{BridgeInstanceStatus, BridgeStatus, UpdateCheckState};
+use crate::services::{artifact_resolver, bridge_ctl, native_sim, update_check};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BridgeRuntimeState {
@@ -96,6 +98,7 @@ pub async fn bridge_status(
     installed: Option<&InstallState>,
     bindings: &BridgeInstancesState,
     controller_state: &ControllerState,
+    update_check: &UpdateCheckState,
 ) -> BridgeStatus {
     if bindings.instances.is_empty() {
         return BridgeStatus {
@@ -111,7 +114,10 @@ pub async fn bridge_status(
 
     let mut instances = Vec::with_capacity(bindings.instances.len());
     for binding in &bindings.instances {
-        instances.push(bridge_instance_status(layout, installed, binding, controller_state).await);
+        instances.push(
+            bridge_instance_status(layout, installed, binding, controller_state, update_check)
+                .await,
+        );
     }
 
     let running = instances.iter().any(|instance| instance.running);
@@ -142,6 +148,7 @@ async fn bridge_instance_status(
     installed: Option<&InstallState>,
     binding: &BridgeInstanceBinding,
     controller_state: &ControllerState,
+    update_check: &UpdateCheckState,
 ) -> BridgeInstanceStatus {
     let artifact_health =
         artifact_resolver::artifact_health_for_binding(layout, installed, binding);
@@ -152,6 +159,8 @@ async fn bridge_instance_status(
         controller_state,
         artifact_health,
     );
+    (status.latest_tag, status.update_available) =
+        update_check::update_for_binding(binding, installed, update_check);
 
     match bridge_ctl::send_command(
         binding.control_port,
@@ -161,6 +170,16 @@ async fn bridge_instance_status(
     .await
     {
         Ok(value) => apply_runtime_status(&mut status, binding, value),
+        Err(_)
+            if binding.mode == BridgeMode::NativeSim
+                && binding.enabled
+                && !native_sim::is_running(&binding.instance_id) =>
+        {
+            status.message = Some(match native_sim::last_spawn_error(&binding.instance_id) {
+                Some(error) => format!("simulator failed to start: {error}"),
+                None => "simulator is not running".to_string(),
+            });
+        }
         Err(error) => {
             status.message = Some(error);
         }
@@ -180,10 +199,13 @@ fn base_instance_status(
         instance_id: binding.instance_id.clone(),
         display_name: binding.display_name.clone(),
         configured_serial: binding.controller_serial.clone(),
-        target: binding.target,
+        app: binding.app,
+        mode: binding.mode.clone(),
+        target: binding.target.clone(),
         artifact_source: binding.artifact_source,
         installed_channel: binding.installed_channel,
         installed_pinned_tag: binding.installed_pinned_tag.clone(),
+        installed_version_range: binding.installed_version_range.clone(),
         artifacts_ready: artifact_health.ready,
         artifact_message: artifact_health.message,
         enabled: binding.enabled,
@@ -195,6 +217,9 @@ fn base_instance_status(
         connected_serial: None,
         message: None,
         last_flashed: controller_state.last_flashed_for_instance(&binding.instance_id),
+        latest_tag: None,
+        update_available: false,
+        update_policy: binding.update_policy.mode,
         artifact_location_path: Some(artifact_resolver::ui_path_string(
             &artifact_resolver::artifact_location_for_binding(layout, installed, binding),
         )),
@@ -232,7 +257,9 @@ fn preferred_instance_message(instance: &BridgeInstanceStatus) -> Option<String>
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ms_manager_core::{ArtifactSource, BridgeApp, BridgeMode, Channel, FirmwareTarget};
+    use ms_manager_core::{
+        ArtifactSource, BridgeApp, BridgeMode, Channel, FirmwareTarget, UpdatePolicy,
+    };
 
     fn binding() -> BridgeInstanceBinding {
         BridgeInstanceBinding {
@@ -243,14 +270,16 @@ mod tests {
             controller_serial: "17076520".to_string(),
             controller_vid: 0x16C0,
             controller_pid: 0x0489,
-            target: FirmwareTarget::Bitwig,
+            target: FirmwareTarget::bitwig(),
             artifact_source: ArtifactSource::Installed,
             installed_channel: Some(Channel::Stable),
             installed_pinned_tag: None,
+            installed_version_range: None,
             host_udp_port: 9000,
             control_port: 7999,
             log_broadcast_port: 9999,
             enabled: true,
+            update_policy: UpdatePolicy::default(),
         }
     }
 
@@ -6,6 +6,7 @@ use std::sync::atomic::{AtomicU16, AtomicU32, Ordering};
 use std::sync::{Mutex, MutexGuard, OnceLock};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use ms_manager_core::BridgeInstancesState;
 use serde::Serialize;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
@@ -66,6 +67,84 @@ impl std::error::Error for ControllerFsError {}
 
 pub type ControllerFsResult<T> = Result<T, ControllerFsError>;
 
+/// Bridge control port a controller FS request targets.
+///
+/// An explicit instance wins (and must agree with `control_port` when both are given); otherwise
+/// the explicit port, the default port when no instance is configured, or the single (enabled)
+/// configured instance.
+pub fn resolve_control_port(
+    bridge_state: &BridgeInstancesState,
+    instance_id: Option<&str>,
+    control_port: Option<u16>,
+) -> ControllerFsResult<u16> {
+    if control_port == Some(0) {
+        return Err(ControllerFsError::new(
+            "controller_fs_control_port_invalid",
+            "bridge control port cannot be 0",
+        ));
+    }
+
+    if let Some(instance_id) = instance_id {
+        let instance = bridge_state
+            .instances
+            .iter()
+            .find(|instance| instance.instance_id == instance_id)
+            .ok_or_else(|| {
+                ControllerFsError::new(
+                    "controller_fs_instance_missing",
+                    format!("bridge instance not found: {instance_id}"),
+                )
+            })?;
+        if let Some(requested_port) = control_port {
+            if requested_port != instance.control_port {
+                return Err(ControllerFsError::new(
+                    "controller_fs_endpoint_mismatch",
+                    format!(
+                        "bridge instance {instance_id} uses control port {}, not {requested_port}",
+                        instance.control_port
+                    ),
+                ));
+            }
+        }
+        return Ok(instance.control_port);
+    }
+
+    if let Some(port) = control_port {
+        return Ok(port);
+    }
+
+    if bridge_state.instances.is_empty() {
+        return Ok(DEFAULT_BRIDGE_CONTROL_PORT);
+    }
+
+    let enabled: Vec<_> = bridge_state
+        .instances
+        .iter()
+        .filter(|instance| instance.enabled)
+        .collect();
+    if enabled.len() == 1 {
+        return Ok(enabled[0].control_port);
+    }
+    if bridge_state.instances.len() == 1 {
+        return Ok(bridge_state.instances[0].control_port);
+    }
+
+    Err(ControllerFsError::new(
+        "controller_fs_instance_required",
+        "multiple bridge instances are configured; provide instance_id or control_port",
+    ))
+}
+
+/// Controller FS client over the bridge listening on `control_port`, with default transfer
+/// settings.
+pub fn client_for_port(control_port: u16) -> ControllerFsResult<ControllerFsClient> {
+    ControllerFsClient::new(
+        BridgeBinaryClient::new(control_port).with_timeout(DEFAULT_CONTROL_TIMEOUT),
+    )
+    .with_chunk_size(FS_RPC_MAX_CHUNK_SIZE)?
+    .with_read_pipeline_window(DEFAULT_READ_PIPELINE_WINDOW)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum FsMessageId {
@@ -17,10 +17,7 @@ pub async fn device_status(layout: &PayloadLayout) -> DeviceStatus {
         }
     };
 
-    let targets = match list_targets_with_loader(&loader).await {
-        Ok(targets) => targets,
-        Err(_) => Vec::new(),
-    };
+    let targets = list_targets_with_loader(&loader).await.unwrap_or_default();
 
     DeviceStatus {
         connected: !targets.is_empty(),
@@ -1,12 +1,26 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
 use ms_manager_core::{
-    compare_tags, extract_tags_from_releases_atom, is_tag_for_channel, latest_tag_for_channel,
-    latest_tag_for_channel_from_releases, manifest_sig_url_for_tag, manifest_url_for_tag,
-    parse_manifest_json, parse_releases_api_json, public_key_b64_for_channel,
-    stable_latest_manifest_url, stable_latest_sig_url, verify_manifest_sig_b64, Channel, Manifest,
-    ManifestChannel, DIST_REPO_SLUG,
+    builtin_keyring, channel_for_tag, compare_tags, extract_tags_from_releases_atom,
+    is_tag_for_channel, latest_tag_for_channel, latest_tag_for_channel_from_releases,
+    local_path_for_location, parse_manifest_json, parse_releases_api_json,
+    select_install_set_assets, Channel, DistributionSource, Keyring, Manifest, ManifestChannel,
+    Platform, ReleaseInfo, RevocationList, VersionRange, REVOCATION_LIST_SCHEMA,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::api_error::{ApiError, ApiResult};
+use crate::layout::PayloadLayout;
+use crate::models::{AssetPlan, InstallPlan};
+use crate::services::http_client::{HttpClient, HTTP_RATE_LIMITED};
+use crate::services::offline_bundle;
+use crate::storage::{read_json_optional, write_json_atomic};
+
+/// Version checked against a manifest's `min_manager_version`.
+pub const MANAGER_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub struct LatestManifest {
     pub available: bool,
@@ -16,14 +30,18 @@ pub struct LatestManifest {
 }
 
 pub async fn resolve_latest_manifest(
-    client: &reqwest::Client,
+    client: &HttpClient,
+    layout: &PayloadLayout,
+    source: &DistributionSource,
     channel: Channel,
 ) -> ApiResult<LatestManifest> {
-    // Stable: use /latest.
-    if channel == Channel::Stable {
-        let manifest_url = stable_latest_manifest_url();
-        let sig_url = stable_latest_sig_url();
+    let keyring = load_keyring(client, layout, source).await;
 
+    // Stable: use /latest when the source has one.
+    if let Some((manifest_url, sig_url)) = source
+        .latest_stable_manifest_urls()
+        .filter(|_| channel == Channel::Stable)
+    {
         let (m_status, m_bytes) = fetch_bytes(client, &manifest_url).await?;
         if m_status == reqwest::StatusCode::NOT_FOUND {
             return Ok(LatestManifest {
@@ -41,9 +59,11 @@ pub async fn resolve_latest_manifest(
             return Err(http_status_error(&sig_url, s_status));
         }
 
-        verify_manifest_sig_b64(&m_bytes, &sig_text, public_key_b64_for_channel(channel))?;
+        keyring.verify_manifest(channel, &m_bytes, &sig_text)?;
         let manifest = parse_manifest_json(&m_bytes)?;
         ensure_manifest_channel(channel, &manifest)?;
+        ensure_manifest_valid(&manifest)?;
+        store_channel_manifest(layout, channel, &manifest.tag, &m_bytes, &sig_text);
 
         return Ok(LatestManifest {
             available: true,
@@ -53,13 +73,13 @@ pub async fn resolve_latest_manifest(
         });
     }
 
-    // Beta: resolve tag first.
-    let tag = resolve_latest_tag(client, channel)
+    // Prerelease channels and mirrors: resolve tag first.
+    let tag = resolve_latest_tag(client, source, channel)
         .await?
         .ok_or_else(|| ApiError::new("no_releases", "no releases found for channel"))?;
 
-    let manifest_url = manifest_url_for_tag(&tag);
-    let sig_url = manifest_sig_url_for_tag(&tag);
+    let manifest_url = source.manifest_url_for_tag(&tag);
+    let sig_url = source.manifest_sig_url_for_tag(&tag);
 
     let (m_status, m_bytes) = fetch_bytes(client, &manifest_url).await?;
     if !m_status.is_success() {
@@ -71,15 +91,17 @@ pub async fn resolve_latest_manifest(
         return Err(http_status_error(&sig_url, s_status));
     }
 
-    verify_manifest_sig_b64(&m_bytes, &sig_text, public_key_b64_for_channel(channel))?;
+    keyring.verify_manifest(channel, &m_bytes, &sig_text)?;
     let manifest = parse_manifest_json(&m_bytes)?;
     ensure_manifest_channel(channel, &manifest)?;
+    ensure_manifest_valid(&manifest)?;
     if manifest.tag != tag {
         return Err(ApiError::new(
             "manifest_tag_mismatch",
             format!("expected tag {tag}, got {}", manifest.tag),
         ));
     }
+    store_channel_manifest(layout, channel, &tag, &m_bytes, &sig_text);
 
     Ok(LatestManifest {
         available: true,
@@ -90,22 +112,230 @@ pub async fn resolve_latest_manifest(
 }
 
 pub async fn resolve_manifest_for_tag(
-    client: &reqwest::Client,
+    client: &HttpClient,
+    layout: &PayloadLayout,
+    source: &DistributionSource,
     channel: Channel,
     tag: &str,
 ) -> ApiResult<LatestManifest> {
-    let manifest_url = manifest_url_for_tag(tag);
-    let sig_url = manifest_sig_url_for_tag(tag);
+    let Some(signed) = fetch_signed_manifest_for_tag(client, layout, source, channel, tag).await?
+    else {
+        return Ok(LatestManifest {
+            available: false,
+            tag: None,
+            manifest: None,
+            message: Some("Release not found.".to_string()),
+        });
+    };
+
+    Ok(LatestManifest {
+        available: true,
+        tag: Some(tag.to_string()),
+        manifest: Some(signed.manifest),
+        message: None,
+    })
+}
+
+async fn resolve_manifest(
+    client: &HttpClient,
+    layout: &PayloadLayout,
+    source: &DistributionSource,
+    channel: Channel,
+    tag: Option<&str>,
+) -> ApiResult<LatestManifest> {
+    match tag {
+        Some(t) => resolve_manifest_for_tag(client, layout, source, channel, t).await,
+        None => resolve_latest_manifest(client, layout, source, channel).await,
+    }
+}
+
+/// A resolved manifest and where it came from.
+pub struct AvailableManifest {
+    pub source: DistributionSource,
+    pub manifest: Manifest,
+    pub tag: String,
+    /// Set when the source was unreachable and this is the last verified manifest of the
+    /// channel, fetched at this time (unix ms).
+    pub stale_fetched_at_ms: Option<u64>,
+}
+
+/// Resolve a release manifest, falling back to imported release bundles or the channel's last
+/// verified manifest (whichever is newer) when the configured source is unreachable.
+///
+/// Only transport failures fall back. A manifest the source did serve but that fails its
+/// signature, key, validation or manager version checks is an error, never "last known".
+pub async fn resolve_available_manifest(
+    client: &HttpClient,
+    layout: &PayloadLayout,
+    source: &DistributionSource,
+    channel: Channel,
+    tag: Option<&str>,
+) -> ApiResult<AvailableManifest> {
+    let out = match resolve_manifest(client, layout, source, channel, tag).await {
+        Ok(out) => out,
+        Err(err) if is_source_unreachable(&err) => {
+            return resolve_unreachable(client, layout, source, channel, tag, err).await
+        }
+        Err(err) => return Err(err),
+    };
+    if !out.available {
+        return Err(ApiError::new(
+            "no_release_available",
+            out.message.unwrap_or_else(|| "no releases".to_string()),
+        ));
+    }
+
+    let manifest = out
+        .manifest
+        .ok_or_else(|| ApiError::new("internal_error", "missing manifest"))?;
+    let tag = out
+        .tag
+        .ok_or_else(|| ApiError::new("internal_error", "missing tag"))?;
+    Ok(AvailableManifest {
+        source: source.clone(),
+        manifest,
+        tag,
+        stale_fetched_at_ms: None,
+    })
+}
+
+/// Whether `err` means the source could not be reached, as opposed to answering badly.
+fn is_source_unreachable(err: &ApiError) -> bool {
+    match err.code.as_str() {
+        "http_request_failed" | "http_read_failed" | "local_read_failed" => true,
+        HTTP_RATE_LIMITED => true,
+        "http_status" => err
+            .details
+            .as_ref()
+            .and_then(|details| details.get("status"))
+            .and_then(|status| status.as_u64())
+            .is_some_and(|status| status >= 500),
+        _ => false,
+    }
+}
+
+async fn resolve_unreachable(
+    client: &HttpClient,
+    layout: &PayloadLayout,
+    source: &DistributionSource,
+    channel: Channel,
+    tag: Option<&str>,
+    err: ApiError,
+) -> ApiResult<AvailableManifest> {
+    let offline = offline_bundle::offline_source(layout);
+    let imported = match resolve_manifest(client, layout, &offline, channel, tag).await {
+        Ok(LatestManifest {
+            available: true,
+            manifest: Some(manifest),
+            tag: Some(tag),
+            ..
+        }) => Some(AvailableManifest {
+            source: offline,
+            manifest,
+            tag,
+            stale_fetched_at_ms: None,
+        }),
+        _ => None,
+    };
+    let keyring = load_keyring(client, layout, source).await;
+    let cached = load_channel_manifest(layout, &keyring, channel)
+        .filter(|cached| tag.is_none_or(|tag| tag == cached.manifest.tag))
+        .map(|cached| AvailableManifest {
+            source: source.clone(),
+            tag: cached.manifest.tag.clone(),
+            manifest: cached.manifest,
+            stale_fetched_at_ms: Some(cached.fetched_at_ms),
+        });
+
+    match (imported, cached) {
+        (Some(imported), Some(cached)) => {
+            let cached_newer =
+                compare_tags(channel, &cached.tag, &imported.tag).is_some_and(|ord| ord.is_gt());
+            Ok(if cached_newer { cached } else { imported })
+        }
+        (Some(found), None) | (None, Some(found)) => Ok(found),
+        (None, None) => Err(err),
+    }
+}
+
+/// Resolve the assets of install set `profile` for the current platform.
+pub async fn plan_install(
+    client: &HttpClient,
+    layout: &PayloadLayout,
+    source: &DistributionSource,
+    channel: Channel,
+    profile: &str,
+    tag: Option<&str>,
+) -> ApiResult<InstallPlan> {
+    let AvailableManifest {
+        source,
+        manifest,
+        tag,
+        ..
+    } = resolve_available_manifest(client, layout, source, channel, tag).await?;
+    manifest.check_manager_version(MANAGER_VERSION)?;
+    if profile.is_empty() {
+        return Err(ApiError::new("invalid_profile", "profile cannot be empty"));
+    }
+
+    let platform = Platform::current()?;
+    let assets = select_install_set_assets(
+        &manifest,
+        profile,
+        platform.os.as_str(),
+        platform.arch.as_str(),
+    )?;
+
+    let plans = assets
+        .into_iter()
+        .map(|a| AssetPlan {
+            id: a.id,
+            kind: a.kind,
+            filename: a.filename.clone(),
+            sha256: a.sha256,
+            size: a.size,
+            url: a
+                .url
+                .unwrap_or_else(|| source.asset_url_for_tag(&tag, &a.filename)),
+            description: a.description,
+            signature: a.signature,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(InstallPlan {
+        channel,
+        tag,
+        profile: profile.to_string(),
+        platform,
+        assets: plans,
+        notes: manifest.notes,
+    })
+}
+
+/// A verified manifest together with the exact bytes and signature it was verified from.
+pub struct SignedManifest {
+    pub manifest: Manifest,
+    pub manifest_bytes: Vec<u8>,
+    pub sig_text: String,
+}
+
+/// Fetch and verify the manifest of `tag`; `None` when the source does not have it.
+pub async fn fetch_signed_manifest_for_tag(
+    client: &HttpClient,
+    layout: &PayloadLayout,
+    source: &DistributionSource,
+    channel: Channel,
+    tag: &str,
+) -> ApiResult<Option<SignedManifest>> {
+    let keyring = load_keyring(client, layout, source).await;
+
+    let manifest_url = source.manifest_url_for_tag(tag);
+    let sig_url = source.manifest_sig_url_for_tag(tag);
 
     let (m_status, m_bytes) = fetch_bytes(client, &manifest_url).await?;
     if !m_status.is_success() {
         if m_status == reqwest::StatusCode::NOT_FOUND {
-            return Ok(LatestManifest {
-                available: false,
-                tag: None,
-                manifest: None,
-                message: Some("Release not found.".to_string()),
-            });
+            return Ok(None);
         }
         return Err(http_status_error(&manifest_url, m_status));
     }
@@ -115,9 +345,10 @@ pub async fn resolve_manifest_for_tag(
         return Err(http_status_error(&sig_url, s_status));
     }
 
-    verify_manifest_sig_b64(&m_bytes, &sig_text, public_key_b64_for_channel(channel))?;
+    keyring.verify_manifest(channel, &m_bytes, &sig_text)?;
     let manifest = parse_manifest_json(&m_bytes)?;
     ensure_manifest_channel(channel, &manifest)?;
+    ensure_manifest_valid(&manifest)?;
     if manifest.tag != tag {
         return Err(ApiError::new(
             "manifest_tag_mismatch",
@@ -125,49 +356,37 @@ pub async fn resolve_manifest_for_tag(
         ));
     }
 
-    Ok(LatestManifest {
-        available: true,
-        tag: Some(tag.to_string()),
-        manifest: Some(manifest),
-        message: None,
-    })
+    Ok(Some(SignedManifest {
+        manifest,
+        manifest_bytes: m_bytes,
+        sig_text,
+    }))
 }
 
 pub async fn list_tags_for_channel(
-    client: &reqwest::Client,
+    client: &HttpClient,
+    source: &DistributionSource,
     channel: Channel,
 ) -> ApiResult<Vec<String>> {
-    // 1) Try GitHub Releases API.
-    let api_url = format!("https://api.github.com/repos/{DIST_REPO_SLUG}/releases?per_page=100");
-    if let Ok((status, body)) = fetch_text(client, &api_url).await {
-        if status.is_success() {
-            if let Ok(releases) = parse_releases_api_json(&body) {
-                let mut tags = releases
-                    .into_iter()
-                    .filter(|r| !r.draft)
-                    .filter(|r| match channel {
-                        Channel::Stable => !r.prerelease,
-                        Channel::Beta => r.prerelease,
-                    })
-                    .map(|r| r.tag)
-                    .filter(|t| is_tag_for_channel(channel, t))
-                    .collect::<Vec<_>>();
-                sort_tags(channel, &mut tags);
-                tags.dedup();
-                if !tags.is_empty() {
-                    return Ok(tags);
-                }
-            }
+    // 1) Try the releases index (GitHub Releases API or mirror releases.json).
+    let index = fetch_releases(client, source).await;
+    if let Ok(Some(releases)) = &index {
+        let mut tags = releases
+            .iter()
+            .filter(|r| !r.draft)
+            .filter(|r| r.prerelease == channel.is_prerelease())
+            .map(|r| r.tag.clone())
+            .filter(|t| is_tag_for_channel(channel, t))
+            .collect::<Vec<_>>();
+        sort_tags(channel, &mut tags);
+        tags.dedup();
+        if !tags.is_empty() {
+            return Ok(tags);
         }
     }
 
-    // 2) Fallback: Atom feed.
-    let atom_url = format!("https://github.com/{DIST_REPO_SLUG}/releases.atom");
-    let (status, xml) = fetch_text(client, &atom_url).await?;
-    if !status.is_success() {
-        return Err(http_status_error(&atom_url, status));
-    }
-    let mut tags = extract_tags_from_releases_atom(&xml)
+    // 2) Fallback: Atom feed or directory listing.
+    let mut tags = fallback_or_rate_limit(fetch_fallback_tags(client, source).await, index)?
         .into_iter()
         .filter(|t| is_tag_for_channel(channel, t))
         .collect::<Vec<_>>();
@@ -176,6 +395,29 @@ pub async fn list_tags_for_channel(
     Ok(tags)
 }
 
+/// Highest tag of `channel` within `range`.
+pub async fn resolve_tag_in_range(
+    client: &HttpClient,
+    source: &DistributionSource,
+    channel: Channel,
+    range: &VersionRange,
+) -> ApiResult<String> {
+    let tags = list_tags_for_channel(client, source, channel).await?;
+    range
+        .highest_match(channel, &tags)
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| {
+            ApiError::new(
+                "no_matching_release",
+                format!("no {} release matches {range}", channel.as_str()),
+            )
+            .with_details(serde_json::json!({
+                "channel": channel,
+                "range": range.to_string(),
+            }))
+        })
+}
+
 fn sort_tags(channel: Channel, tags: &mut [String]) {
     tags.sort_by(|a, b| {
         let ord = compare_tags(channel, a, b).unwrap_or(std::cmp::Ordering::Equal);
@@ -184,38 +426,270 @@ fn sort_tags(channel: Channel, tags: &mut [String]) {
 }
 
 async fn resolve_latest_tag(
-    client: &reqwest::Client,
+    client: &HttpClient,
+    source: &DistributionSource,
     channel: Channel,
 ) -> ApiResult<Option<String>> {
-    // 1) Try GitHub Releases API.
-    let api_url = format!("https://api.github.com/repos/{DIST_REPO_SLUG}/releases?per_page=100");
-    if let Ok((status, body)) = fetch_text(client, &api_url).await {
-        if status.is_success() {
-            if let Ok(releases) = parse_releases_api_json(&body) {
-                if let Ok(tag) = latest_tag_for_channel_from_releases(channel, &releases) {
-                    if tag.is_some() {
-                        return Ok(tag);
-                    }
-                }
+    // 1) Try the releases index (GitHub Releases API or mirror releases.json).
+    let index = fetch_releases(client, source).await;
+    if let Ok(Some(releases)) = &index {
+        if let Ok(tag) = latest_tag_for_channel_from_releases(channel, releases) {
+            if tag.is_some() {
+                return Ok(tag);
             }
         }
     }
 
-    // 2) Fallback: Atom feed.
-    let atom_url = format!("https://github.com/{DIST_REPO_SLUG}/releases.atom");
-    let (status, xml) = fetch_text(client, &atom_url).await?;
+    // 2) Fallback: Atom feed or directory listing.
+    let tags = fallback_or_rate_limit(fetch_fallback_tags(client, source).await, index)?;
+    Ok(latest_tag_for_channel(channel, &tags))
+}
+
+/// When both the releases index and the fallback fail, a rate-limit refusal of the index says
+/// more (and when to retry) than the fallback's error.
+fn fallback_or_rate_limit<T>(
+    fallback: ApiResult<Vec<String>>,
+    index: ApiResult<T>,
+) -> ApiResult<Vec<String>> {
+    match (fallback, index) {
+        (Ok(tags), _) => Ok(tags),
+        (Err(_), Err(rate_limited)) => Err(rate_limited),
+        (Err(err), Ok(_)) => Err(err),
+    }
+}
+
+/// The releases index, or `None` when it cannot be used. Only a rate-limit refusal is
+/// reported, so callers can surface it if the fallback fails too.
+async fn fetch_releases(
+    client: &HttpClient,
+    source: &DistributionSource,
+) -> ApiResult<Option<Vec<ReleaseInfo>>> {
+    let (status, body) = match fetch_text(client, &source.releases_index_url()).await {
+        Ok(out) => out,
+        Err(err) if err.code == HTTP_RATE_LIMITED => return Err(err),
+        Err(_) => return Ok(None),
+    };
     if !status.is_success() {
-        return Err(http_status_error(&atom_url, status));
+        return Ok(None);
     }
-    let tags = extract_tags_from_releases_atom(&xml);
-    Ok(latest_tag_for_channel(channel, &tags))
+    Ok(parse_releases_api_json(&body).ok())
 }
 
-fn ensure_manifest_channel(channel: Channel, manifest: &Manifest) -> ApiResult<()> {
-    let expected = match channel {
-        Channel::Stable => ManifestChannel::Stable,
-        Channel::Beta => ManifestChannel::Beta,
+async fn fetch_fallback_tags(
+    client: &HttpClient,
+    source: &DistributionSource,
+) -> ApiResult<Vec<String>> {
+    match source {
+        DistributionSource::Directory { path } => list_directory_tags(Path::new(path)),
+        DistributionSource::Github { .. } | DistributionSource::Https { .. } => {
+            let Some(atom_url) = source.releases_atom_url() else {
+                let url = source.releases_index_url();
+                return Err(ApiError::new(
+                    "release_index_unavailable",
+                    format!("GET {url}: no usable release index"),
+                )
+                .with_details(serde_json::json!({ "url": url })));
+            };
+            let (status, xml) = fetch_text(client, &atom_url).await?;
+            if !status.is_success() {
+                return Err(http_status_error(&atom_url, status));
+            }
+            Ok(extract_tags_from_releases_atom(&xml))
+        }
+    }
+}
+
+/// Tags of a local distribution directory without a `releases.json`: every `<tag>/manifest.json`.
+fn list_directory_tags(root: &Path) -> ApiResult<Vec<String>> {
+    let entries = std::fs::read_dir(root).map_err(|e| {
+        ApiError::new(
+            "local_read_failed",
+            format!("read dir {}: {e}", root.display()),
+        )
+    })?;
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("manifest.json").is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|tag| channel_for_tag(tag).is_some())
+        .collect())
+}
+
+/// Last verified manifest of a channel, stored as the exact bytes it was verified from.
+#[derive(Debug, Serialize, Deserialize)]
+struct ChannelManifestRecord {
+    schema: u32,
+    tag: String,
+    fetched_at_ms: u64,
+    manifest: String,
+    signature: String,
+}
+
+const CHANNEL_MANIFEST_RECORD_SCHEMA: u32 = 1;
+
+pub struct CachedChannelManifest {
+    pub manifest: Manifest,
+    pub fetched_at_ms: u64,
+}
+
+/// Best-effort: without the record, an unreachable source just has nothing to fall back to.
+fn store_channel_manifest(
+    layout: &PayloadLayout,
+    channel: Channel,
+    tag: &str,
+    manifest_bytes: &[u8],
+    sig_text: &str,
+) {
+    let Ok(manifest) = std::str::from_utf8(manifest_bytes) else {
+        return;
     };
+    let _ = write_json_atomic(
+        &layout.channel_manifest_cache_file(channel),
+        &ChannelManifestRecord {
+            schema: CHANNEL_MANIFEST_RECORD_SCHEMA,
+            tag: tag.to_string(),
+            fetched_at_ms: now_ms(),
+            manifest: manifest.to_string(),
+            signature: sig_text.to_string(),
+        },
+    );
+}
+
+/// The channel's last verified manifest, verified again against `keyring` so a key revoked
+/// since then is not trusted from the cache.
+pub fn load_channel_manifest(
+    layout: &PayloadLayout,
+    keyring: &Keyring,
+    channel: Channel,
+) -> Option<CachedChannelManifest> {
+    let record =
+        read_json_optional::<ChannelManifestRecord>(&layout.channel_manifest_cache_file(channel))
+            .ok()
+            .flatten()
+            .filter(|record| record.schema == CHANNEL_MANIFEST_RECORD_SCHEMA)?;
+    let bytes = record.manifest.as_bytes();
+    keyring
+        .verify_manifest(channel, bytes, &record.signature)
+        .ok()?;
+    let manifest = parse_manifest_json(bytes).ok()?;
+    if manifest.tag != record.tag
+        || ensure_manifest_channel(channel, &manifest).is_err()
+        || ensure_manifest_valid(&manifest).is_err()
+    {
+        return None;
+    }
+    Some(CachedChannelManifest {
+        manifest,
+        fetched_at_ms: record.fetched_at_ms,
+    })
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// How long a merged revocation list is trusted before it is fetched again. Refetches are
+/// revalidated with the HTTP cache, so an unchanged list costs a `304`.
+const REVOCATION_LIST_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// When each published revocation list was last merged into a `revoked_keys.json`, keyed by
+/// `(revoked keys file, list url)`.
+static REVOCATION_LISTS_MERGED: Mutex<BTreeMap<(PathBuf, String), Instant>> =
+    Mutex::new(BTreeMap::new());
+
+fn revocation_list_due(merged_at: Option<Instant>, now: Instant) -> bool {
+    merged_at.is_none_or(|at| now.saturating_duration_since(at) >= REVOCATION_LIST_TTL)
+}
+
+/// Built-in keyring with every revocation seen so far applied.
+///
+/// Revocations are persisted in the payload state dir so that a stale or withheld revocation
+/// list can never bring a retired key back. Fetching the published list is best-effort; within
+/// [`REVOCATION_LIST_TTL`] of the last merge for a source, calls only read the persisted
+/// revocations, so a long-running app still picks up new revocations.
+pub async fn load_keyring(
+    client: &HttpClient,
+    layout: &PayloadLayout,
+    source: &DistributionSource,
+) -> Keyring {
+    let path = layout.revoked_keys_file();
+    let mut keyring = builtin_keyring();
+    let stored = read_json_optional::<RevocationList>(&path)
+        .ok()
+        .flatten()
+        .filter(|list| list.schema == REVOCATION_LIST_SCHEMA);
+    let mut revoked = stored.map(|list| list.revoked).unwrap_or_default();
+    for entry in &revoked {
+        keyring.revoke(entry.key_id.clone());
+    }
+
+    let merged_key = (path.clone(), source.revocation_list_url());
+    let merged_at = REVOCATION_LISTS_MERGED
+        .lock()
+        .unwrap()
+        .get(&merged_key)
+        .copied();
+    if !revocation_list_due(merged_at, Instant::now()) {
+        return keyring;
+    }
+    let Some(published) = fetch_revocation_list(client, source, &mut keyring).await else {
+        return keyring;
+    };
+
+    let known = revoked.len();
+    for entry in published.revoked {
+        if !revoked
+            .iter()
+            .any(|existing| existing.key_id == entry.key_id)
+        {
+            revoked.push(entry);
+        }
+    }
+    let stored = revoked.len() == known
+        || write_json_atomic(
+            &path,
+            &RevocationList {
+                schema: REVOCATION_LIST_SCHEMA,
+                revoked,
+            },
+        )
+        .is_ok();
+    if stored {
+        REVOCATION_LISTS_MERGED
+            .lock()
+            .unwrap()
+            .insert(merged_key, Instant::now());
+    }
+
+    keyring
+}
+
+async fn fetch_revocation_list(
+    client: &HttpClient,
+    source: &DistributionSource,
+    keyring: &mut Keyring,
+) -> Option<RevocationList> {
+    let (status, bytes) = fetch_bytes(client, &source.revocation_list_url())
+        .await
+        .ok()?;
+    if !status.is_success() {
+        return None;
+    }
+    let (status, sig_text) = fetch_text(client, &source.revocation_list_sig_url())
+        .await
+        .ok()?;
+    if !status.is_success() {
+        return None;
+    }
+
+    keyring.apply_revocation_list(&bytes, &sig_text).ok()
+}
+
+fn ensure_manifest_channel(channel: Channel, manifest: &Manifest) -> ApiResult<()> {
+    let expected = ManifestChannel::from(channel);
     if manifest.channel != expected {
         return Err(ApiError::new(
             "manifest_channel_mismatch",
@@ -225,44 +699,173 @@ fn ensure_manifest_channel(channel: Channel, manifest: &Manifest) -> ApiResult<(
     Ok(())
 }
 
-fn http_status_error(url: &str, status: reqwest::StatusCode) -> ApiError {
+/// Refuse manifests with structural errors before anything is downloaded; warnings are tolerated.
+pub(crate) fn ensure_manifest_valid(manifest: &Manifest) -> ApiResult<()> {
+    let report = manifest.validate();
+    let Some(first) = report.errors().next() else {
+        return Ok(());
+    };
+    Err(ApiError::new(
+        "manifest_invalid",
+        format!(
+            "manifest {} has {} error(s); first: {} ({})",
+            manifest.tag,
+            report.errors().count(),
+            first.message,
+            first.path
+        ),
+    )
+    .with_details(serde_json::json!(report)))
+}
+
+pub(crate) fn http_status_error(url: &str, status: reqwest::StatusCode) -> ApiError {
     ApiError::new("http_status", format!("GET {url}: {status}"))
         .with_details(serde_json::json!({"url": url, "status": status.as_u16()}))
 }
 
-async fn fetch_text(
-    client: &reqwest::Client,
+pub(crate) async fn fetch_text(
+    client: &HttpClient,
     url: &str,
 ) -> ApiResult<(reqwest::StatusCode, String)> {
-    let res = client
-        .get(url)
-        .header("Accept", "application/vnd.github+json")
-        .send()
-        .await
-        .map_err(|e| {
-            ApiError::new("http_request_failed", format!("GET {url}: {e}"))
-                .with_details(serde_json::json!({"url": url}))
-        })?;
-    let status = res.status();
-    let text = res.text().await.map_err(|e| {
-        ApiError::new("http_read_failed", format!("read {url}: {e}"))
-            .with_details(serde_json::json!({"url": url}))
-    })?;
-    Ok((status, text))
+    let (status, bytes) = if local_path_for_location(url).is_some() {
+        read_local(url).await?
+    } else {
+        client
+            .get_cached(url, Some("application/vnd.github+json"))
+            .await?
+    };
+    Ok((status, String::from_utf8_lossy(&bytes).into_owned()))
 }
 
-async fn fetch_bytes(
-    client: &reqwest::Client,
+pub(crate) async fn fetch_bytes(
+    client: &HttpClient,
     url: &str,
 ) -> ApiResult<(reqwest::StatusCode, Vec<u8>)> {
-    let res = client.get(url).send().await.map_err(|e| {
-        ApiError::new("http_request_failed", format!("GET {url}: {e}"))
-            .with_details(serde_json::json!({"url": url}))
-    })?;
-    let status = res.status();
-    let bytes = res.bytes().await.map_err(|e| {
-        ApiError::new("http_read_failed", format!("read {url}: {e}"))
-            .with_details(serde_json::json!({"url": url}))
-    })?;
-    Ok((status, bytes.to_vec()))
+    if local_path_for_location(url).is_some() {
+        return read_local(url).await;
+    }
+    client.get_cached(url, None).await
+}
+
+/// Read a `file://` location, mapping a missing file to `404` like the HTTP sources.
+async fn read_local(location: &str) -> ApiResult<(reqwest::StatusCode, Vec<u8>)> {
+    let path = local_path_for_location(location).unwrap_or(location);
+    match tokio::fs::read(path).await {
+        Ok(bytes) => Ok((reqwest::StatusCode::OK, bytes)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Ok((reqwest::StatusCode::NOT_FOUND, Vec::new()))
+        }
+        Err(e) => Err(
+            ApiError::new("local_read_failed", format!("read {path}: {e}"))
+                .with_details(serde_json::json!({ "url": location })),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRoot;
+
+    #[test]
+    fn cached_channel_manifest_is_verified_again_on_load() {
+        let root = TestRoot::new("distribution");
+        let layout = root.layout();
+        let keyring = builtin_keyring();
+        assert!(load_channel_manifest(&layout, &keyring, Channel::Stable).is_none());
+
+        let manifest = serde_json::json!({
+            "schema": 3,
+            "channel": "stable",
+            "tag": "v1.0.0",
+            "published_at": "2026-10-01T00:00:00Z",
+            "repos": [],
+            "assets": [],
+            "install_sets": [],
+        })
+        .to_string();
+        store_channel_manifest(
+            &layout,
+            Channel::Stable,
+            "v1.0.0",
+            manifest.as_bytes(),
+            "unknown-key:AAAA",
+        );
+        let path = layout.channel_manifest_cache_file(Channel::Stable);
+        assert!(path.is_file());
+
+        assert!(load_channel_manifest(&layout, &keyring, Channel::Stable).is_none());
+        assert!(load_channel_manifest(&layout, &keyring, Channel::Beta).is_none());
+    }
+
+    #[test]
+    fn only_transport_failures_count_as_unreachable() {
+        let status = |code: u16| {
+            http_status_error(
+                "https://example.test/manifest.json",
+                reqwest::StatusCode::from_u16(code).unwrap(),
+            )
+        };
+        assert!(is_source_unreachable(&status(503)));
+        assert!(!is_source_unreachable(&status(404)));
+        assert!(is_source_unreachable(&ApiError::new(
+            "http_request_failed",
+            "timeout"
+        )));
+        assert!(is_source_unreachable(&ApiError::new(
+            HTTP_RATE_LIMITED,
+            "rate limited"
+        )));
+        for code in [
+            "manifest_sig_invalid",
+            "signing_key_revoked",
+            "manifest_invalid",
+            "manager_update_required",
+        ] {
+            assert!(!is_source_unreachable(&ApiError::new(code, "rejected")));
+        }
+    }
+
+    #[test]
+    fn bad_signature_does_not_fall_back() {
+        let root = TestRoot::new("distribution");
+        let layout = root.layout();
+        let release = root.path().join("releases").join("v1.0.0");
+        std::fs::create_dir_all(&release).unwrap();
+        std::fs::write(release.join("manifest.json"), b"{}").unwrap();
+        std::fs::write(release.join("manifest.json.sig"), "AAAA").unwrap();
+        let source = DistributionSource::Directory {
+            path: root.path().join("releases").display().to_string(),
+        };
+        let client = HttpClient::new(
+            crate::services::http_client::build_client(&Default::default()).unwrap(),
+        );
+
+        let err = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(resolve_available_manifest(
+                &client,
+                &layout,
+                &source,
+                Channel::Stable,
+                Some("v1.0.0"),
+            ))
+            .err()
+            .unwrap();
+        assert_eq!(err.code, "manifest_sig_invalid");
+    }
+
+    #[test]
+    fn revocation_list_is_fetched_again_after_its_ttl() {
+        let now = Instant::now();
+        assert!(revocation_list_due(None, now));
+        assert!(!revocation_list_due(Some(now), now));
+        assert!(!revocation_list_due(
+            Some(now),
+            now + REVOCATION_LIST_TTL - Duration::from_secs(1)
+        ));
+        assert!(revocation_list_due(Some(now), now + REVOCATION_LIST_TTL));
+    }
 }
@@ -0,0 +1,221 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::time::Instant;
+
+/// Minimum spacing between two progress events.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+/// How far ahead of the rate cap a download may run, as time at the capped rate.
+const RATE_BURST: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadEvent {
+    /// An asset started downloading; `index` is 1-based in the plan order.
+    Started {
+        index: usize,
+        asset_id: String,
+        filename: String,
+    },
+    Progress(DownloadProgress),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DownloadProgress {
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    /// Average rate of bytes actually transferred in this run (cache hits excluded).
+    pub bytes_per_sec: u64,
+    pub eta_ms: Option<u64>,
+}
+
+/// Byte accounting shared by every download of one install, with an optional rate cap.
+///
+/// The cap applies to the combined rate through one token bucket holding at most
+/// [`RATE_BURST`] worth of bytes, so concurrent downloads share the budget. Time before the
+/// first transferred byte (keyring loading, cache checks) earns no credit.
+pub struct DownloadTracker {
+    bytes_total: u64,
+    bytes_done: AtomicU64,
+    transferred: AtomicU64,
+    limit_bytes_per_sec: Option<u64>,
+    /// When the first byte was transferred; throughput is measured from there.
+    first_transfer: Mutex<Option<Instant>>,
+    bucket: Mutex<Option<TokenBucket>>,
+    last_report: Mutex<Option<Instant>>,
+    on_event: Box<dyn Fn(DownloadEvent) + Send + Sync>,
+}
+
+struct TokenBucket {
+    /// Bytes that may be transferred without waiting; negative while transfers are ahead of
+    /// the cap.
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl DownloadTracker {
+    pub fn new(
+        bytes_total: u64,
+        limit_bytes_per_sec: Option<u64>,
+        on_event: impl Fn(DownloadEvent) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            bytes_total,
+            bytes_done: AtomicU64::new(0),
+            transferred: AtomicU64::new(0),
+            limit_bytes_per_sec: limit_bytes_per_sec.filter(|limit| *limit > 0),
+            first_transfer: Mutex::new(None),
+            bucket: Mutex::new(None),
+            last_report: Mutex::new(None),
+            on_event: Box::new(on_event),
+        }
+    }
+
+    /// A tracker that only paces and counts; for callers without a progress display.
+    pub fn silent() -> Self {
+        Self::new(0, None, |_| {})
+    }
+
+    pub fn started(&self, index: usize, asset_id: &str, filename: &str) {
+        (self.on_event)(DownloadEvent::Started {
+            index,
+            asset_id: asset_id.to_string(),
+            filename: filename.to_string(),
+        });
+    }
+
+    /// Count bytes that were already on disk (cache hits, resumed prefixes).
+    pub fn skip(&self, bytes: u64) {
+        self.bytes_done.fetch_add(bytes, Ordering::Relaxed);
+        self.report(false);
+    }
+
+    /// Count freshly transferred bytes, sleeping as needed to respect the rate cap.
+    pub async fn transferred(&self, bytes: u64) {
+        let now = Instant::now();
+        self.first_transfer.lock().unwrap().get_or_insert(now);
+        self.bytes_done.fetch_add(bytes, Ordering::Relaxed);
+        self.transferred.fetch_add(bytes, Ordering::Relaxed);
+        self.report(false);
+
+        if let Some(limit) = self.limit_bytes_per_sec {
+            let wait = self.take_tokens(limit as f64, bytes as f64, now);
+            if !wait.is_zero() {
+                tokio::time::sleep(wait).await;
+            }
+        }
+    }
+
+    /// Take `bytes` from the bucket and return how long to wait until the cap allows them.
+    fn take_tokens(&self, limit: f64, bytes: f64, now: Instant) -> Duration {
+        let burst = limit * RATE_BURST.as_secs_f64();
+        let mut bucket = self.bucket.lock().unwrap();
+        let bucket = bucket.get_or_insert(TokenBucket {
+            tokens: burst,
+            refilled: now,
+        });
+        let refill = now.saturating_duration_since(bucket.refilled).as_secs_f64() * limit;
+        bucket.tokens = (bucket.tokens + refill).min(burst) - bytes;
+        bucket.refilled = now;
+        if bucket.tokens < 0.0 {
+            Duration::from_secs_f64(-bucket.tokens / limit)
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    /// Report the final state, bypassing the progress interval.
+    pub fn finish(&self) {
+        self.report(true);
+    }
+
+    pub fn snapshot(&self) -> DownloadProgress {
+        let bytes_done = self.bytes_done.load(Ordering::Relaxed);
+        // A restarted download counts its bytes twice; never report more than 100%.
+        let bytes_total = self.bytes_total.max(bytes_done);
+        let elapsed = self
+            .first_transfer
+            .lock()
+            .unwrap()
+            .map_or(0.0, |at| at.elapsed().as_secs_f64());
+        let transferred = self.transferred.load(Ordering::Relaxed);
+        let bytes_per_sec = if elapsed > 0.0 {
+            (transferred as f64 / elapsed) as u64
+        } else {
+            0
+        };
+        let eta_ms = (bytes_per_sec > 0).then(|| {
+            let remaining = bytes_total.saturating_sub(bytes_done);
+            remaining.saturating_mul(1000) / bytes_per_sec
+        });
+        DownloadProgress {
+            bytes_done,
+            bytes_total,
+            bytes_per_sec,
+            eta_ms,
+        }
+    }
+
+    fn report(&self, force: bool) {
+        {
+            let mut last = self.last_report.lock().unwrap();
+            let now = Instant::now();
+            if !force && last.is_some_and(|at| now.duration_since(at) < PROGRESS_INTERVAL) {
+                return;
+            }
+            *last = Some(now);
+        }
+        (self.on_event)(DownloadEvent::Progress(self.snapshot()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bandwidth_cap_paces_transferred_bytes() {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(async {
+                let tracker = DownloadTracker::new(60_000, Some(100_000), |_| {});
+                let started = Instant::now();
+                for _ in 0..4 {
+                    tracker.transferred(15_000).await;
+                }
+                // 60 kB at 100 kB/s, less the 25 kB burst.
+                assert!(started.elapsed() >= Duration::from_millis(340));
+            });
+    }
+
+    #[test]
+    fn idle_time_before_the_first_byte_earns_no_burst() {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(async {
+                let tracker = DownloadTracker::new(60_000, Some(100_000), |_| {});
+                tokio::time::sleep(Duration::from_millis(400)).await;
+                let started = Instant::now();
+                for _ in 0..4 {
+                    tracker.transferred(15_000).await;
+                }
+                assert!(started.elapsed() >= Duration::from_millis(340));
+            });
+    }
+
+    #[test]
+    fn snapshot_excludes_skipped_bytes_from_throughput() {
+        let tracker = DownloadTracker::new(1_000, None, |_| {});
+        tracker.skip(400);
+        let progress = tracker.snapshot();
+        assert_eq!(progress.bytes_done, 400);
+        assert_eq!(progress.bytes_total, 1_000);
+        assert_eq!(progress.bytes_per_sec, 0);
+        assert_eq!(progress.eta_ms, None);
+    }
+}
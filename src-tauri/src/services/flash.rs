@@ -1,17 +1,24 @@
+use std::path::PathBuf;
 use std::process::Stdio;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use tokio::io::{AsyncBufReadExt, BufReader};
 
-use ms_manager_core::{ArtifactSource, BridgeInstanceBinding, InstallState, LastFlashed};
+use ms_manager_core::{
+    ArtifactSource, BridgeInstanceBinding, BridgeMode, InstallState, LastFlashed,
+};
+#[cfg(feature = "desktop")]
 use tauri::Emitter;
 
 use crate::api_error::{ApiError, ApiResult};
 use crate::layout::PayloadLayout;
 use crate::models::{FlashEvent, FlashMessageLevel};
 use crate::services::process;
-use crate::services::{artifact_resolver, bridge_ctl, bridge_status, device, ux_recorder};
+#[cfg(feature = "desktop")]
+use crate::services::ux_recorder;
+use crate::services::{artifact_resolver, bridge_ctl, bridge_status, device};
 
+#[cfg(feature = "desktop")]
 const FLASH_EVENT: &str = "ms-manager://flash";
 const POST_FLASH_READY_TIMEOUT: Duration = Duration::from_secs(20);
 const POST_FLASH_POLL_INTERVAL: Duration = Duration::from_millis(250);
@@ -60,29 +67,89 @@ impl BridgeControlAction {
     }
 }
 
+/// Loader, firmware image and device target of a flash, resolved before the bridge is touched.
+pub struct FlashPlan {
+    pub loader: PathBuf,
+    pub firmware: PathBuf,
+    pub build_profile: Option<String>,
+    pub device_target_id: String,
+}
+
+#[cfg(feature = "desktop")]
 pub async fn flash_firmware_for_binding(
     app: &tauri::AppHandle,
     layout: &PayloadLayout,
     installed: Option<&InstallState>,
     binding: &BridgeInstanceBinding,
-    firmware_override: Option<(std::path::PathBuf, String)>,
+    firmware_override: Option<(PathBuf, Option<String>)>,
 ) -> ApiResult<LastFlashed> {
+    let plan = plan_flash(layout, installed, binding, firmware_override, None).await?;
+    ux_recorder::close_session_for_instance(app, layout, &binding.instance_id, "flash_begin");
+    let emit = |event: FlashEvent| {
+        let _ = app.emit(FLASH_EVENT, event);
+    };
+    run_flash(&plan, installed, binding, &emit).await
+}
+
+/// Resolve what flashing `binding` needs. The overrides replace the instance's own firmware
+/// (with its build profile, if any) and its controller's device target.
+pub async fn plan_flash(
+    layout: &PayloadLayout,
+    installed: Option<&InstallState>,
+    binding: &BridgeInstanceBinding,
+    firmware_override: Option<(PathBuf, Option<String>)>,
+    device_override: Option<String>,
+) -> ApiResult<FlashPlan> {
+    if binding.mode == BridgeMode::NativeSim {
+        return Err(ApiError::new(
+            "native_sim_flash_unsupported",
+            "Simulator instances run the simulator build of their release; install or rebuild it instead of flashing.",
+        ));
+    }
     let loader = artifact_resolver::resolve_loader_exe_for_binding(layout, binding)
         .map_err(|error| make_actionable_flash_error(error, binding))?;
     let (firmware, build_profile) = match firmware_override {
-        Some((firmware, build_profile)) => (firmware, Some(build_profile)),
+        Some(firmware_override) => firmware_override,
         None => (
             artifact_resolver::resolve_firmware_for_binding(layout, installed, binding)
                 .map_err(|error| make_actionable_flash_error(error, binding))?,
             None,
         ),
     };
-    let device_target = resolve_flash_target(&loader, binding)
-        .await
-        .map_err(|error| make_actionable_flash_error(error, binding))?;
+    let device_target_id = match device_override {
+        Some(target_id) => target_id,
+        None => {
+            resolve_flash_target(&loader, binding)
+                .await
+                .map_err(|error| make_actionable_flash_error(error, binding))?
+                .target_id
+        }
+    };
+    Ok(FlashPlan {
+        loader,
+        firmware,
+        build_profile,
+        device_target_id,
+    })
+}
+
+/// Flash a resolved plan, pausing the instance's bridge around it and checking that the
+/// controller comes back afterwards. Progress is reported through `emit`.
+pub async fn run_flash(
+    plan: &FlashPlan,
+    installed: Option<&InstallState>,
+    binding: &BridgeInstanceBinding,
+    emit: &(impl Fn(FlashEvent) + Sync),
+) -> ApiResult<LastFlashed> {
+    let FlashPlan {
+        loader,
+        firmware,
+        build_profile,
+        device_target_id,
+    } = plan;
     let mut bridge_transition_warnings = Vec::new();
-    ux_recorder::close_session_for_instance(app, layout, &binding.instance_id, "flash_begin");
-    if let Some(warning) = run_bridge_control_action(app, binding, BridgeControlAction::Pause).await
+    if let Some(warning) =
+        run_bridge_control_action(emit, binding, BridgeControlAction::Pause).await
     {
         bridge_transition_warnings.push(warning);
     }
@@ -93,16 +160,13 @@ pub async fn flash_firmware_for_binding(
         .unwrap_or(ms_manager_core::Channel::Stable);
     let tag = flash_tag(installed, binding);
 
-    let _ = app.emit(
-        FLASH_EVENT,
-        FlashEvent::Begin {
-            channel,
-            tag: tag.clone(),
-            profile: profile.clone(),
-        },
-    );
+    emit(FlashEvent::Begin {
+        channel,
+        tag: tag.clone(),
+        profile: profile.clone(),
+    });
 
-    let mut cmd = tokio::process::Command::new(&loader);
+    let mut cmd = tokio::process::Command::new(loader);
     process::no_console_window(&mut cmd);
     let mut child = cmd
         .args([
@@ -115,8 +179,8 @@ pub async fn flash_firmware_for_binding(
             "60000",
         ])
         .arg("--device")
-        .arg(&device_target.target_id)
-        .arg(&firmware)
+        .arg(device_target_id)
+        .arg(firmware)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
@@ -159,7 +223,7 @@ pub async fn flash_firmware_for_binding(
             continue;
         }
         stdout_lines.push(line.clone());
-        let _ = app.emit(FLASH_EVENT, FlashEvent::Output { line });
+        emit(FlashEvent::Output { line });
     }
 
     let status = child.wait().await.map_err(|e| {
@@ -170,7 +234,7 @@ pub async fn flash_firmware_for_binding(
     })?;
 
     if let Some(warning) =
-        run_bridge_control_action(app, binding, BridgeControlAction::Resume).await
+        run_bridge_control_action(emit, binding, BridgeControlAction::Resume).await
     {
         bridge_transition_warnings.push(warning);
     }
@@ -178,7 +242,7 @@ pub async fn flash_firmware_for_binding(
     let stderr = stderr_task.await.unwrap_or_default();
 
     if !status.success() {
-        let _ = app.emit(FLASH_EVENT, FlashEvent::Done { ok: false });
+        emit(FlashEvent::Done { ok: false });
         let summary = summarize_flash_failure(&stdout_lines, &stderr);
         return Err(make_actionable_flash_error(
             ApiError::new("flash_failed", format!("firmware flash failed: {summary}"))
@@ -188,7 +252,7 @@ pub async fn flash_firmware_for_binding(
                     "stdout": stdout_lines,
                     "firmware": firmware.display().to_string(),
                     "instance_id": binding.instance_id,
-                    "device_target_id": device_target.target_id,
+                    "device_target_id": device_target_id,
                     "bridge_transition_warnings": bridge_transition_warnings,
                 })),
             binding,
@@ -196,18 +260,18 @@ pub async fn flash_firmware_for_binding(
     }
 
     if let Err(error) =
-        verify_post_flash_health(app, &loader, binding, &bridge_transition_warnings).await
+        verify_post_flash_health(emit, loader, binding, &bridge_transition_warnings).await
     {
-        let _ = app.emit(FLASH_EVENT, FlashEvent::Done { ok: false });
+        emit(FlashEvent::Done { ok: false });
         return Err(make_actionable_flash_error(error, binding));
     }
-    let _ = app.emit(FLASH_EVENT, FlashEvent::Done { ok: true });
+    emit(FlashEvent::Done { ok: true });
 
     Ok(LastFlashed {
         channel,
         tag,
         profile,
-        build_profile,
+        build_profile: build_profile.clone(),
         flashed_at_ms: now_ms(),
     })
 }
@@ -262,21 +326,21 @@ fn last_non_empty_line(text: &str) -> Option<&str> {
 }
 
 async fn verify_post_flash_health(
-    app: &tauri::AppHandle,
+    emit: &(impl Fn(FlashEvent) + Sync),
     loader: &std::path::Path,
     binding: &BridgeInstanceBinding,
     bridge_transition_warnings: &[String],
 ) -> ApiResult<()> {
     if binding.enabled {
-        emit_flash_output(app, "Verifying bridge reconnect after flash...");
+        emit_flash_output(emit, "Verifying bridge reconnect after flash...");
         wait_for_bridge_ready_after_flash(binding, bridge_transition_warnings).await?;
-        emit_flash_output(app, "Bridge healthy after flash");
+        emit_flash_output(emit, "Bridge healthy after flash");
         return Ok(());
     }
 
-    emit_flash_output(app, "Verifying controller reconnect after flash...");
+    emit_flash_output(emit, "Verifying controller reconnect after flash...");
     wait_for_device_ready_after_flash(loader, binding, bridge_transition_warnings).await?;
-    emit_flash_output(app, "Controller reconnected after flash");
+    emit_flash_output(emit, "Controller reconnected after flash");
     Ok(())
 }
 
@@ -375,8 +439,8 @@ async fn wait_for_device_ready_after_flash(
     }
 }
 
-fn emit_flash_output(app: &tauri::AppHandle, line: impl Into<String>) {
-    let _ = app.emit(FLASH_EVENT, FlashEvent::Output { line: line.into() });
+fn emit_flash_output(emit: &impl Fn(FlashEvent), line: impl Into<String>) {
+    emit(FlashEvent::Output { line: line.into() });
 }
 
 fn make_actionable_flash_error(error: ApiError, binding: &BridgeInstanceBinding) -> ApiError {
@@ -533,14 +597,16 @@ fn artifact_missing_user_message(binding: &BridgeInstanceBinding) -> String {
         ArtifactSource::Installed => {
             "The selected firmware release is not available locally for flashing.".to_string()
         }
-        ArtifactSource::Workspace => match binding.target {
-            ms_manager_core::FirmwareTarget::Standalone => {
-                "The standalone firmware artifact is missing from the workspace.".to_string()
-            }
-            ms_manager_core::FirmwareTarget::Bitwig => {
-                "The Bitwig firmware artifact is missing from the workspace.".to_string()
-            }
-        },
+        ArtifactSource::Workspace if binding.target.is_standalone() => {
+            "The standalone firmware artifact is missing from the workspace.".to_string()
+        }
+        ArtifactSource::Workspace if binding.target.is_bitwig() => {
+            "The Bitwig firmware artifact is missing from the workspace.".to_string()
+        }
+        ArtifactSource::Workspace => format!(
+            "The workspace does not build the {} firmware target.",
+            binding.target
+        ),
     }
 }
 
@@ -559,6 +625,7 @@ fn artifact_missing_actions(binding: &BridgeInstanceBinding) -> Vec<&'static str
     }
 }
 
+#[cfg(feature = "desktop")]
 pub fn emit_flash_message(
     app: &tauri::AppHandle,
     level: FlashMessageLevel,
@@ -573,12 +640,13 @@ pub fn emit_flash_message(
     );
 }
 
+#[cfg(feature = "desktop")]
 pub fn emit_flash_done(app: &tauri::AppHandle, ok: bool) {
     let _ = app.emit(FLASH_EVENT, FlashEvent::Done { ok });
 }
 
 async fn run_bridge_control_action(
-    app: &tauri::AppHandle,
+    emit: &(impl Fn(FlashEvent) + Sync),
     binding: &BridgeInstanceBinding,
     action: BridgeControlAction,
 ) -> Option<String> {
@@ -586,7 +654,11 @@ async fn run_bridge_control_action(
         return None;
     }
 
-    emit_flash_message(app, FlashMessageLevel::Info, action.request_message());
+    let message = |level, message: String| emit(FlashEvent::Message { level, message });
+    message(
+        FlashMessageLevel::Info,
+        action.request_message().to_string(),
+    );
 
     let timeout = match action {
         BridgeControlAction::Pause => Duration::from_secs(2),
@@ -597,17 +669,20 @@ async fn run_bridge_control_action(
         Ok(value) => {
             let runtime = bridge_status::BridgeRuntimeState::from_value(value);
             if runtime.ok {
-                emit_flash_message(app, FlashMessageLevel::Info, action.success_message());
+                message(
+                    FlashMessageLevel::Info,
+                    action.success_message().to_string(),
+                );
                 None
             } else {
                 let warning = format_bridge_control_warning(action, runtime.message.as_deref());
-                emit_flash_message(app, FlashMessageLevel::Warn, warning.clone());
+                message(FlashMessageLevel::Warn, warning.clone());
                 Some(warning)
             }
         }
         Err(error) => {
             let warning = format_bridge_control_warning(action, Some(&error));
-            emit_flash_message(app, FlashMessageLevel::Warn, warning.clone());
+            message(FlashMessageLevel::Warn, warning.clone());
             Some(warning)
         }
     }
@@ -661,14 +736,16 @@ mod tests {
             controller_serial: "17076520".to_string(),
             controller_vid: 0x16C0,
             controller_pid: 0x0489,
-            target: ms_manager_core::FirmwareTarget::Bitwig,
+            target: ms_manager_core::FirmwareTarget::bitwig(),
             artifact_source: ArtifactSource::Workspace,
             installed_channel: None,
             installed_pinned_tag: None,
+            installed_version_range: None,
             host_udp_port: 9000,
             control_port: 7999,
             log_broadcast_port: 9999,
             enabled: true,
+            update_policy: ms_manager_core::UpdatePolicy::default(),
         };
 
         assert_eq!(flash_tag(None, &binding), "workspace");
@@ -684,14 +761,16 @@ mod tests {
             controller_serial: "17076520".to_string(),
             controller_vid: 0x16C0,
             controller_pid: 0x0489,
-            target: ms_manager_core::FirmwareTarget::Bitwig,
+            target: ms_manager_core::FirmwareTarget::bitwig(),
             artifact_source: ArtifactSource::Installed,
             installed_channel: Some(ms_manager_core::Channel::Stable),
             installed_pinned_tag: None,
+            installed_version_range: None,
             host_udp_port: 9000,
             control_port: 7999,
             log_broadcast_port: 9999,
             enabled: true,
+            update_policy: ms_manager_core::UpdatePolicy::default(),
         };
 
         let runtime = bridge_status::BridgeRuntimeState::from_value(serde_json::json!({
@@ -729,14 +808,16 @@ mod tests {
             controller_serial: "17076520".to_string(),
             controller_vid: 0x16C0,
             controller_pid: 0x0489,
-            target: ms_manager_core::FirmwareTarget::Standalone,
+            target: ms_manager_core::FirmwareTarget::standalone(),
             artifact_source: ArtifactSource::Workspace,
             installed_channel: None,
             installed_pinned_tag: None,
+            installed_version_range: None,
             host_udp_port: 9000,
             control_port: 7999,
             log_broadcast_port: 9999,
             enabled: true,
+            update_policy: ms_manager_core::UpdatePolicy::default(),
         };
 
         let err = make_actionable_flash_error(
@@ -0,0 +1,422 @@
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use ms_manager_core::{sha256_hex, NetworkSettings};
+use reqwest::header::{
+    HeaderMap, ACCEPT, AUTHORIZATION, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+    RETRY_AFTER,
+};
+use reqwest::{Certificate, Client, Proxy, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::api_error::{ApiError, ApiResult};
+use crate::models::RateLimitedDetails;
+use crate::storage::{read_json_optional, write_json_atomic};
+
+/// Error code of a request refused by the server's rate limit; details are
+/// [`RateLimitedDetails`].
+pub const HTTP_RATE_LIMITED: &str = "http_rate_limited";
+
+/// The only host the GitHub token is ever sent to.
+const GITHUB_API_PREFIX: &str = "https://api.github.com/";
+
+/// Retry delay when a rate-limited response says nothing about when to come back.
+const DEFAULT_RETRY_AFTER_SECS: u64 = 60;
+
+/// Build the shared HTTP client from the network settings.
+pub fn build_client(network: &NetworkSettings) -> ApiResult<Client> {
+    let mut builder = Client::builder()
+        .user_agent("ms-manager")
+        .connect_timeout(Duration::from_secs(network.connect_timeout_secs.into()))
+        .read_timeout(Duration::from_secs(network.read_timeout_secs.into()));
+
+    if let Some(settings) = &network.proxy {
+        let mut proxy = Proxy::all(&settings.url).map_err(|e| {
+            ApiError::new("http_proxy_invalid", format!("proxy {}: {e}", settings.url))
+        })?;
+        if let Some(username) = &settings.username {
+            proxy = proxy.basic_auth(username, settings.password.as_deref().unwrap_or(""));
+        }
+        builder = builder.proxy(proxy);
+    }
+
+    for path in &network.extra_ca_certificates {
+        let pem = std::fs::read(path)
+            .map_err(|e| ApiError::new("io_read_failed", format!("read {path}: {e}")))?;
+        let certs = Certificate::from_pem_bundle(&pem).map_err(|e| {
+            ApiError::new("http_ca_invalid", format!("parse {path}: {e}"))
+                .with_details(serde_json::json!({ "path": path }))
+        })?;
+        if certs.is_empty() {
+            return Err(ApiError::new(
+                "http_ca_invalid",
+                format!("{path} has no PEM certificates"),
+            )
+            .with_details(serde_json::json!({ "path": path })));
+        }
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    builder
+        .build()
+        .map_err(|e| ApiError::new("http_client_failed", e.to_string()))
+}
+
+/// The shared client plus what release-index requests need on top of it: a conditional-request
+/// cache and the GitHub token. Derefs to the plain client for downloads.
+#[derive(Clone)]
+pub struct HttpClient {
+    client: Client,
+    cache_dir: Option<PathBuf>,
+    github_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Deref for HttpClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        &self.client
+    }
+}
+
+impl HttpClient {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            cache_dir: None,
+            github_token: None,
+        }
+    }
+
+    pub fn with_cache_dir(mut self, dir: PathBuf) -> Self {
+        self.cache_dir = Some(dir);
+        self
+    }
+
+    pub fn with_github_token(mut self, token: Option<String>) -> Self {
+        self.github_token = token;
+        self
+    }
+
+    /// GET `url`, revalidating a cached copy with `If-None-Match`/`If-Modified-Since`.
+    ///
+    /// A `304 Not Modified` is answered from the cache as `200`. Rate-limited responses become
+    /// [`HTTP_RATE_LIMITED`] errors; other statuses are returned as-is for the caller to judge.
+    pub async fn get_cached(
+        &self,
+        url: &str,
+        accept: Option<&str>,
+    ) -> ApiResult<(StatusCode, Vec<u8>)> {
+        let cached = self.cache_lookup(url);
+        let mut request = self.client.get(url);
+        if let Some(accept) = accept {
+            request = request.header(ACCEPT, accept);
+        }
+        if let Some(token) = self.github_token.as_deref() {
+            if url.starts_with(GITHUB_API_PREFIX) {
+                request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+            }
+        }
+        if let Some((entry, _)) = &cached {
+            if let Some(etag) = &entry.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let res = request.send().await.map_err(|e| {
+            ApiError::new("http_request_failed", format!("GET {url}: {e}"))
+                .with_details(serde_json::json!({ "url": url }))
+        })?;
+        let status = res.status();
+        if let Some(details) = rate_limited(url, status, res.headers()) {
+            return Err(rate_limited_error(details));
+        }
+        if status == StatusCode::NOT_MODIFIED {
+            if let Some((_, body)) = cached {
+                return Ok((StatusCode::OK, body));
+            }
+        }
+
+        let header = |name| {
+            res.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+        let body = res.bytes().await.map_err(|e| {
+            ApiError::new("http_read_failed", format!("read {url}: {e}"))
+                .with_details(serde_json::json!({ "url": url }))
+        })?;
+        if status.is_success() && (etag.is_some() || last_modified.is_some()) {
+            self.cache_store(
+                CacheEntry {
+                    url: url.to_string(),
+                    etag,
+                    last_modified,
+                },
+                &body,
+            );
+        }
+        Ok((status, body.to_vec()))
+    }
+
+    fn cache_paths(&self, url: &str) -> Option<(PathBuf, PathBuf)> {
+        let dir = self.cache_dir.as_ref()?;
+        let key = sha256_hex(url.as_bytes());
+        Some((
+            dir.join(format!("{key}.json")),
+            dir.join(format!("{key}.body")),
+        ))
+    }
+
+    fn cache_lookup(&self, url: &str) -> Option<(CacheEntry, Vec<u8>)> {
+        let (meta_path, body_path) = self.cache_paths(url)?;
+        let entry = read_json_optional::<CacheEntry>(&meta_path).ok()??;
+        if entry.url != url {
+            return None;
+        }
+        let body = std::fs::read(body_path).ok()?;
+        Some((entry, body))
+    }
+
+    /// Best-effort: a cache that cannot be written only costs a full response next time.
+    fn cache_store(&self, entry: CacheEntry, body: &[u8]) {
+        let Some((meta_path, body_path)) = self.cache_paths(&entry.url) else {
+            return;
+        };
+        // Body first, so metadata never points at a body from another response.
+        let _ = std::fs::remove_file(&meta_path);
+        if std::fs::create_dir_all(body_path.parent().unwrap_or(&body_path)).is_err()
+            || std::fs::write(&body_path, body).is_err()
+        {
+            return;
+        }
+        let _ = write_json_atomic(&meta_path, &entry);
+    }
+}
+
+/// `X-RateLimit-*` and `Retry-After` of a refused response; `None` when it was not a
+/// rate-limit refusal.
+fn rate_limited(url: &str, status: StatusCode, headers: &HeaderMap) -> Option<RateLimitedDetails> {
+    let number = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    };
+    let limit = number("x-ratelimit-limit");
+    let remaining = number("x-ratelimit-remaining");
+    let reset_at = number("x-ratelimit-reset");
+    let retry_after = number(RETRY_AFTER.as_str());
+
+    let refused = status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN && (remaining == Some(0) || retry_after.is_some()));
+    if !refused {
+        return None;
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let retry_after_secs = retry_after
+        .or_else(|| reset_at.map(|reset| reset.saturating_sub(now)))
+        .unwrap_or(DEFAULT_RETRY_AFTER_SECS);
+    Some(RateLimitedDetails {
+        url: url.to_string(),
+        status: status.as_u16(),
+        retry_after_secs,
+        limit,
+        remaining,
+        reset_at,
+    })
+}
+
+fn rate_limited_error(details: RateLimitedDetails) -> ApiError {
+    let message = format!(
+        "GET {}: rate limited, retry in {}s{}",
+        details.url,
+        details.retry_after_secs,
+        if details.url.starts_with(GITHUB_API_PREFIX) {
+            " (a GitHub token raises the limit)"
+        } else {
+            ""
+        }
+    );
+    ApiError::new(HTTP_RATE_LIMITED, message).with_details(serde_json::json!(details))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    use ms_manager_core::ProxySettings;
+
+    use super::*;
+    use crate::test_support::TestRoot;
+
+    #[test]
+    fn requests_go_through_the_configured_proxy_with_credentials() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let proxy = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let request = read_request(&mut stream);
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .unwrap();
+            request
+        });
+
+        let client = build_client(&NetworkSettings {
+            proxy: Some(ProxySettings {
+                url: format!("http://127.0.0.1:{port}"),
+                username: Some("lab".to_string()),
+                password: Some("secret".to_string()),
+            }),
+            ..NetworkSettings::default()
+        })
+        .unwrap();
+        let body = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(async {
+                client
+                    .get("http://releases.invalid/manifest.json")
+                    .send()
+                    .await?
+                    .text()
+                    .await
+            })
+            .unwrap();
+        assert_eq!(body, "ok");
+
+        let request = proxy.join().unwrap();
+        assert!(request.starts_with("GET http://releases.invalid/manifest.json HTTP/1.1\r\n"));
+        let lower = request.to_ascii_lowercase();
+        assert!(
+            lower.contains("\r\nproxy-authorization: basic "),
+            "{request}"
+        );
+        // base64("lab:secret")
+        assert!(request.contains("bGFiOnNlY3JldA=="), "{request}");
+    }
+
+    #[test]
+    fn cached_responses_are_revalidated_with_etag() {
+        let (url, requests) = serve(vec![
+            "HTTP/1.1 200 OK\r\nETag: \"r1\"\r\nContent-Length: 4\r\n\r\ntags",
+            "HTTP/1.1 304 Not Modified\r\nETag: \"r1\"\r\n\r\n",
+        ]);
+        let root = TestRoot::new("http-cache");
+        let client = HttpClient::new(Client::builder().no_proxy().build().unwrap())
+            .with_cache_dir(root.path().to_path_buf());
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        for _ in 0..2 {
+            let (status, body) = runtime.block_on(client.get_cached(&url, None)).unwrap();
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(body, b"tags");
+        }
+
+        let requests = requests.join().unwrap();
+        assert!(!requests[0].to_ascii_lowercase().contains("if-none-match"));
+        assert!(requests[1]
+            .to_ascii_lowercase()
+            .contains("if-none-match: \"r1\""));
+    }
+
+    #[test]
+    fn exhausted_rate_limit_is_a_typed_error() {
+        let (url, requests) = serve(vec![
+            "HTTP/1.1 403 Forbidden\r\nX-RateLimit-Limit: 60\r\nX-RateLimit-Remaining: 0\r\nX-RateLimit-Reset: 1\r\nContent-Length: 0\r\n\r\n",
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 30\r\nContent-Length: 0\r\n\r\n",
+            "HTTP/1.1 403 Forbidden\r\nX-RateLimit-Remaining: 12\r\nContent-Length: 0\r\n\r\n",
+        ]);
+        let client = HttpClient::new(Client::builder().no_proxy().build().unwrap());
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let err = runtime.block_on(client.get_cached(&url, None)).unwrap_err();
+        assert_eq!(err.code, HTTP_RATE_LIMITED);
+        let details = err.details.unwrap();
+        assert_eq!(details["limit"], 60);
+        assert_eq!(details["remaining"], 0);
+        // The reset time is long past.
+        assert_eq!(details["retry_after_secs"], 0);
+
+        let err = runtime.block_on(client.get_cached(&url, None)).unwrap_err();
+        assert_eq!(err.details.unwrap()["retry_after_secs"], 30);
+
+        // A plain 403 stays an HTTP status for the caller.
+        let (status, _) = runtime.block_on(client.get_cached(&url, None)).unwrap();
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        requests.join().unwrap();
+    }
+
+    #[test]
+    fn unreadable_or_empty_ca_files_are_rejected() {
+        let root = TestRoot::new("http-client-ca");
+        let path = root.path().join("ca.pem");
+        std::fs::write(&path, "not a certificate").unwrap();
+        let network = |path: &std::path::Path| NetworkSettings {
+            extra_ca_certificates: vec![path.display().to_string()],
+            ..NetworkSettings::default()
+        };
+
+        let err = build_client(&network(&path)).unwrap_err();
+        assert_eq!(err.code, "http_ca_invalid");
+        std::fs::remove_file(&path).unwrap();
+        let err = build_client(&network(&path)).unwrap_err();
+        assert_eq!(err.code, "io_read_failed");
+    }
+
+    /// Answer one connection per canned response and return the raw requests.
+    fn serve(responses: Vec<&'static str>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/releases", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|response| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let request = read_request(&mut stream);
+                    stream.write_all(response.as_bytes()).unwrap();
+                    request
+                })
+                .collect()
+        });
+        (url, handle)
+    }
+
+    fn read_request(stream: &mut std::net::TcpStream) -> String {
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+            let n = stream.read(&mut buf).unwrap();
+            request.extend_from_slice(&buf[..n]);
+        }
+        String::from_utf8(request).unwrap()
+    }
+}
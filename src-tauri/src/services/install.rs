@@ -1,9 +1,21 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ms_manager_core::{
+    compare_tags, host_app_for_extension_kind, Channel, InstallHistoryAction, InstallState,
+    INSTALL_STATE_SCHEMA,
+};
+use serde::{Deserialize, Serialize};
 
 use crate::api_error::{ApiError, ApiResult};
-use crate::layout::PayloadLayout;
-use crate::models::InstallPlan;
-use crate::services::assets::CachedAsset;
+use crate::layout::{PayloadLayout, VERSION_ASSETS_FILE, VERSION_INVENTORY_FILE};
+use crate::models::{InstallEvent, InstallJournalPhase, InstallPlan};
+use crate::services::assets::{self, sha256_file_hex, CachedAsset};
+use crate::services::download::{DownloadEvent, DownloadTracker};
+use crate::services::{bridge_ctl, distribution, install_journal};
+use crate::state::{AppState, InstallGuard};
+use crate::storage::{read_json_optional, write_json_atomic};
 
 #[cfg(windows)]
 use crate::services::process;
@@ -14,6 +26,244 @@ pub struct InstalledVersion {
     pub profile: String,
 }
 
+/// Cache blobs a version was installed from, so payload GC knows which ones are still in use.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VersionAssets {
+    pub assets: Vec<VersionAsset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionAsset {
+    pub sha256: String,
+    pub filename: String,
+    #[serde(default)]
+    pub kind: String,
+}
+
+pub const VERSION_INVENTORY_SCHEMA: u32 = 1;
+
+/// Every file of an installed version with its size and sha256, written when it is installed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VersionInventory {
+    pub schema: u32,
+    /// Keyed by `/`-separated path relative to the version directory.
+    pub files: BTreeMap<String, InventoryFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InventoryFile {
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Manager bookkeeping inside a version directory; not part of the installed content.
+pub fn is_version_metadata(rel_path: &str) -> bool {
+    rel_path == VERSION_ASSETS_FILE || rel_path == VERSION_INVENTORY_FILE
+}
+
+pub fn read_version_inventory(
+    layout: &PayloadLayout,
+    tag: &str,
+) -> ApiResult<Option<VersionInventory>> {
+    read_json_optional(&layout.version_inventory_file(tag))
+}
+
+/// Hash every regular file below `dir`, skipping manager metadata.
+pub fn build_inventory(dir: &Path) -> ApiResult<VersionInventory> {
+    let mut files = BTreeMap::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let read = std::fs::read_dir(&current).map_err(|e| {
+            ApiError::new(
+                "io_read_failed",
+                format!("read dir {}: {e}", current.display()),
+            )
+        })?;
+        for entry in read {
+            let entry = entry.map_err(|e| ApiError::new("io_read_failed", e.to_string()))?;
+            let path = entry.path();
+            let file_type = entry
+                .file_type()
+                .map_err(|e| ApiError::new("io_read_failed", e.to_string()))?;
+            if file_type.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            if !file_type.is_file() {
+                continue;
+            }
+            let rel = inventory_path(dir, &path);
+            if is_version_metadata(&rel) {
+                continue;
+            }
+            let size = entry
+                .metadata()
+                .map_err(|e| ApiError::new("io_read_failed", e.to_string()))?
+                .len();
+            let sha256 = sha256_file_hex(&path)?;
+            files.insert(rel, InventoryFile { size, sha256 });
+        }
+    }
+    Ok(VersionInventory {
+        schema: VERSION_INVENTORY_SCHEMA,
+        files,
+    })
+}
+
+/// `/`-separated path of `path` relative to `dir`, the inventory key format.
+pub fn inventory_path(dir: &Path, path: &Path) -> String {
+    path.strip_prefix(dir)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// `None` for versions installed before the record existed, or with an unreadable record.
+pub fn read_version_assets(layout: &PayloadLayout, tag: &str) -> Option<VersionAssets> {
+    let bytes = std::fs::read(layout.version_assets_file(tag)).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Refuse to move `installed` to an older `tag` on the same channel.
+pub fn ensure_not_downgrade(
+    installed: Option<&InstallState>,
+    channel: Channel,
+    tag: &str,
+) -> ApiResult<()> {
+    let Some(installed) = installed.filter(|installed| installed.channel == channel) else {
+        return Ok(());
+    };
+
+    let ord = compare_tags(channel, tag, &installed.tag).ok_or_else(|| {
+        ApiError::new(
+            "tag_invalid",
+            format!(
+                "cannot compare tags for channel {}: {} vs {}",
+                channel.as_str(),
+                tag,
+                installed.tag
+            ),
+        )
+    })?;
+
+    if ord.is_lt() {
+        return Err(ApiError::new(
+            "downgrade_refused",
+            format!(
+                "refusing downgrade: installed {} -> target {}",
+                installed.tag, tag
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Download, verify and install `plan`; the pipeline behind both the app and `ms-manager-cli`.
+///
+/// Unless `allow_downgrade`, the release may not be older than the active one of its channel.
+/// With `activate`, the enabled bridges are stopped and the release becomes `current` and the
+/// active install state. Progress is reported through `emit`.
+pub async fn install_release(
+    state: &AppState,
+    channel: Channel,
+    plan: InstallPlan,
+    allow_downgrade: bool,
+    activate: bool,
+    _install: &InstallGuard<'_>,
+    emit: impl Fn(InstallEvent) + Send + Sync + 'static,
+) -> ApiResult<InstallState> {
+    let layout = state.layout_get();
+
+    // Anti-rollback: default update path must never auto-downgrade.
+    // Explicit pinning is treated as user intent, and may downgrade.
+    if !allow_downgrade {
+        ensure_not_downgrade(state.install_state_get().as_ref(), channel, &plan.tag)?;
+    }
+
+    let emit = Arc::new(emit);
+    emit(InstallEvent::Begin {
+        channel,
+        tag: plan.tag.clone(),
+        profile: plan.profile.clone(),
+        assets_total: plan.assets.len(),
+        notes: plan.notes.clone(),
+    });
+
+    let settings = state.settings_get();
+    if settings.network.offline {
+        assets::ensure_available_offline(&layout, &plan.assets)?;
+    }
+    let keyring =
+        distribution::load_keyring(&state.http(), &layout, &state.distribution_source_get()).await;
+    let total = plan.assets.len();
+    let events = Arc::clone(&emit);
+    let progress = Arc::new(DownloadTracker::new(
+        plan.assets.iter().map(|a| a.size).sum(),
+        settings.downloads.bandwidth_limit_bytes_per_sec(),
+        move |event| {
+            events(match event {
+                DownloadEvent::Started {
+                    index,
+                    asset_id,
+                    filename,
+                } => InstallEvent::Downloading {
+                    index,
+                    total,
+                    asset_id,
+                    filename,
+                },
+                DownloadEvent::Progress(p) => InstallEvent::DownloadProgress {
+                    bytes_done: p.bytes_done,
+                    bytes_total: p.bytes_total,
+                    bytes_per_sec: p.bytes_per_sec,
+                    eta_ms: p.eta_ms,
+                },
+            })
+        },
+    ));
+    let cached = assets::cache_assets(
+        &state.http(),
+        &layout,
+        &keyring,
+        channel,
+        &plan.assets,
+        settings.downloads.concurrency as usize,
+        progress,
+    )
+    .await?;
+
+    emit(InstallEvent::Applying {
+        step: "extract_and_stage".to_string(),
+    });
+
+    if activate {
+        bridge_ctl::shutdown_enabled(&state.bridge_instances_get()).await;
+    }
+
+    let installed = apply_install(&layout, &plan, &cached, activate).await?;
+
+    let next = InstallState {
+        schema: INSTALL_STATE_SCHEMA,
+        channel,
+        profile: installed.profile,
+        tag: installed.tag,
+    };
+    let next = if activate {
+        state.install_state_activate(next, InstallHistoryAction::Install)?
+    } else {
+        next
+    };
+
+    emit(InstallEvent::Done {
+        tag: next.tag.clone(),
+        profile: next.profile.clone(),
+    });
+
+    Ok(next)
+}
+
 pub async fn apply_install(
     layout: &PayloadLayout,
     plan: &InstallPlan,
@@ -34,8 +284,11 @@ pub async fn apply_install(
     } else {
         install_additional_assets(&version_dir, cached)?;
         ensure_bundle_executables(&version_dir)?;
+        record_additional_inventory(layout, &plan.tag, cached)?;
     }
 
+    record_version_assets(layout, &plan.tag, cached)?;
+
     if activate_current {
         set_current(layout, &plan.tag)?;
     }
@@ -52,8 +305,24 @@ async fn install_fresh(
     cached: &[CachedAsset],
 ) -> ApiResult<()> {
     let staging_dir = layout.version_staging_dir(&plan.tag);
+    install_journal::begin(layout, InstallJournalPhase::Stage, &plan.tag)?;
+    let result = stage_and_promote(layout, &plan.tag, &staging_dir, cached).await;
+    // The journal is only for a process that dies mid-step; a failed step cleans up itself.
+    if result.is_err() {
+        let _ = std::fs::remove_dir_all(&staging_dir);
+    }
+    install_journal::clear(layout);
+    result
+}
+
+async fn stage_and_promote(
+    layout: &PayloadLayout,
+    tag: &str,
+    staging_dir: &Path,
+    cached: &[CachedAsset],
+) -> ApiResult<()> {
     if staging_dir.exists() {
-        std::fs::remove_dir_all(&staging_dir).map_err(|e| {
+        std::fs::remove_dir_all(staging_dir).map_err(|e| {
             ApiError::new(
                 "io_remove_failed",
                 format!("remove {}: {e}", staging_dir.display()),
@@ -61,30 +330,17 @@ async fn install_fresh(
         })?;
     }
 
-    std::fs::create_dir_all(&staging_dir).map_err(|e| {
-        ApiError::new(
-            "io_mkdir_failed",
-            format!("create dir {}: {e}", staging_dir.display()),
-        )
-    })?;
-
-    let bundle = cached
-        .iter()
-        .find(|a| a.plan.kind == "bundle")
-        .ok_or_else(|| ApiError::new("install_plan_invalid", "missing bundle asset"))?;
-
-    extract_zip_into(&bundle.path, &staging_dir).await?;
-    install_additional_assets(&staging_dir, cached)?;
-    ensure_bundle_executables(&staging_dir)?;
+    stage_version(staging_dir, cached).await?;
 
-    let version_dir = layout.version_dir(&plan.tag);
+    let version_dir = layout.version_dir(tag);
     if version_dir.exists() {
         // Another process installed it while we were staging.
-        std::fs::remove_dir_all(&staging_dir).ok();
+        std::fs::remove_dir_all(staging_dir).ok();
         return Ok(());
     }
 
-    std::fs::rename(&staging_dir, &version_dir).map_err(|e| {
+    install_journal::begin(layout, InstallJournalPhase::Promote, tag)?;
+    std::fs::rename(staging_dir, &version_dir).map_err(|e| {
         ApiError::new(
             "io_rename_failed",
             format!(
@@ -93,9 +349,51 @@ async fn install_fresh(
                 version_dir.display()
             ),
         )
+    })
+}
+
+/// Build a complete version directory in `staging_dir` from cached assets, inventory included.
+pub(crate) async fn stage_version(staging_dir: &Path, cached: &[CachedAsset]) -> ApiResult<()> {
+    std::fs::create_dir_all(staging_dir).map_err(|e| {
+        ApiError::new(
+            "io_mkdir_failed",
+            format!("create dir {}: {e}", staging_dir.display()),
+        )
     })?;
 
-    Ok(())
+    let bundle = cached
+        .iter()
+        .find(|a| a.plan.kind == "bundle")
+        .ok_or_else(|| ApiError::new("install_plan_invalid", "missing bundle asset"))?;
+
+    extract_zip_into(&bundle.path, staging_dir).await?;
+    install_additional_assets(staging_dir, cached)?;
+    ensure_bundle_executables(staging_dir)?;
+
+    let inventory = build_inventory(staging_dir)?;
+    write_json_atomic(&staging_dir.join(VERSION_INVENTORY_FILE), &inventory)
+}
+
+/// Add the files copied into an existing version to its inventory, when it has one.
+fn record_additional_inventory(
+    layout: &PayloadLayout,
+    tag: &str,
+    cached: &[CachedAsset],
+) -> ApiResult<()> {
+    let Some(mut inventory) = read_version_inventory(layout, tag)? else {
+        return Ok(());
+    };
+    for a in cached.iter().filter(|a| a.plan.kind != "bundle") {
+        let rel = asset_relative_path(&a.plan)?;
+        inventory.files.insert(
+            inventory_path(Path::new(""), &rel),
+            InventoryFile {
+                size: a.plan.size,
+                sha256: a.plan.sha256.clone(),
+            },
+        );
+    }
+    write_json_atomic(&layout.version_inventory_file(tag), &inventory)
 }
 
 fn install_additional_assets(version_dir: &Path, cached: &[CachedAsset]) -> ApiResult<()> {
@@ -130,16 +428,38 @@ fn install_additional_assets(version_dir: &Path, cached: &[CachedAsset]) -> ApiR
     Ok(())
 }
 
+/// Merge `cached` into the version's asset record; installing another profile adds to it.
+fn record_version_assets(
+    layout: &PayloadLayout,
+    tag: &str,
+    cached: &[CachedAsset],
+) -> ApiResult<()> {
+    let mut record = read_version_assets(layout, tag).unwrap_or_default();
+    for a in cached {
+        let asset = VersionAsset {
+            sha256: a.plan.sha256.clone(),
+            filename: a.plan.filename.clone(),
+            kind: a.plan.kind.clone(),
+        };
+        if !record.assets.contains(&asset) {
+            record.assets.push(asset);
+        }
+    }
+    write_json_atomic(&layout.version_assets_file(tag), &record)
+}
+
 fn asset_relative_path(asset: &crate::models::AssetPlan) -> ApiResult<PathBuf> {
     if asset.filename.is_empty() {
         return Err(ApiError::new("asset_invalid", "missing filename"));
     }
 
+    if let Some(dir) = host_app_for_extension_kind(&asset.kind)
+        .and_then(|descriptor| descriptor.extension_install_dir)
+    {
+        return Ok(PathBuf::from(dir).join(&asset.filename));
+    }
     match asset.kind.as_str() {
         "firmware" => Ok(PathBuf::from("firmware").join(&asset.filename)),
-        "bitwig-extension" => Ok(PathBuf::from("integrations")
-            .join("bitwig")
-            .join(&asset.filename)),
         other => Ok(PathBuf::from("assets").join(other).join(&asset.filename)),
     }
 }
@@ -148,7 +468,7 @@ async fn extract_zip_into(zip_path: &Path, dest_dir: &Path) -> ApiResult<()> {
     let zip_path = zip_path.to_path_buf();
     let dest_dir = dest_dir.to_path_buf();
 
-    tauri::async_runtime::spawn_blocking(move || extract_zip_into_blocking(&zip_path, &dest_dir))
+    tokio::task::spawn_blocking(move || extract_zip_into_blocking(&zip_path, &dest_dir))
         .await
         .map_err(|e| ApiError::new("internal_error", format!("extract task failed: {e}")))??;
     Ok(())
@@ -252,7 +572,13 @@ fn ensure_bundle_executables(_version_dir: &Path) -> ApiResult<()> {
     Ok(())
 }
 
-pub(crate) fn set_current(layout: &PayloadLayout, tag: &str) -> ApiResult<()> {
+/// Release the `current` link points to, if it is a link.
+pub fn current_tag(layout: &PayloadLayout) -> Option<String> {
+    let target = std::fs::read_link(layout.current_dir()).ok()?;
+    Some(target.file_name()?.to_str()?.to_string())
+}
+
+pub fn set_current(layout: &PayloadLayout, tag: &str) -> ApiResult<()> {
     let current = layout.current_dir();
     let target = layout.version_dir(tag);
     if !target.exists() {
@@ -261,59 +587,68 @@ pub(crate) fn set_current(layout: &PayloadLayout, tag: &str) -> ApiResult<()> {
             format!("missing installed version dir: {}", target.display()),
         ));
     }
+    install_journal::begin(layout, InstallJournalPhase::SwitchCurrent, tag)?;
+    let result = switch_current(&current, &target);
+    install_journal::clear(layout);
+    result
+}
 
-    if std::fs::symlink_metadata(&current).is_ok() {
-        #[cfg(windows)]
-        {
-            remove_windows_junction(&current)?;
-        }
-
-        #[cfg(unix)]
-        {
-            // Symlink removal.
-            if std::fs::remove_file(&current).is_err() {
-                let _ = std::fs::remove_dir_all(&current);
-            }
-        }
+#[cfg(windows)]
+fn switch_current(current: &Path, target: &Path) -> ApiResult<()> {
+    if std::fs::symlink_metadata(current).is_ok() {
+        remove_windows_junction(current)?;
     }
 
-    #[cfg(windows)]
-    {
-        // Prefer a junction for a stable, non-admin `current/` pointer.
-        let mut cmd = std::process::Command::new("cmd");
-        process::no_console_window_std(&mut cmd);
-        let out = cmd
-            .args(["/c", "mklink", "/J"])
-            .arg(&current)
-            .arg(&target)
-            .output()
-            .map_err(|e| ApiError::new("io_exec_failed", format!("mklink: {e}")))?;
-
-        if !out.status.success() {
-            return Err(
-                ApiError::new("current_link_failed", "failed to create current junction")
-                    .with_details(serde_json::json!({
-                        "exit_code": out.status.code(),
-                        "stdout": String::from_utf8_lossy(&out.stdout).trim(),
-                        "stderr": String::from_utf8_lossy(&out.stderr).trim(),
-                        "current": current.display().to_string(),
-                        "target": target.display().to_string(),
-                    })),
-            );
-        }
+    // Prefer a junction for a stable, non-admin `current/` pointer.
+    let mut cmd = std::process::Command::new("cmd");
+    process::no_console_window_std(&mut cmd);
+    let out = cmd
+        .args(["/c", "mklink", "/J"])
+        .arg(current)
+        .arg(target)
+        .output()
+        .map_err(|e| ApiError::new("io_exec_failed", format!("mklink: {e}")))?;
+
+    if !out.status.success() {
+        return Err(
+            ApiError::new("current_link_failed", "failed to create current junction").with_details(
+                serde_json::json!({
+                    "exit_code": out.status.code(),
+                    "stdout": String::from_utf8_lossy(&out.stdout).trim(),
+                    "stderr": String::from_utf8_lossy(&out.stderr).trim(),
+                    "current": current.display().to_string(),
+                    "target": target.display().to_string(),
+                }),
+            ),
+        );
     }
+    Ok(())
+}
 
-    #[cfg(unix)]
-    {
-        std::os::unix::fs::symlink(&target, &current).map_err(|e| {
-            ApiError::new(
-                "current_link_failed",
-                format!("symlink {} -> {}: {e}", current.display(), target.display()),
-            )
-        })?;
-    }
+/// Create the new link next to `current` and rename it over the old one, so `current` never
+/// goes missing.
+#[cfg(unix)]
+fn switch_current(current: &Path, target: &Path) -> ApiResult<()> {
+    let staged = current.with_extension("tmp");
+    let _ = std::fs::remove_file(&staged);
+    std::os::unix::fs::symlink(target, &staged).map_err(|e| {
+        ApiError::new(
+            "current_link_failed",
+            format!("symlink {} -> {}: {e}", staged.display(), target.display()),
+        )
+    })?;
 
-    Ok(())
+    // A plain directory cannot be renamed over; only links are swapped in place.
+    if std::fs::symlink_metadata(current).is_ok_and(|meta| meta.is_dir()) {
+        let _ = std::fs::remove_dir_all(current);
+    }
+    std::fs::rename(&staged, current).map_err(|e| {
+        let _ = std::fs::remove_file(&staged);
+        ApiError::new(
+            "current_link_failed",
+            format!("rename {} -> {}: {e}", staged.display(), current.display()),
+        )
+    })
 }
 
 #[cfg(windows)]
@@ -0,0 +1,212 @@
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ms_manager_core::{
+    rollback_target, ArtifactSource, InstallHistoryAction, InstallHistoryEntry, InstallState,
+    INSTALL_STATE_SCHEMA,
+};
+
+use crate::api_error::{ApiError, ApiResult};
+use crate::layout::PayloadLayout;
+use crate::services::{bridge_ctl, install};
+use crate::state::AppState;
+
+/// Append one entry to the install history (one JSON object per line).
+pub fn append(layout: &PayloadLayout, entry: &InstallHistoryEntry) -> ApiResult<()> {
+    let path = layout.install_history_file();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            ApiError::new(
+                "io_mkdir_failed",
+                format!("create dir {}: {e}", parent.display()),
+            )
+        })?;
+    }
+
+    let mut line = serde_json::to_vec(entry)
+        .map_err(|e| ApiError::new("json_serialize_failed", e.to_string()))?;
+    line.push(b'\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(&line))
+        .map_err(|e| ApiError::new("io_write_failed", format!("append {}: {e}", path.display())))
+}
+
+/// History entries, oldest first. Lines that do not parse (e.g. a torn final write) are skipped.
+pub fn read(layout: &PayloadLayout) -> ApiResult<Vec<InstallHistoryEntry>> {
+    let path = layout.install_history_file();
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(ApiError::new(
+                "io_read_failed",
+                format!("read {}: {e}", path.display()),
+            ))
+        }
+    };
+    Ok(text
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+pub fn entry(
+    action: InstallHistoryAction,
+    next: &InstallState,
+    previous: Option<&InstallState>,
+) -> InstallHistoryEntry {
+    InstallHistoryEntry {
+        at_ms: now_ms(),
+        action,
+        channel: next.channel,
+        profile: next.profile.clone(),
+        tag: next.tag.clone(),
+        previous_tag: previous.map(|p| p.tag.clone()),
+    }
+}
+
+/// Re-activate the previously active release, which must still be installed.
+///
+/// This is an explicit user request, so like a pinned install it is not subject to the
+/// anti-rollback check. Unpinned instances that followed the active release keep following
+/// it, even when the rollback crosses channels.
+pub async fn rollback(state: &AppState) -> ApiResult<InstallState> {
+    let _install = state.install_lock().await?;
+    let layout = state.layout_get();
+    let installed = state
+        .install_state_get()
+        .ok_or_else(|| ApiError::new("rollback_unavailable", "no release is active"))?;
+    let history = read(&layout)?;
+    let target = rollback_target(&history, &installed.tag, |tag| {
+        layout.version_dir(tag).is_dir()
+    })
+    .ok_or_else(|| {
+        ApiError::new(
+            "rollback_unavailable",
+            format!(
+                "no earlier installed release to roll back to from {}",
+                installed.tag
+            ),
+        )
+    })?;
+
+    let bindings = state.bridge_instances_get();
+    bridge_ctl::shutdown_enabled(&bindings).await;
+    install::set_current(&layout, &target.tag)?;
+
+    let next = state.install_state_activate(
+        InstallState {
+            schema: INSTALL_STATE_SCHEMA,
+            channel: target.channel,
+            profile: target.profile.clone(),
+            tag: target.tag.clone(),
+        },
+        InstallHistoryAction::Rollback,
+    )?;
+
+    if next.channel != installed.channel {
+        for binding in bindings.instances.iter().filter(|binding| {
+            binding.artifact_source == ArtifactSource::Installed
+                && binding.installed_pinned_tag.is_none()
+                && binding.installed_channel == Some(installed.channel)
+        }) {
+            state.bridge_instance_set_installed_release(
+                &binding.instance_id,
+                next.channel,
+                None,
+            )?;
+        }
+    }
+
+    Ok(next)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use ms_manager_core::Channel;
+
+    use super::*;
+    use crate::test_support::TestRoot;
+
+    fn activate(state: &AppState, tag: &str) {
+        let layout = state.layout_get();
+        std::fs::create_dir_all(layout.version_dir(tag)).unwrap();
+        install::set_current(&layout, tag).unwrap();
+        state
+            .install_state_activate(
+                InstallState {
+                    schema: INSTALL_STATE_SCHEMA,
+                    channel: Channel::Stable,
+                    profile: "default".to_string(),
+                    tag: tag.to_string(),
+                },
+                InstallHistoryAction::Install,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn read_skips_torn_lines() {
+        let root = TestRoot::new("install-history-torn");
+        let state = root.state();
+        activate(&state, "v1.0.0");
+        let path = state.layout_get().install_history_file();
+        let mut text = std::fs::read_to_string(&path).unwrap();
+        text.push_str("{\"at_ms\":1,\"act");
+        std::fs::write(&path, text).unwrap();
+
+        let entries = read(&state.layout_get()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].tag, "v1.0.0");
+        assert_eq!(entries[0].previous_tag, None);
+    }
+
+    #[test]
+    fn rollback_repoints_current_and_records_itself() {
+        let root = TestRoot::new("install-history-rollback");
+        let state = root.state();
+        activate(&state, "v1.0.0");
+        activate(&state, "v1.1.0");
+
+        let next = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(rollback(&state))
+            .unwrap();
+
+        let layout = state.layout_get();
+        assert_eq!(next.tag, "v1.0.0");
+        assert_eq!(state.install_state_get().unwrap().tag, "v1.0.0");
+        assert_eq!(
+            std::fs::read_link(layout.current_dir()).unwrap(),
+            layout.version_dir("v1.0.0")
+        );
+        let last = read(&layout).unwrap().pop().unwrap();
+        assert_eq!(last.action, InstallHistoryAction::Rollback);
+        assert_eq!(last.previous_tag.as_deref(), Some("v1.1.0"));
+    }
+
+    #[test]
+    fn rollback_without_history_is_unavailable() {
+        let root = TestRoot::new("install-history-empty");
+        let state = root.state();
+        let err = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(rollback(&state))
+            .unwrap_err();
+        assert_eq!(err.code, "rollback_unavailable");
+    }
+}
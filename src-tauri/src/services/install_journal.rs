@@ -0,0 +1,330 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ms_manager_core::InstallState;
+use serde::{Deserialize, Serialize};
+
+use crate::api_error::ApiResult;
+use crate::layout::{is_version_scratch_dir, PayloadLayout};
+use crate::models::{InstallJournalPhase, PayloadRecoveryReport};
+use crate::services::install;
+use crate::services::payload_lock::PayloadLock;
+use crate::storage::{read_json_optional, write_json_atomic};
+
+/// Written before each install step and removed once it completes, so a journal found on load
+/// means the step was cut short.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallJournal {
+    pub phase: InstallJournalPhase,
+    pub tag: String,
+    /// Release `current` pointed to when the step started.
+    pub previous_current: Option<String>,
+    /// For diagnostics only: PIDs are reused after a reboot, so liveness comes from the
+    /// payload lock instead.
+    pub pid: u32,
+    pub started_at_ms: u64,
+}
+
+pub fn begin(layout: &PayloadLayout, phase: InstallJournalPhase, tag: &str) -> ApiResult<()> {
+    let journal = InstallJournal {
+        phase,
+        tag: tag.to_string(),
+        previous_current: install::current_tag(layout),
+        pid: std::process::id(),
+        started_at_ms: now_ms(),
+    };
+    write_json_atomic(&layout.install_journal_file(), &journal)
+}
+
+pub fn clear(layout: &PayloadLayout) {
+    let _ = std::fs::remove_file(layout.install_journal_file());
+}
+
+/// Undo what an interrupted install step left behind.
+///
+/// Scratch directories are removed, a version a repair had moved aside is put back, and a
+/// missing or dangling `current` is pointed at the installed release again (or the one it
+/// pointed to before the step). Returns `None` when there was nothing to do, and also when a step
+/// still holds the [`PayloadLock`]: its journal and scratch directories are in use, not stale.
+pub fn recover(
+    layout: &PayloadLayout,
+    installed: Option<&InstallState>,
+) -> Option<PayloadRecoveryReport> {
+    // Without the lock (e.g. a read-only payload) nothing else can be writing either.
+    let _lock = match PayloadLock::try_acquire(layout) {
+        Ok(None) => return None,
+        Ok(Some(lock)) => Some(lock),
+        Err(_) => None,
+    };
+    let journal_path = layout.install_journal_file();
+    let journal = read_json_optional::<InstallJournal>(&journal_path)
+        .ok()
+        .flatten();
+
+    let mut report = PayloadRecoveryReport {
+        interrupted: journal.as_ref().map(|j| j.phase),
+        interrupted_tag: journal.as_ref().map(|j| j.tag.clone()),
+        removed_dirs: Vec::new(),
+        restored_versions: Vec::new(),
+        current_restored: None,
+        failed: Vec::new(),
+    };
+
+    recover_version_dirs(layout, &mut report);
+
+    let current = layout.current_dir();
+    let has_link = std::fs::symlink_metadata(&current).is_ok();
+    if !current.is_dir() && (has_link || installed.is_some() || journal.is_some()) {
+        let candidates = installed
+            .map(|installed| installed.tag.clone())
+            .into_iter()
+            .chain(journal.as_ref().and_then(|j| j.previous_current.clone()));
+        let mut restored = false;
+        for tag in candidates {
+            if !layout.version_dir(&tag).is_dir() {
+                continue;
+            }
+            match install::set_current(layout, &tag) {
+                Ok(()) => {
+                    report.current_restored = Some(tag);
+                    restored = true;
+                    break;
+                }
+                Err(err) => report
+                    .failed
+                    .push(format!("current -> {tag}: {}", err.message)),
+            }
+        }
+        if !restored && installed.is_some() {
+            report
+                .failed
+                .push("current: no installed release to point it at".to_string());
+        }
+    }
+
+    // Also drops a journal that no longer parses (e.g. a torn write).
+    clear(layout);
+
+    let nothing_done = report.interrupted.is_none()
+        && report.removed_dirs.is_empty()
+        && report.restored_versions.is_empty()
+        && report.current_restored.is_none()
+        && report.failed.is_empty();
+    (!nothing_done).then_some(report)
+}
+
+fn recover_version_dirs(layout: &PayloadLayout, report: &mut PayloadRecoveryReport) {
+    let Ok(entries) = std::fs::read_dir(layout.versions_dir()) else {
+        return;
+    };
+    let mut scratch = entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|name| is_version_scratch_dir(name))
+        .collect::<Vec<_>>();
+    scratch.sort();
+
+    for name in scratch {
+        let path = layout.versions_dir().join(&name);
+        // A repair swap cut between its two renames leaves only `<tag>.old`.
+        if let Some(tag) = name.strip_suffix(".old") {
+            let version_dir = layout.version_dir(tag);
+            if !version_dir.exists() {
+                match std::fs::rename(&path, &version_dir) {
+                    Ok(()) => report.restored_versions.push(tag.to_string()),
+                    Err(e) => report
+                        .failed
+                        .push(format!("restore {}: {e}", path.display())),
+                }
+                continue;
+            }
+        }
+        remove_scratch_dir(&path, report);
+    }
+}
+
+fn remove_scratch_dir(path: &Path, report: &mut PayloadRecoveryReport) {
+    match std::fs::remove_dir_all(path) {
+        Ok(()) => report.removed_dirs.push(path.display().to_string()),
+        Err(e) => report
+            .failed
+            .push(format!("remove {}: {e}", path.display())),
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use ms_manager_core::{Channel, INSTALL_STATE_SCHEMA};
+
+    use super::*;
+    use crate::state::AppState;
+    use crate::test_support::TestRoot;
+
+    fn installed(tag: &str) -> InstallState {
+        InstallState {
+            schema: INSTALL_STATE_SCHEMA,
+            channel: Channel::Stable,
+            profile: "default".to_string(),
+            tag: tag.to_string(),
+        }
+    }
+
+    #[test]
+    fn interrupted_install_is_cleaned_up_and_current_restored() {
+        let root = TestRoot::new("install-journal-stage");
+        let layout = root.layout();
+        std::fs::create_dir_all(layout.version_dir("v1.0.0")).unwrap();
+        install::set_current(&layout, "v1.0.0").unwrap();
+
+        // Cut short while staging v1.1.0, with `current` already removed.
+        begin(&layout, InstallJournalPhase::Stage, "v1.1.0").unwrap();
+        std::fs::create_dir_all(layout.version_staging_dir("v1.1.0").join("bin")).unwrap();
+        std::fs::remove_file(layout.current_dir()).unwrap();
+
+        let report = recover(&layout, Some(&installed("v1.0.0"))).unwrap();
+        assert_eq!(report.interrupted, Some(InstallJournalPhase::Stage));
+        assert_eq!(report.interrupted_tag.as_deref(), Some("v1.1.0"));
+        assert_eq!(report.removed_dirs.len(), 1);
+        assert_eq!(report.current_restored.as_deref(), Some("v1.0.0"));
+        assert!(report.failed.is_empty(), "{:?}", report.failed);
+        assert!(!layout.version_staging_dir("v1.1.0").exists());
+        assert!(!layout.install_journal_file().exists());
+        assert_eq!(install::current_tag(&layout).as_deref(), Some("v1.0.0"));
+
+        assert!(recover(&layout, Some(&installed("v1.0.0"))).is_none());
+    }
+
+    #[test]
+    fn failed_stage_clears_the_journal_and_staging_dir() {
+        let root = TestRoot::new("install-journal-failed-stage");
+        let layout = root.layout();
+        let plan = crate::models::InstallPlan {
+            channel: Channel::Stable,
+            tag: "v1.1.0".to_string(),
+            profile: "default".to_string(),
+            platform: ms_manager_core::Platform::current().unwrap(),
+            assets: Vec::new(),
+            notes: None,
+        };
+
+        let err = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(install::apply_install(&layout, &plan, &[], false))
+            .unwrap_err();
+        assert_eq!(err.code, "install_plan_invalid");
+        assert!(!layout.install_journal_file().exists());
+        assert!(!layout.version_staging_dir("v1.1.0").exists());
+        assert!(recover(&layout, None).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn set_current_swaps_the_link_in_place() {
+        let root = TestRoot::new("install-journal-swap");
+        let layout = root.layout();
+        std::fs::create_dir_all(layout.version_dir("v1.0.0")).unwrap();
+        std::fs::create_dir_all(layout.version_dir("v1.1.0")).unwrap();
+        install::set_current(&layout, "v1.0.0").unwrap();
+
+        install::set_current(&layout, "v1.1.0").unwrap();
+        assert_eq!(install::current_tag(&layout).as_deref(), Some("v1.1.0"));
+        assert!(!layout.current_dir().with_extension("tmp").exists());
+        assert!(!layout.install_journal_file().exists());
+    }
+
+    #[test]
+    fn interrupted_repair_swap_puts_the_old_copy_back() {
+        let root = TestRoot::new("install-journal-repair");
+        let layout = root.layout();
+        std::fs::create_dir_all(layout.version_dir("v1.0.0")).unwrap();
+        std::fs::write(layout.version_dir("v1.0.0").join("marker"), "old").unwrap();
+        install::set_current(&layout, "v1.0.0").unwrap();
+
+        begin(&layout, InstallJournalPhase::RepairSwap, "v1.0.0").unwrap();
+        std::fs::create_dir_all(layout.version_repair_dir("v1.0.0")).unwrap();
+        std::fs::rename(
+            layout.version_dir("v1.0.0"),
+            layout.version_replaced_dir("v1.0.0"),
+        )
+        .unwrap();
+
+        let report = recover(&layout, Some(&installed("v1.0.0"))).unwrap();
+        assert_eq!(report.restored_versions, ["v1.0.0"]);
+        assert_eq!(report.removed_dirs.len(), 1);
+        assert_eq!(report.current_restored, None);
+        assert_eq!(
+            std::fs::read_to_string(layout.version_dir("v1.0.0").join("marker")).unwrap(),
+            "old"
+        );
+        assert!(!layout.version_repair_dir("v1.0.0").exists());
+    }
+
+    #[test]
+    fn missing_current_falls_back_to_the_previous_release_on_load() {
+        let root = TestRoot::new("install-journal-load");
+        let layout = root.layout();
+        std::fs::create_dir_all(layout.version_dir("v1.0.0")).unwrap();
+        install::set_current(&layout, "v1.0.0").unwrap();
+        write_json_atomic(&layout.install_state_file(), &installed("v2.0.0")).unwrap();
+
+        // Cut between removing the old link and creating the new one, and the
+        // recorded release is gone too.
+        begin(&layout, InstallJournalPhase::SwitchCurrent, "v2.0.0").unwrap();
+        std::fs::remove_file(layout.current_dir()).unwrap();
+
+        let state = root.state();
+
+        let report = state.payload_recovery_get().unwrap();
+        assert_eq!(report.interrupted, Some(InstallJournalPhase::SwitchCurrent));
+        assert_eq!(report.current_restored.as_deref(), Some("v1.0.0"));
+        assert_eq!(install::current_tag(&layout).as_deref(), Some("v1.0.0"));
+    }
+
+    #[test]
+    fn load_recovers_only_the_payload_root_in_use() {
+        let root = TestRoot::new("install-journal-override");
+        let settings_layout = root.layout();
+        let override_layout =
+            PayloadLayout::resolve(Some(root.path().join("override").to_str().unwrap())).unwrap();
+        begin(&settings_layout, InstallJournalPhase::Stage, "v1.1.0").unwrap();
+        std::fs::create_dir_all(settings_layout.version_staging_dir("v1.1.0")).unwrap();
+        begin(&override_layout, InstallJournalPhase::Stage, "v1.2.0").unwrap();
+
+        let override_root = override_layout.root().display().to_string();
+        let state =
+            AppState::load_from_settings_path(root.settings_file(), Some(&override_root)).unwrap();
+
+        let report = state.payload_recovery_get().unwrap();
+        assert_eq!(report.interrupted_tag.as_deref(), Some("v1.2.0"));
+        assert!(settings_layout.install_journal_file().exists());
+        assert!(settings_layout.version_staging_dir("v1.1.0").exists());
+        assert!(!override_layout.install_journal_file().exists());
+    }
+
+    #[test]
+    fn journal_of_a_step_holding_the_lock_is_left_alone() {
+        let root = TestRoot::new("install-journal-locked");
+        let layout = root.layout();
+        begin(&layout, InstallJournalPhase::Stage, "v1.1.0").unwrap();
+        std::fs::create_dir_all(layout.version_staging_dir("v1.1.0")).unwrap();
+
+        let lock = PayloadLock::try_acquire(&layout).unwrap().unwrap();
+        assert!(recover(&layout, None).is_none());
+        assert!(layout.install_journal_file().exists());
+        assert!(layout.version_staging_dir("v1.1.0").exists());
+
+        // Once the step is gone, a journal naming a live PID is still stale.
+        drop(lock);
+        let report = recover(&layout, None).unwrap();
+        assert_eq!(report.interrupted_tag.as_deref(), Some("v1.1.0"));
+        assert!(!layout.version_staging_dir("v1.1.0").exists());
+    }
+}
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use ms_manager_core::{ArtifactSource, BridgeInstanceBinding, Channel, InstallState};
+use ms_manager_core::{ArtifactSource, BridgeInstanceBinding, BridgeMode, Channel, InstallState};
 
 use crate::api_error::{ApiError, ApiResult};
 use crate::layout::PayloadLayout;
@@ -55,6 +55,17 @@ pub fn installed_artifact_health_for_binding(
     let bridge = installed_oc_bridge_exe_for_tag(layout, binding.installed_pinned_tag.as_deref());
     let loader = installed_loader_exe_for_tag(layout, binding.installed_pinned_tag.as_deref());
 
+    if binding.mode == BridgeMode::NativeSim {
+        let sim = installed_native_sim_exe_for_tag(layout, binding.installed_pinned_tag.as_deref());
+        return ArtifactHealth {
+            source: ArtifactSource::Installed,
+            ready: bridge.exists() && sim.exists(),
+            config_path: None,
+            message: (!bridge.exists() || !sim.exists())
+                .then(|| "installed version has no native simulator for this instance".to_string()),
+        };
+    }
+
     if !bridge.exists() || !loader.exists() {
         return ArtifactHealth {
             source: ArtifactSource::Installed,
@@ -120,6 +131,16 @@ pub fn installed_loader_exe_for_tag(layout: &PayloadLayout, tag: Option<&str>) -
     }
 }
 
+pub fn installed_native_sim_exe_for_tag(layout: &PayloadLayout, tag: Option<&str>) -> PathBuf {
+    match tag
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+    {
+        Some(tag) => installed_bin(layout.version_dir(tag).join("bin"), "midi-studio-sim"),
+        None => installed_bin(layout.current_dir().join("bin"), "midi-studio-sim"),
+    }
+}
+
 pub fn installed_core_file_tool_exe(layout: &PayloadLayout) -> PathBuf {
     installed_bin(layout.current_dir().join("bin"), "ms-core-file-tool")
 }
@@ -226,7 +247,9 @@ pub fn installed_firmware_for_profile(
 mod tests {
     use std::time::{SystemTime, UNIX_EPOCH};
 
-    use ms_manager_core::{BridgeApp, BridgeMode, FirmwareTarget, INSTALL_STATE_SCHEMA};
+    use ms_manager_core::{
+        BridgeApp, BridgeMode, FirmwareTarget, UpdatePolicy, INSTALL_STATE_SCHEMA,
+    };
 
     use super::*;
 
@@ -294,14 +317,16 @@ mod tests {
             controller_serial: "17081760".to_string(),
             controller_vid: 0x16c0,
             controller_pid: 0x0489,
-            target: FirmwareTarget::Standalone,
+            target: FirmwareTarget::standalone(),
             artifact_source: ArtifactSource::Installed,
             installed_channel: Some(Channel::Beta),
             installed_pinned_tag: Some(tag.to_string()),
+            installed_version_range: None,
             host_udp_port: 9000,
             control_port: 7999,
             log_broadcast_port: 9999,
             enabled: true,
+            update_policy: UpdatePolicy::default(),
         };
 
         assert!(!installed_bin(
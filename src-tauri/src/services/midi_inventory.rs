@@ -1,6 +1,7 @@
+use crate::models::{DeviceStatus, MidiInventoryProvider, MidiInventoryStatus};
+#[cfg(windows)]
 use crate::models::{
-    DeviceStatus, DeviceTarget, MidiInventoryProvider, MidiInventoryStatus, MidiMatchConfidence,
-    MidiPortDirection, MidiPortInfo, MidiPortMatch,
+    DeviceTarget, MidiMatchConfidence, MidiPortDirection, MidiPortInfo, MidiPortMatch,
 };
 
 #[cfg(windows)]
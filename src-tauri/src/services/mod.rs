@@ -1,9 +1,12 @@
+pub mod app_update;
 pub mod artifact_paths;
 pub mod artifact_resolver;
 pub mod assets;
+#[cfg(feature = "desktop")]
 pub mod bridge;
 pub mod bridge_ctl;
 pub mod bridge_instances;
+#[cfg(feature = "desktop")]
 pub mod bridge_logs;
 pub mod bridge_process;
 pub mod bridge_status;
@@ -11,16 +14,33 @@ pub mod controller_fs;
 mod controller_fs_job;
 pub mod device;
 pub mod distribution;
+pub mod download;
 pub mod flash;
+pub mod http_client;
 pub mod install;
+pub mod install_history;
+pub mod install_journal;
 pub mod installed_artifacts;
+#[cfg(feature = "desktop")]
 pub mod local_fs_watcher;
+#[cfg(feature = "desktop")]
 pub mod manager_autostart;
+#[cfg(feature = "desktop")]
 pub mod midi_inventory;
+pub mod native_sim;
+pub mod offline_bundle;
 pub mod payload;
+pub mod payload_gc;
+pub mod payload_lock;
 pub mod process;
+#[cfg(feature = "desktop")]
 pub mod startup;
+#[cfg(feature = "desktop")]
 pub mod tray;
-pub mod ux_recorder;
+#[cfg(feature = "desktop")]
+pub(crate) mod ux_recorder;
+pub mod update_check;
+pub mod version_audit;
 pub mod workspace_artifacts;
+#[cfg(feature = "desktop")]
 pub mod workspace_firmware;
@@ -0,0 +1,245 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::process::Stdio;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use ms_manager_core::{BridgeInstanceBinding, BridgeMode};
+use tokio::process::{Child, Command};
+
+use crate::api_error::{ApiError, ApiResult};
+use crate::layout::PayloadLayout;
+use crate::services::bridge_instances::native_sim_port;
+use crate::services::{artifact_resolver, bridge_process, process};
+
+/// Simulators started by this manager, by instance id.
+static SIMULATORS: OnceLock<Mutex<HashMap<String, Child>>> = OnceLock::new();
+
+fn simulators() -> MutexGuard<'static, HashMap<String, Child>> {
+    SIMULATORS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Why the last attempt to start an instance's simulator failed, by instance id.
+static SPAWN_ERRORS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+
+fn spawn_errors() -> MutexGuard<'static, HashMap<String, String>> {
+    SPAWN_ERRORS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Start the simulator of a `native_sim` instance unless it is already running.
+///
+/// Its output goes to `sim.log` and its virtual storage to `storage/`, both under the instance's
+/// [`PayloadLayout::native_sim_dir`]. A failure is kept for [`last_spawn_error`] until the
+/// simulator starts.
+pub fn ensure_running(layout: &PayloadLayout, binding: &BridgeInstanceBinding) -> ApiResult<()> {
+    if binding.mode != BridgeMode::NativeSim {
+        return Ok(());
+    }
+
+    let mut simulators = simulators();
+    let mut exited = None;
+    if let Some(child) = simulators.get_mut(&binding.instance_id) {
+        match child.try_wait() {
+            Ok(None) => return Ok(()),
+            Ok(Some(status)) => exited = Some(status.to_string()),
+            Err(_) => {}
+        }
+        simulators.remove(&binding.instance_id);
+    }
+
+    match spawn(layout, binding, exited.as_deref()) {
+        Ok(child) => {
+            simulators.insert(binding.instance_id.clone(), child);
+            spawn_errors().remove(&binding.instance_id);
+            Ok(())
+        }
+        Err(err) => {
+            spawn_errors().insert(binding.instance_id.clone(), err.message.clone());
+            Err(err)
+        }
+    }
+}
+
+fn spawn(
+    layout: &PayloadLayout,
+    binding: &BridgeInstanceBinding,
+    exited: Option<&str>,
+) -> ApiResult<Child> {
+    let exe = artifact_resolver::resolve_native_sim_exe_for_binding(layout, binding)?;
+    // A simulator left behind by an earlier run still holds the instance's port.
+    bridge_process::kill_native_sims(&exe, &binding.instance_id);
+
+    let dir = layout.native_sim_dir(&binding.instance_id);
+    let storage = dir.join("storage");
+    std::fs::create_dir_all(&storage).map_err(|e| {
+        ApiError::new(
+            "io_write_failed",
+            format!("create {}: {e}", storage.display()),
+        )
+    })?;
+    let log_path = dir.join("sim.log");
+    // Append, so a crash stays readable above the output of the restarted simulator.
+    let mut log = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .map_err(|e| {
+            ApiError::new(
+                "io_write_failed",
+                format!("open {}: {e}", log_path.display()),
+            )
+        })?;
+    writeln!(
+        log,
+        "{}",
+        restart_separator(exited, &chrono::Utc::now().to_rfc3339())
+    )
+    .map_err(|e| {
+        ApiError::new(
+            "io_write_failed",
+            format!("write {}: {e}", log_path.display()),
+        )
+    })?;
+    let log_err = log.try_clone().map_err(|e| {
+        ApiError::new(
+            "io_write_failed",
+            format!("open {}: {e}", log_path.display()),
+        )
+    })?;
+
+    let mut cmd = Command::new(&exe);
+    process::no_console_window(&mut cmd);
+    cmd.args(simulator_args(binding, &storage))
+        .current_dir(&dir)
+        .stdin(Stdio::null())
+        .stdout(log)
+        .stderr(log_err)
+        .kill_on_drop(true);
+    cmd.spawn().map_err(|e| {
+        ApiError::new(
+            "native_sim_spawn_failed",
+            format!("start {}: {e}", exe.display()),
+        )
+    })
+}
+
+fn restart_separator(exited: Option<&str>, at: &str) -> String {
+    match exited {
+        Some(status) => format!("--- simulator exited ({status}); restarted at {at} ---"),
+        None => format!("--- simulator started at {at} ---"),
+    }
+}
+
+fn simulator_args(binding: &BridgeInstanceBinding, storage: &std::path::Path) -> Vec<String> {
+    vec![
+        "--instance-id".to_string(),
+        binding.instance_id.clone(),
+        "--serial-number".to_string(),
+        binding.controller_serial.clone(),
+        "--port".to_string(),
+        native_sim_port(binding).to_string(),
+        "--profile".to_string(),
+        binding.target.profile_id().to_string(),
+        "--storage-dir".to_string(),
+        storage.display().to_string(),
+    ]
+}
+
+pub fn is_running(instance_id: &str) -> bool {
+    simulators()
+        .get_mut(instance_id)
+        .is_some_and(|child| matches!(child.try_wait(), Ok(None)))
+}
+
+/// The error of the last failed start of `instance_id`'s simulator, if it has not started since.
+pub fn last_spawn_error(instance_id: &str) -> Option<String> {
+    spawn_errors().get(instance_id).cloned()
+}
+
+/// Stop the simulator of `instance_id`; the supervisor starts it again while the instance is
+/// enabled.
+pub fn stop(instance_id: &str) {
+    if let Some(mut child) = simulators().remove(instance_id) {
+        let _ = child.start_kill();
+    }
+    spawn_errors().remove(instance_id);
+}
+
+/// Stop simulators whose instance is not in `keep`.
+pub fn stop_others(keep: &HashSet<&str>) {
+    simulators().retain(|instance_id, child| {
+        let keep = keep.contains(instance_id.as_str());
+        if !keep {
+            let _ = child.start_kill();
+        }
+        keep
+    });
+    spawn_errors().retain(|instance_id, _| keep.contains(instance_id.as_str()));
+}
+
+pub fn stop_all() {
+    stop_others(&HashSet::new());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ms_manager_core::{ArtifactSource, BridgeApp, Channel, FirmwareTarget, UpdatePolicy};
+
+    #[test]
+    fn simulator_args_carry_identity_port_and_storage() {
+        let binding = BridgeInstanceBinding {
+            instance_id: "bitwig-native-sim-sim1".to_string(),
+            display_name: None,
+            app: BridgeApp::Bitwig,
+            mode: BridgeMode::NativeSim,
+            controller_serial: "sim1".to_string(),
+            controller_vid: 0,
+            controller_pid: 0,
+            target: FirmwareTarget::standalone(),
+            artifact_source: ArtifactSource::Installed,
+            installed_channel: Some(Channel::Stable),
+            installed_pinned_tag: None,
+            installed_version_range: None,
+            host_udp_port: 9100,
+            control_port: 8001,
+            log_broadcast_port: 10001,
+            enabled: true,
+            update_policy: UpdatePolicy::default(),
+        };
+
+        let storage = std::path::Path::new("storage");
+        assert_eq!(
+            simulator_args(&binding, storage),
+            vec![
+                "--instance-id",
+                "bitwig-native-sim-sim1",
+                "--serial-number",
+                "sim1",
+                "--port",
+                "8502",
+                "--profile",
+                "default",
+                "--storage-dir",
+                "storage",
+            ]
+        );
+    }
+
+    #[test]
+    fn restart_separator_names_the_previous_exit() {
+        assert_eq!(
+            restart_separator(None, "2026-01-02T03:04:05+00:00"),
+            "--- simulator started at 2026-01-02T03:04:05+00:00 ---"
+        );
+        assert_eq!(
+            restart_separator(Some("exit status: 3"), "2026-01-02T03:04:05+00:00"),
+            "--- simulator exited (exit status: 3); restarted at 2026-01-02T03:04:05+00:00 ---"
+        );
+    }
+}
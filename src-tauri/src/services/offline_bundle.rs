@@ -0,0 +1,460 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use ms_manager_core::{
+    channel_for_tag, parse_manifest_json, select_install_set_assets, Channel, DistributionSource,
+    Keyring, Manifest, ManifestAsset, Platform,
+};
+use sha2::{Digest, Sha256};
+
+use crate::api_error::{ApiError, ApiResult};
+use crate::layout::PayloadLayout;
+use crate::models::{BundleInstallSet, ReleaseBundleExportReport, ReleaseBundleImportReport};
+use crate::services::assets::{
+    digest_hex_lower, sha256_file_hex, verify_asset_signature, write_asset_signature,
+};
+use crate::services::distribution::{ensure_manifest_valid, SignedManifest};
+
+pub const BUNDLE_MANIFEST_NAME: &str = "manifest.json";
+pub const BUNDLE_SIG_NAME: &str = "manifest.json.sig";
+
+/// Distribution source serving the releases seeded from offline bundles.
+///
+/// Imported manifests keep the `<tag>/manifest.json` layout of a directory source, so the regular
+/// resolution path (signature checks included) works on them without network access.
+pub fn offline_source(layout: &PayloadLayout) -> DistributionSource {
+    DistributionSource::Directory {
+        path: layout.offline_releases_dir().display().to_string(),
+    }
+}
+
+/// Import a release bundle: a zip with `manifest.json`, `manifest.json.sig` and asset files
+/// (by manifest filename) at its root.
+///
+/// The manifest signature is verified before anything is written; every asset found in the
+/// archive must match its manifest size and sha256. Assets missing from the archive are skipped,
+/// so a bundle may carry any subset of install sets.
+pub async fn import_bundle(
+    layout: &PayloadLayout,
+    keyring: Keyring,
+    bundle_path: &Path,
+) -> ApiResult<ReleaseBundleImportReport> {
+    let layout = layout.clone();
+    let bundle_path = bundle_path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        import_bundle_blocking(&layout, &keyring, &bundle_path)
+    })
+    .await
+    .map_err(|e| ApiError::new("internal_error", format!("bundle import task failed: {e}")))?
+}
+
+/// Write a release bundle for `profiles` on `platform` from the local asset cache.
+pub async fn export_bundle(
+    layout: &PayloadLayout,
+    signed: SignedManifest,
+    profiles: Vec<String>,
+    platform: Platform,
+    dest: &Path,
+) -> ApiResult<ReleaseBundleExportReport> {
+    let layout = layout.clone();
+    let dest = dest.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        export_bundle_blocking(&layout, &signed, &profiles, &platform, &dest)
+    })
+    .await
+    .map_err(|e| ApiError::new("internal_error", format!("bundle export task failed: {e}")))?
+}
+
+fn import_bundle_blocking(
+    layout: &PayloadLayout,
+    keyring: &Keyring,
+    bundle_path: &Path,
+) -> ApiResult<ReleaseBundleImportReport> {
+    let mut archive = open_archive(bundle_path)?;
+    let manifest_bytes = read_entry(&mut archive, BUNDLE_MANIFEST_NAME)?;
+    let sig_text = String::from_utf8(read_entry(&mut archive, BUNDLE_SIG_NAME)?)
+        .map_err(|_| ApiError::new("bundle_invalid", "manifest.json.sig is not valid UTF-8"))?;
+
+    let manifest = parse_manifest_json(&manifest_bytes)?;
+    let channel = Channel::from(manifest.channel);
+    if channel_for_tag(&manifest.tag) != Some(channel) {
+        return Err(ApiError::new(
+            "bundle_invalid",
+            format!(
+                "manifest tag {} does not belong to channel {}",
+                manifest.tag,
+                channel.as_str()
+            ),
+        ));
+    }
+    keyring.verify_manifest(channel, &manifest_bytes, &sig_text)?;
+    ensure_manifest_valid(&manifest)?;
+
+    let assets_imported = seed_asset_cache(layout, keyring, channel, &manifest, &mut archive)?;
+
+    let release_dir = layout.offline_releases_dir().join(&manifest.tag);
+    std::fs::create_dir_all(&release_dir).map_err(|e| {
+        ApiError::new(
+            "io_mkdir_failed",
+            format!("create dir {}: {e}", release_dir.display()),
+        )
+    })?;
+    write_file(&release_dir.join(BUNDLE_SIG_NAME), sig_text.as_bytes())?;
+    write_file(&release_dir.join(BUNDLE_MANIFEST_NAME), &manifest_bytes)?;
+
+    let install_sets = manifest
+        .install_sets
+        .iter()
+        .filter(|set| {
+            set.assets.iter().all(|id| {
+                manifest
+                    .assets
+                    .iter()
+                    .find(|asset| &asset.id == id)
+                    .is_some_and(|asset| is_cached(layout, asset))
+            })
+        })
+        .map(|set| BundleInstallSet {
+            id: set.id.clone(),
+            os: set.os.clone(),
+            arch: set.arch.clone(),
+        })
+        .collect();
+
+    Ok(ReleaseBundleImportReport {
+        channel,
+        tag: manifest.tag,
+        assets_imported,
+        install_sets,
+    })
+}
+
+fn export_bundle_blocking(
+    layout: &PayloadLayout,
+    signed: &SignedManifest,
+    profiles: &[String],
+    platform: &Platform,
+    dest: &Path,
+) -> ApiResult<ReleaseBundleExportReport> {
+    if profiles.is_empty() {
+        return Err(ApiError::new(
+            "invalid_profile",
+            "at least one profile is required",
+        ));
+    }
+
+    let mut assets = BTreeMap::new();
+    for profile in profiles {
+        for asset in select_install_set_assets(
+            &signed.manifest,
+            profile,
+            platform.os.as_str(),
+            platform.arch.as_str(),
+        )? {
+            assets.entry(asset.id.clone()).or_insert(asset);
+        }
+    }
+
+    let missing = assets
+        .values()
+        .filter(|asset| !is_cached(layout, asset))
+        .map(|asset| asset.id.clone())
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        return Err(ApiError::new(
+            "bundle_asset_missing",
+            format!("assets not in local cache: {}", missing.join(", ")),
+        )
+        .with_details(serde_json::json!({ "asset_ids": missing })));
+    }
+
+    let mut tmp = dest.as_os_str().to_owned();
+    tmp.push(".partial");
+    let tmp = PathBuf::from(tmp);
+    if let Err(err) = write_archive(layout, signed, assets.values(), &tmp) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(err);
+    }
+    std::fs::rename(&tmp, dest).map_err(|e| {
+        ApiError::new(
+            "io_rename_failed",
+            format!("rename {} -> {}: {e}", tmp.display(), dest.display()),
+        )
+    })?;
+
+    Ok(ReleaseBundleExportReport {
+        channel: Channel::from(signed.manifest.channel),
+        tag: signed.manifest.tag.clone(),
+        path: dest.display().to_string(),
+        assets: assets.into_keys().collect(),
+    })
+}
+
+fn write_archive<'a>(
+    layout: &PayloadLayout,
+    signed: &SignedManifest,
+    assets: impl Iterator<Item = &'a ManifestAsset>,
+    path: &Path,
+) -> ApiResult<()> {
+    let file = std::fs::File::create(path)
+        .map_err(|e| ApiError::new("io_write_failed", format!("create {}: {e}", path.display())))?;
+    let zip_err = |e: zip::result::ZipError| {
+        ApiError::new("zip_write_failed", format!("write {}: {e}", path.display()))
+    };
+    let io_err = |e: std::io::Error| {
+        ApiError::new("io_write_failed", format!("write {}: {e}", path.display()))
+    };
+
+    // Assets are already compressed archives or firmware images; store them as-is.
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let mut zip = zip::ZipWriter::new(file);
+
+    zip.start_file(BUNDLE_MANIFEST_NAME, options)
+        .map_err(zip_err)?;
+    zip.write_all(&signed.manifest_bytes).map_err(io_err)?;
+    zip.start_file(BUNDLE_SIG_NAME, options).map_err(zip_err)?;
+    zip.write_all(signed.sig_text.as_bytes()).map_err(io_err)?;
+
+    for asset in assets {
+        let src = layout.asset_cache_path(&asset.sha256, &asset.filename);
+        let mut f = std::fs::File::open(&src)
+            .map_err(|e| ApiError::new("io_read_failed", format!("open {}: {e}", src.display())))?;
+        zip.start_file(asset.filename.as_str(), options)
+            .map_err(zip_err)?;
+        std::io::copy(&mut f, &mut zip).map_err(io_err)?;
+    }
+
+    zip.finish().map_err(zip_err)?;
+    Ok(())
+}
+
+fn seed_asset_cache<R: Read + std::io::Seek>(
+    layout: &PayloadLayout,
+    keyring: &Keyring,
+    channel: Channel,
+    manifest: &Manifest,
+    archive: &mut zip::ZipArchive<R>,
+) -> ApiResult<Vec<String>> {
+    let mut imported = Vec::new();
+
+    for asset in &manifest.assets {
+        let mut entry = match archive.by_name(&asset.filename) {
+            Ok(entry) => entry,
+            Err(zip::result::ZipError::FileNotFound) => continue,
+            Err(e) => {
+                return Err(ApiError::new(
+                    "zip_invalid",
+                    format!("zip entry {}: {e}", asset.filename),
+                ))
+            }
+        };
+
+        verify_asset_signature(
+            keyring,
+            Some(channel),
+            &asset.filename,
+            asset.size,
+            &asset.sha256,
+            asset.signature.as_deref(),
+        )?;
+
+        if is_cached(layout, asset) {
+            write_asset_signature(
+                layout,
+                &asset.sha256,
+                &asset.filename,
+                asset.signature.as_deref(),
+            )?;
+            imported.push(asset.id.clone());
+            continue;
+        }
+
+        let dest = layout.asset_cache_path(&asset.sha256, &asset.filename);
+        let parent = dest.parent().ok_or_else(|| {
+            ApiError::new(
+                "io_invalid_path",
+                format!("no parent for {}", dest.display()),
+            )
+        })?;
+        std::fs::create_dir_all(parent).map_err(|e| {
+            ApiError::new(
+                "io_mkdir_failed",
+                format!("create dir {}: {e}", parent.display()),
+            )
+        })?;
+
+        let tmp = dest.with_extension("download");
+        let (written, got) = copy_hashing(&mut entry, &tmp)?;
+        if written != asset.size || got != asset.sha256 {
+            let _ = std::fs::remove_file(&tmp);
+            let code = if written != asset.size {
+                "asset_size_mismatch"
+            } else {
+                "asset_sha256_mismatch"
+            };
+            return Err(ApiError::new(
+                code,
+                format!(
+                    "bundle asset {}: {written} bytes sha256 {got}, expected {} bytes sha256 {}",
+                    asset.filename, asset.size, asset.sha256
+                ),
+            )
+            .with_details(serde_json::json!({ "asset_id": asset.id })));
+        }
+
+        std::fs::rename(&tmp, &dest).map_err(|e| {
+            ApiError::new(
+                "io_rename_failed",
+                format!("rename {} -> {}: {e}", tmp.display(), dest.display()),
+            )
+        })?;
+        write_asset_signature(
+            layout,
+            &asset.sha256,
+            &asset.filename,
+            asset.signature.as_deref(),
+        )?;
+        imported.push(asset.id.clone());
+    }
+
+    Ok(imported)
+}
+
+fn is_cached(layout: &PayloadLayout, asset: &ManifestAsset) -> bool {
+    let path = layout.asset_cache_path(&asset.sha256, &asset.filename);
+    std::fs::metadata(&path).is_ok_and(|meta| meta.len() == asset.size)
+        && sha256_file_hex(&path).is_ok_and(|got| got == asset.sha256)
+}
+
+fn copy_hashing(src: &mut impl Read, dest: &Path) -> ApiResult<(u64, String)> {
+    let mut out = std::fs::File::create(dest)
+        .map_err(|e| ApiError::new("io_write_failed", format!("create {}: {e}", dest.display())))?;
+
+    let mut hasher = Sha256::new();
+    let mut written: u64 = 0;
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = src
+            .read(&mut buf)
+            .map_err(|e| ApiError::new("zip_invalid", format!("read {}: {e}", dest.display())))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        out.write_all(&buf[..n]).map_err(|e| {
+            ApiError::new("io_write_failed", format!("write {}: {e}", dest.display()))
+        })?;
+        written = written.saturating_add(n as u64);
+    }
+
+    Ok((written, digest_hex_lower(hasher.finalize())))
+}
+
+fn open_archive(path: &Path) -> ApiResult<zip::ZipArchive<std::fs::File>> {
+    let f = std::fs::File::open(path)
+        .map_err(|e| ApiError::new("io_read_failed", format!("open {}: {e}", path.display())))?;
+    zip::ZipArchive::new(f).map_err(|e| ApiError::new("zip_invalid", format!("open zip: {e}")))
+}
+
+fn read_entry<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> ApiResult<Vec<u8>> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|_| ApiError::new("bundle_invalid", format!("bundle is missing {name}")))?;
+    let mut bytes = Vec::new();
+    entry
+        .read_to_end(&mut bytes)
+        .map_err(|e| ApiError::new("zip_invalid", format!("read {name}: {e}")))?;
+    Ok(bytes)
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> ApiResult<()> {
+    std::fs::write(path, bytes)
+        .map_err(|e| ApiError::new("io_write_failed", format!("write {}: {e}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use ms_manager_core::sha256_hex;
+
+    use super::*;
+    use crate::test_support::TestRoot;
+
+    fn manifest_with(assets: &[(&str, &[u8])]) -> Manifest {
+        let assets = assets
+            .iter()
+            .map(|(filename, bytes)| {
+                serde_json::json!({
+                    "id": filename,
+                    "kind": "firmware",
+                    "filename": filename,
+                    "size": bytes.len(),
+                    "sha256": sha256_hex(bytes),
+                })
+            })
+            .collect::<Vec<_>>();
+        serde_json::from_value(serde_json::json!({
+            "schema": 3,
+            "channel": "stable",
+            "tag": "v1.0.0",
+            "published_at": "2026-10-01T00:00:00Z",
+            "repos": [],
+            "assets": assets,
+            "install_sets": [],
+        }))
+        .unwrap()
+    }
+
+    fn archive_with(entries: &[(&str, &[u8])]) -> zip::ZipArchive<std::io::Cursor<Vec<u8>>> {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, bytes) in entries {
+            zip.start_file(*name, zip::write::FileOptions::default())
+                .unwrap();
+            zip.write_all(bytes).unwrap();
+        }
+        zip::ZipArchive::new(zip.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn seed_asset_cache_imports_present_assets_only() {
+        let root = TestRoot::new("offline-bundle");
+        let layout = root.layout();
+        let manifest = manifest_with(&[("a.hex", b"firmware-a"), ("b.hex", b"firmware-b")]);
+        let mut archive = archive_with(&[("a.hex", b"firmware-a")]);
+
+        let imported = seed_asset_cache(
+            &layout,
+            &Keyring::default(),
+            Channel::Stable,
+            &manifest,
+            &mut archive,
+        )
+        .unwrap();
+        assert_eq!(imported, vec!["a.hex".to_string()]);
+        assert!(is_cached(&layout, &manifest.assets[0]));
+        assert!(!is_cached(&layout, &manifest.assets[1]));
+    }
+
+    #[test]
+    fn seed_asset_cache_rejects_tampered_asset() {
+        let root = TestRoot::new("offline-bundle");
+        let layout = root.layout();
+        let manifest = manifest_with(&[("a.hex", b"firmware-a")]);
+        let mut archive = archive_with(&[("a.hex", b"firmware-x")]);
+
+        let err = seed_asset_cache(
+            &layout,
+            &Keyring::default(),
+            Channel::Stable,
+            &manifest,
+            &mut archive,
+        )
+        .unwrap_err();
+        assert_eq!(err.code, "asset_sha256_mismatch");
+        assert!(!is_cached(&layout, &manifest.assets[0]));
+    }
+}
@@ -66,7 +66,7 @@ pub async fn oc_bridge_kill_process() -> ApiResult<Option<CommandReport>> {
         if r.exit_code == Some(1) {
             r.ok = true;
         }
-        return Ok(Some(r));
+        Ok(Some(r))
     }
 }
 
@@ -142,19 +142,17 @@ fn relocate_payload_root_blocking(
             if let Some(tag) = installed_tag {
                 install::set_current(new_layout, tag)?;
             }
-            return Ok(());
+            Ok(())
         }
         Err(e) if is_cross_device(&e) || is_access_denied(&e) => {
             // Fall back to copy+swap. This is also used on Windows when the directory is locked.
             copy_swap_payload_root(old_layout, new_layout, installed_tag)?;
-            return Ok(());
-        }
-        Err(e) => {
-            return Err(ApiError::new(
-                "payload_root_move_failed",
-                format!("move {} -> {}: {e}", old_root.display(), new_root.display()),
-            ));
+            Ok(())
         }
+        Err(e) => Err(ApiError::new(
+            "payload_root_move_failed",
+            format!("move {} -> {}: {e}", old_root.display(), new_root.display()),
+        )),
     }
 }
 
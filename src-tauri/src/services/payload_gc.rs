@@ -0,0 +1,439 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use ms_manager_core::{
+    channel_for_tag, compare_tags, parse_manifest_json, rollback_target, BridgeInstancesState,
+    Channel, InstallState,
+};
+
+use crate::api_error::{ApiError, ApiResult};
+use crate::layout::{is_version_scratch_dir, PayloadLayout};
+use crate::models::{GcFailure, GcKeepReason, GcKeptVersion, GcRemovedEntry, PayloadGcReport};
+use crate::services::{install, install_history};
+
+/// Cache blobs touched this recently are left alone: an install may still be about to use them.
+const BLOB_GRACE_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+/// Remove installed versions and cached assets nothing refers to any more.
+///
+/// A version is kept when `current` points at it, the install state names it, it is the
+/// rollback target, a bridge instance pins it or resolves to it through its channel, or it is
+/// among the newest `keep_per_channel` versions of its channel. A cache blob is kept when a kept version was
+/// installed from it, or when an imported offline release that is not installed yet needs it.
+pub fn collect_garbage(
+    layout: &PayloadLayout,
+    installed: Option<&InstallState>,
+    bindings: &BridgeInstancesState,
+    keep_per_channel: usize,
+    dry_run: bool,
+) -> ApiResult<PayloadGcReport> {
+    let blob_cutoff = SystemTime::now() - BLOB_GRACE_PERIOD;
+    collect(
+        layout,
+        installed,
+        bindings,
+        keep_per_channel,
+        dry_run,
+        blob_cutoff,
+    )
+}
+
+fn collect(
+    layout: &PayloadLayout,
+    installed: Option<&InstallState>,
+    bindings: &BridgeInstancesState,
+    keep_per_channel: usize,
+    dry_run: bool,
+    blob_cutoff: SystemTime,
+) -> ApiResult<PayloadGcReport> {
+    let versions = installed_versions(layout)?;
+    let kept = kept_versions(layout, &versions, installed, bindings, keep_per_channel);
+
+    let mut report = PayloadGcReport {
+        dry_run,
+        kept_versions: Vec::new(),
+        removed_versions: Vec::new(),
+        removed_blobs: Vec::new(),
+        failed: Vec::new(),
+        bytes_freed: 0,
+    };
+
+    for tag in &versions {
+        if let Some(reasons) = kept.get(tag) {
+            report.kept_versions.push(GcKeptVersion {
+                tag: tag.clone(),
+                reasons: reasons.clone(),
+            });
+            continue;
+        }
+        remove_entry(&mut report, tag, &layout.version_dir(tag), dry_run, true);
+    }
+
+    let referenced = referenced_blobs(layout, &versions, &kept);
+    for (sha256, dir) in list_dirs(&layout.asset_cache_dir())? {
+        if referenced.contains(&sha256) || touched_since(&dir, blob_cutoff) {
+            continue;
+        }
+        remove_entry(&mut report, &sha256, &dir, dry_run, false);
+    }
+
+    Ok(report)
+}
+
+/// Tags under `versions/`, leaving out in-progress staging and repair directories.
+fn installed_versions(layout: &PayloadLayout) -> ApiResult<Vec<String>> {
+    Ok(list_dirs(&layout.versions_dir())?
+        .into_iter()
+        .map(|(name, _)| name)
+        .filter(|name| !is_version_scratch_dir(name))
+        .collect())
+}
+
+fn kept_versions(
+    layout: &PayloadLayout,
+    versions: &[String],
+    installed: Option<&InstallState>,
+    bindings: &BridgeInstancesState,
+    keep_per_channel: usize,
+) -> BTreeMap<String, Vec<GcKeepReason>> {
+    let mut kept = BTreeMap::<String, Vec<GcKeepReason>>::new();
+    let mut keep = |tag: &str, reason: GcKeepReason| {
+        if versions.iter().any(|v| v == tag) {
+            kept.entry(tag.to_string()).or_default().push(reason);
+        }
+    };
+
+    if let Some(tag) = install::current_tag(layout) {
+        keep(&tag, GcKeepReason::Current);
+    }
+    if let Some(installed) = installed {
+        keep(&installed.tag, GcKeepReason::InstallState);
+        let history = install_history::read(layout).unwrap_or_default();
+        let available = |tag: &str| versions.iter().any(|v| v == tag);
+        if let Some(entry) = rollback_target(&history, &installed.tag, available) {
+            keep(&entry.tag, GcKeepReason::RollbackTarget);
+        }
+    }
+
+    let mut by_channel = BTreeMap::<&str, Vec<&str>>::new();
+    for tag in versions {
+        match channel_for_tag(tag) {
+            Some(channel) => by_channel.entry(channel.as_str()).or_default().push(tag),
+            None => keep(tag, GcKeepReason::UnknownTag),
+        }
+    }
+    for tags in by_channel.values_mut() {
+        tags.sort_by(|a, b| newest_first(a, b));
+    }
+
+    for binding in &bindings.instances {
+        let instance_id = binding.instance_id.clone();
+        match binding
+            .installed_pinned_tag
+            .as_deref()
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+        {
+            Some(tag) => keep(tag, GcKeepReason::Pinned { instance_id }),
+            None => {
+                let channel = binding.installed_channel.unwrap_or(Channel::Stable);
+                if let Some(tag) = by_channel.get(channel.as_str()).and_then(|t| t.first()) {
+                    keep(tag, GcKeepReason::ChannelResolved { instance_id });
+                }
+            }
+        }
+    }
+
+    for tags in by_channel.values() {
+        for tag in tags.iter().take(keep_per_channel) {
+            keep(tag, GcKeepReason::Retention);
+        }
+    }
+
+    kept
+}
+
+fn newest_first(a: &str, b: &str) -> std::cmp::Ordering {
+    channel_for_tag(a)
+        .and_then(|channel| compare_tags(channel, b, a))
+        .unwrap_or_else(|| b.cmp(a))
+}
+
+fn referenced_blobs(
+    layout: &PayloadLayout,
+    versions: &[String],
+    kept: &BTreeMap<String, Vec<GcKeepReason>>,
+) -> HashSet<String> {
+    let mut referenced = HashSet::new();
+    for tag in kept.keys() {
+        if let Some(record) = install::read_version_assets(layout, tag) {
+            referenced.extend(record.assets.into_iter().map(|a| a.sha256));
+        }
+    }
+
+    // Offline imports seed the cache ahead of an install; keep those until the release is
+    // installed, and afterwards only while its version is kept.
+    let releases = list_dirs(&layout.offline_releases_dir()).unwrap_or_default();
+    for (tag, dir) in releases {
+        if versions.contains(&tag) && !kept.contains_key(&tag) {
+            continue;
+        }
+        let Ok(bytes) = std::fs::read(dir.join("manifest.json")) else {
+            continue;
+        };
+        if let Ok(manifest) = parse_manifest_json(&bytes) {
+            referenced.extend(manifest.assets.into_iter().map(|a| a.sha256));
+        }
+    }
+    referenced
+}
+
+fn remove_entry(
+    report: &mut PayloadGcReport,
+    name: &str,
+    path: &Path,
+    dry_run: bool,
+    is_version: bool,
+) {
+    let bytes = dir_size(path);
+    if !dry_run {
+        if let Err(e) = std::fs::remove_dir_all(path) {
+            report.failed.push(GcFailure {
+                path: path.display().to_string(),
+                message: e.to_string(),
+            });
+            return;
+        }
+    }
+
+    report.bytes_freed = report.bytes_freed.saturating_add(bytes);
+    let entry = GcRemovedEntry {
+        name: name.to_string(),
+        path: path.display().to_string(),
+        bytes,
+    };
+    if is_version {
+        report.removed_versions.push(entry);
+    } else {
+        report.removed_blobs.push(entry);
+    }
+}
+
+/// Sub-directories of `dir` by name; a missing `dir` has none.
+fn list_dirs(dir: &Path) -> ApiResult<Vec<(String, std::path::PathBuf)>> {
+    let read = match std::fs::read_dir(dir) {
+        Ok(read) => read,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(ApiError::new(
+                "io_read_failed",
+                format!("read dir {}: {e}", dir.display()),
+            ))
+        }
+    };
+
+    let mut dirs = Vec::new();
+    for entry in read.flatten() {
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+        if let (true, Some(name)) = (is_dir, entry.file_name().to_str()) {
+            dirs.push((name.to_string(), entry.path()));
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Total size of regular files below `path`, without following symlinks.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    std::fs::read_dir(path)
+        .map(|read| read.flatten().map(|entry| dir_size(&entry.path())).sum())
+        .unwrap_or(0)
+}
+
+fn touched_since(dir: &Path, since: SystemTime) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    if modified(dir).is_some_and(|at| at >= since) {
+        return true;
+    }
+    std::fs::read_dir(dir)
+        .map(|read| {
+            read.flatten()
+                .any(|entry| modified(&entry.path()).is_some_and(|at| at >= since))
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use ms_manager_core::INSTALL_STATE_SCHEMA;
+
+    use super::*;
+    use crate::services::install::{VersionAsset, VersionAssets};
+    use crate::storage::write_json_atomic;
+    use crate::test_support::TestRoot;
+
+    fn version(layout: &PayloadLayout, tag: &str, blobs: &[&str]) {
+        std::fs::create_dir_all(layout.version_dir(tag).join("bin")).unwrap();
+        std::fs::write(layout.version_dir(tag).join("bin/oc-bridge"), tag).unwrap();
+        let record = VersionAssets {
+            assets: blobs
+                .iter()
+                .map(|sha| VersionAsset {
+                    sha256: sha.to_string(),
+                    filename: "asset.zip".to_string(),
+                    kind: "bundle".to_string(),
+                })
+                .collect(),
+        };
+        write_json_atomic(&layout.version_assets_file(tag), &record).unwrap();
+    }
+
+    fn blob(layout: &PayloadLayout, sha: &str) {
+        let path = layout.asset_cache_path(sha, "asset.zip");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, sha).unwrap();
+    }
+
+    fn installed(tag: &str) -> InstallState {
+        InstallState {
+            schema: INSTALL_STATE_SCHEMA,
+            channel: channel_for_tag(tag).unwrap(),
+            profile: "default".to_string(),
+            tag: tag.to_string(),
+        }
+    }
+
+    fn bindings(instances: serde_json::Value) -> BridgeInstancesState {
+        serde_json::from_value(serde_json::json!({ "schema": 1, "instances": instances })).unwrap()
+    }
+
+    fn binding(instance_id: &str, channel: &str, pinned: Option<&str>) -> serde_json::Value {
+        serde_json::json!({
+            "instance_id": instance_id,
+            "app": "bitwig",
+            "mode": "hardware",
+            "controller_serial": "1",
+            "controller_vid": 0,
+            "controller_pid": 0,
+            "installed_channel": channel,
+            "installed_pinned_tag": pinned,
+            "host_udp_port": 9000,
+            "control_port": 9001,
+            "log_broadcast_port": 9002,
+        })
+    }
+
+    fn far_future() -> SystemTime {
+        SystemTime::now() + Duration::from_secs(24 * 60 * 60)
+    }
+
+    fn tags(entries: &[GcRemovedEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn keeps_referenced_versions_and_newest_per_channel() {
+        let root = TestRoot::new("payload-gc");
+        let layout = root.layout();
+        for tag in ["v1.0.0", "v1.1.0", "v1.2.0", "v1.3.0"] {
+            version(&layout, tag, &[]);
+        }
+        version(&layout, "v1.4.0-beta.1", &[]);
+        version(&layout, "v1.4.0-beta.2", &[]);
+        version(&layout, "local-build", &[]);
+        let bindings = bindings(serde_json::json!([
+            binding("pinned", "stable", Some("v1.0.0")),
+            binding("beta", "beta", None),
+        ]));
+
+        let report = collect(
+            &layout,
+            Some(&installed("v1.1.0")),
+            &bindings,
+            1,
+            true,
+            far_future(),
+        )
+        .unwrap();
+
+        assert_eq!(tags(&report.removed_versions), ["v1.2.0", "v1.4.0-beta.1"]);
+        let reasons = |tag: &str| {
+            report
+                .kept_versions
+                .iter()
+                .find(|k| k.tag == tag)
+                .map(|k| k.reasons.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            reasons("v1.0.0"),
+            [GcKeepReason::Pinned {
+                instance_id: "pinned".to_string()
+            }]
+        );
+        assert_eq!(reasons("v1.1.0"), [GcKeepReason::InstallState]);
+        assert_eq!(reasons("v1.3.0"), [GcKeepReason::Retention]);
+        assert_eq!(
+            reasons("v1.4.0-beta.2"),
+            [
+                GcKeepReason::ChannelResolved {
+                    instance_id: "beta".to_string()
+                },
+                GcKeepReason::Retention
+            ]
+        );
+        assert_eq!(reasons("local-build"), [GcKeepReason::UnknownTag]);
+        // Dry run: nothing is deleted.
+        assert!(layout.version_dir("v1.2.0").exists());
+        assert!(report.bytes_freed > 0);
+    }
+
+    #[test]
+    fn removes_unreferenced_versions_and_blobs() {
+        let root = TestRoot::new("payload-gc");
+        let layout = root.layout();
+        version(&layout, "v1.0.0", &["aaa"]);
+        version(&layout, "v1.1.0", &["bbb"]);
+        for sha in ["aaa", "bbb", "ccc"] {
+            blob(&layout, sha);
+        }
+
+        let report = collect(
+            &layout,
+            Some(&installed("v1.1.0")),
+            &bindings(serde_json::json!([])),
+            1,
+            false,
+            far_future(),
+        )
+        .unwrap();
+
+        assert_eq!(tags(&report.removed_versions), ["v1.0.0"]);
+        assert_eq!(tags(&report.removed_blobs), ["aaa", "ccc"]);
+        assert!(report.failed.is_empty());
+        assert!(!layout.version_dir("v1.0.0").exists());
+        assert!(layout.version_dir("v1.1.0").exists());
+        assert!(layout.asset_cache_path("bbb", "asset.zip").exists());
+        assert!(!layout.asset_cache_path("aaa", "asset.zip").exists());
+    }
+
+    #[test]
+    fn recently_touched_blobs_survive_the_grace_period() {
+        let root = TestRoot::new("payload-gc");
+        let layout = root.layout();
+        blob(&layout, "fresh");
+
+        let report =
+            collect_garbage(&layout, None, &bindings(serde_json::json!([])), 1, false).unwrap();
+
+        assert!(report.removed_blobs.is_empty());
+        assert!(layout.asset_cache_path("fresh", "asset.zip").exists());
+    }
+}
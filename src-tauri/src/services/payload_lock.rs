@@ -0,0 +1,79 @@
+use std::fs::File;
+
+use fs2::FileExt;
+
+use crate::api_error::{ApiError, ApiResult};
+use crate::layout::PayloadLayout;
+
+/// Exclusive lock on a payload root, held across processes until dropped.
+///
+/// The desktop app and `ms-manager-cli` share payload roots, and installs share download
+/// checkpoints, staging directories, the install journal and `current`. Every step that writes
+/// to them holds this lock on [`PayloadLayout::install_lock_file`].
+pub struct PayloadLock {
+    _file: File,
+}
+
+impl PayloadLock {
+    /// Take the lock, or fail with `payload_busy` when another step holds it.
+    pub fn acquire(layout: &PayloadLayout) -> ApiResult<Self> {
+        Self::try_acquire(layout)?.ok_or_else(|| {
+            ApiError::new(
+                "payload_busy",
+                format!(
+                    "another install, rollback, repair or cleanup is using {}; try again when it finishes",
+                    layout.root().display()
+                ),
+            )
+        })
+    }
+
+    /// Take the lock, or `None` when another step, in this process or another one, holds it.
+    pub fn try_acquire(layout: &PayloadLayout) -> ApiResult<Option<Self>> {
+        let path = layout.install_lock_file();
+        std::fs::create_dir_all(layout.state_dir()).map_err(|e| {
+            ApiError::new(
+                "io_write_failed",
+                format!("create {}: {e}", layout.state_dir().display()),
+            )
+        })?;
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(|e| {
+                ApiError::new("io_write_failed", format!("open {}: {e}", path.display()))
+            })?;
+        match FileExt::try_lock_exclusive(&file) {
+            Ok(()) => Ok(Some(Self { _file: file })),
+            Err(e) if e.raw_os_error() == fs2::lock_contended_error().raw_os_error() => Ok(None),
+            Err(e) => Err(ApiError::new(
+                "io_lock_failed",
+                format!("lock {}: {e}", path.display()),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRoot;
+
+    #[test]
+    fn second_holder_is_refused_until_the_first_drops() {
+        let root = TestRoot::new("payload-lock");
+        let layout = root.layout();
+
+        let held = PayloadLock::try_acquire(&layout).unwrap().unwrap();
+        assert!(PayloadLock::try_acquire(&layout).unwrap().is_none());
+        assert_eq!(
+            PayloadLock::acquire(&layout).err().unwrap().code,
+            "payload_busy"
+        );
+        drop(held);
+        assert!(PayloadLock::try_acquire(&layout).unwrap().is_some());
+    }
+}
@@ -15,6 +15,20 @@ pub fn spawn_autostart_install() {
     });
 }
 
+/// Swap in an ms-manager update staged by the previous run, then restart into it.
+pub fn apply_staged_app_update(app: &AppHandle) {
+    let Some(install) = services::app_update::detect_install() else {
+        return;
+    };
+    let state = app.state::<crate::state::AppState>();
+    let current_version = app.package_info().version.to_string();
+    let applied =
+        services::app_update::apply_staged_update(&state.layout_get(), &install, &current_version);
+    if matches!(applied, Ok(Some(_))) && services::app_update::relaunch(&install).is_ok() {
+        std::process::exit(0);
+    }
+}
+
 pub fn apply_background_mode<R: Runtime>(app: &App<R>) {
     if !std::env::args().any(|arg| arg == "--background") {
         return;
@@ -28,5 +42,6 @@ pub fn apply_background_mode<R: Runtime>(app: &App<R>) {
 pub fn spawn_background_services(app: AppHandle) {
     services::bridge::spawn_bridge_supervisor(app.clone());
     services::bridge_logs::spawn_bridge_log_supervisor(app.clone());
+    services::update_check::spawn_update_checker(app.clone());
     services::local_fs_watcher::spawn_local_storage_watcher(app);
 }
@@ -32,6 +32,7 @@ pub fn install<R: Runtime>(app: &mut App<R>) -> tauri::Result<()> {
                         .await;
                     }
 
+                    services::native_sim::stop_all();
                     tokio::time::sleep(std::time::Duration::from_millis(250)).await;
 
                     let layout = app.state::<state::AppState>().layout_get();
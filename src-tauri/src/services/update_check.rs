@@ -0,0 +1,498 @@
+#[cfg(feature = "desktop")]
+use std::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ms_manager_core::{
+    compare_tags, ArtifactSource, BridgeInstanceBinding, BridgeInstancesState, Channel,
+    DistributionSource, InstallState, VersionRange,
+};
+
+use crate::layout::PayloadLayout;
+#[cfg(feature = "desktop")]
+use crate::models::AutoUpdateEvent;
+use crate::models::{
+    ChannelLatestTag, InstanceUpdateAvailability, RangeLatestTag, UpdateCheckState,
+};
+use crate::services::distribution;
+use crate::services::http_client::HttpClient;
+use crate::services::installed_artifacts::resolve_installed_tag;
+
+#[cfg(feature = "desktop")]
+const UPDATE_CHECK_EVENT: &str = "ms-manager://update-check";
+#[cfg(feature = "desktop")]
+const AUTO_UPDATE_EVENT: &str = "ms-manager://auto-update";
+#[cfg(feature = "desktop")]
+const UPDATE_CHECK_START_DELAY: Duration = Duration::from_secs(20);
+/// How often a disabled checker looks at its settings again.
+#[cfg(feature = "desktop")]
+const UPDATE_CHECK_IDLE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Periodically resolve the latest tag of every channel followed by an enabled instance and
+/// emit the resulting per-instance update availability.
+#[cfg(feature = "desktop")]
+pub fn spawn_update_checker(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        update_check_loop(app).await;
+    });
+}
+
+#[cfg(feature = "desktop")]
+async fn update_check_loop(app: tauri::AppHandle) {
+    use tauri::{Emitter, Manager};
+
+    use crate::models::UpdateCheckEvent;
+    use crate::state::AppState;
+
+    tokio::time::sleep(UPDATE_CHECK_START_DELAY).await;
+
+    loop {
+        let state = app.state::<AppState>();
+        let settings = state.settings_get().update_check;
+        if !settings.enabled {
+            tokio::time::sleep(UPDATE_CHECK_IDLE_INTERVAL).await;
+            continue;
+        }
+
+        let bindings = state.bridge_instances_get();
+        let checked = check_channels(
+            &state.http(),
+            &state.layout_get(),
+            &state.distribution_source_get(),
+            &bindings,
+        )
+        .await;
+        state.update_check_set(checked.clone());
+        let instances =
+            instance_update_availability(&bindings, state.install_state_get().as_ref(), &checked);
+        let _ = app.emit(
+            UPDATE_CHECK_EVENT,
+            UpdateCheckEvent {
+                check: checked,
+                instances: instances.clone(),
+            },
+        );
+        apply_update_policies(&app, &state, &bindings, &instances).await;
+
+        tokio::time::sleep(Duration::from_secs(u64::from(settings.interval_mins) * 60)).await;
+    }
+}
+
+/// Install, and flash when asked to, the updates that instance policies allow right now.
+///
+/// Installs never downgrade, and flashing pauses the instance's bridge like a manual flash.
+#[cfg(feature = "desktop")]
+async fn apply_update_policies(
+    app: &tauri::AppHandle,
+    state: &crate::state::AppState,
+    bindings: &BridgeInstancesState,
+    instances: &[InstanceUpdateAvailability],
+) {
+    use chrono::Timelike;
+    use tauri::Emitter;
+
+    let hour = chrono::Local::now().hour() as u8;
+    let mut due = Vec::new();
+    for binding in bindings.instances.iter().filter(|binding| binding.enabled) {
+        let Some(update) = instances
+            .iter()
+            .find(|update| update.instance_id == binding.instance_id && update.update_available)
+        else {
+            continue;
+        };
+        let (Some(channel), Some(current), Some(latest)) = (
+            binding.installed_channel,
+            update.current_tag.as_deref(),
+            update.latest_tag.as_deref(),
+        ) else {
+            continue;
+        };
+        if binding
+            .update_policy
+            .allows_auto_update(channel, current, latest, hour)
+        {
+            due.push((binding, latest));
+        }
+    }
+    if due.is_empty() {
+        return;
+    }
+
+    // An install, rollback, repair or GC owns the payload, started from this app or from
+    // `ms-manager-cli`; report it and try again next pass.
+    let Some(install) = state.install_try_lock() else {
+        for (binding, tag) in due {
+            let _ = app.emit(
+                AUTO_UPDATE_EVENT,
+                AutoUpdateEvent {
+                    instance_id: binding.instance_id.clone(),
+                    tag: tag.to_string(),
+                    installed: false,
+                    flashed: false,
+                    error: Some(
+                        "the payload is busy with another install, rollback, repair or cleanup; \
+                         retrying at the next update check"
+                            .to_string(),
+                    ),
+                },
+            );
+        }
+        return;
+    };
+    for (binding, tag) in due {
+        let event = auto_update_binding(app, state, binding, tag, &install).await;
+        let _ = app.emit(AUTO_UPDATE_EVENT, event);
+    }
+}
+
+#[cfg(feature = "desktop")]
+async fn auto_update_binding(
+    app: &tauri::AppHandle,
+    state: &crate::state::AppState,
+    binding: &BridgeInstanceBinding,
+    tag: &str,
+    install: &crate::state::InstallGuard<'_>,
+) -> AutoUpdateEvent {
+    use crate::commands::install::install_release_for_binding;
+    use crate::services::flash;
+
+    let mut event = AutoUpdateEvent {
+        instance_id: binding.instance_id.clone(),
+        tag: tag.to_string(),
+        installed: false,
+        flashed: false,
+        error: None,
+    };
+    // A range may sit below the active release; the policy only ever moves up within it.
+    let allow_downgrade = binding.installed_version_range.is_some();
+    if let Err(err) =
+        install_release_for_binding(binding, Some(tag), allow_downgrade, app, state, install).await
+    {
+        event.error = Some(err.message);
+        return event;
+    }
+    event.installed = true;
+    // Simulators pick up the release when they restart; there is nothing to flash.
+    if binding.update_policy.mode != ms_manager_core::UpdatePolicyMode::AutoInstallAndFlash
+        || binding.mode == ms_manager_core::BridgeMode::NativeSim
+    {
+        return event;
+    }
+
+    // The install pinned the instance to `tag`; flash from the updated binding.
+    let Some(binding) = state
+        .bridge_instances_get()
+        .instances
+        .into_iter()
+        .find(|candidate| candidate.instance_id == binding.instance_id)
+    else {
+        return event;
+    };
+    match flash::flash_firmware_for_binding(
+        app,
+        &state.layout_get(),
+        state.install_state_get().as_ref(),
+        &binding,
+        None,
+    )
+    .await
+    {
+        Ok(last) => {
+            let _ = state.controller_last_flashed_set(&binding.instance_id, last);
+            event.flashed = true;
+        }
+        Err(err) => event.error = Some(err.message),
+    }
+    event
+}
+
+/// Channels followed by enabled instances that run installed artifacts, in binding order.
+pub fn followed_channels(bindings: &BridgeInstancesState) -> Vec<Channel> {
+    let mut channels = Vec::new();
+    for channel in bindings
+        .instances
+        .iter()
+        .filter(|binding| binding.enabled && binding.artifact_source == ArtifactSource::Installed)
+        .filter_map(|binding| binding.installed_channel)
+    {
+        if !channels.contains(&channel) {
+            channels.push(channel);
+        }
+    }
+    channels
+}
+
+/// Distinct `(channel, range)` pairs followed by enabled instances that run installed artifacts.
+pub fn followed_ranges(bindings: &BridgeInstancesState) -> Vec<(Channel, String)> {
+    let mut ranges = Vec::new();
+    for followed in bindings
+        .instances
+        .iter()
+        .filter(|binding| binding.enabled && binding.artifact_source == ArtifactSource::Installed)
+        .filter_map(|binding| {
+            Some((
+                binding.installed_channel?,
+                binding.installed_version_range.clone()?,
+            ))
+        })
+    {
+        if !ranges.contains(&followed) {
+            ranges.push(followed);
+        }
+    }
+    ranges
+}
+
+/// Resolve the latest release of every followed channel and the highest release within every
+/// followed range. Entries that cannot be resolved are reported with their error instead of
+/// failing the whole check.
+pub async fn check_channels(
+    client: &HttpClient,
+    layout: &PayloadLayout,
+    source: &DistributionSource,
+    bindings: &BridgeInstancesState,
+) -> UpdateCheckState {
+    let mut channels = Vec::new();
+    for channel in followed_channels(bindings) {
+        let resolved =
+            distribution::resolve_available_manifest(client, layout, source, channel, None).await;
+        channels.push(match resolved {
+            Ok(available) => ChannelLatestTag {
+                channel,
+                tag: Some(available.tag),
+                stale: available.stale_fetched_at_ms.is_some(),
+                error: None,
+            },
+            Err(err) => ChannelLatestTag {
+                channel,
+                tag: None,
+                stale: false,
+                error: Some(err.message),
+            },
+        });
+    }
+    let mut ranges = Vec::new();
+    for (channel, range) in followed_ranges(bindings) {
+        let resolved = match VersionRange::parse(&range) {
+            Ok(parsed) => {
+                distribution::resolve_tag_in_range(client, source, channel, &parsed).await
+            }
+            Err(err) => Err(err.into()),
+        };
+        let (tag, error) = match resolved {
+            Ok(tag) => (Some(tag), None),
+            Err(err) => (None, Some(err.message)),
+        };
+        ranges.push(RangeLatestTag {
+            channel,
+            range,
+            tag,
+            error,
+        });
+    }
+    UpdateCheckState {
+        checked_at_ms: Some(now_ms()),
+        channels,
+        ranges,
+    }
+}
+
+/// Latest known tag for `binding` (the highest within its range when it follows one), and
+/// whether it is newer than the release the instance runs.
+pub fn update_for_binding(
+    binding: &BridgeInstanceBinding,
+    installed: Option<&InstallState>,
+    check: &UpdateCheckState,
+) -> (Option<String>, bool) {
+    if binding.artifact_source != ArtifactSource::Installed {
+        return (None, false);
+    }
+    let Some(channel) = binding.installed_channel else {
+        return (None, false);
+    };
+    let latest = match &binding.installed_version_range {
+        Some(range) => check
+            .ranges
+            .iter()
+            .find(|entry| entry.channel == channel && &entry.range == range)
+            .and_then(|entry| entry.tag.clone()),
+        None => check
+            .channels
+            .iter()
+            .find(|entry| entry.channel == channel)
+            .and_then(|entry| entry.tag.clone()),
+    };
+    let Some(latest) = latest else {
+        return (None, false);
+    };
+    let available = resolve_installed_tag(binding.installed_pinned_tag.as_deref(), installed)
+        .ok()
+        .and_then(|current| compare_tags(channel, &latest, current))
+        .is_some_and(|ord| ord.is_gt());
+    (Some(latest), available)
+}
+
+pub fn instance_update_availability(
+    bindings: &BridgeInstancesState,
+    installed: Option<&InstallState>,
+    check: &UpdateCheckState,
+) -> Vec<InstanceUpdateAvailability> {
+    bindings
+        .instances
+        .iter()
+        .map(|binding| {
+            let (latest_tag, update_available) = update_for_binding(binding, installed, check);
+            InstanceUpdateAvailability {
+                instance_id: binding.instance_id.clone(),
+                current_tag: resolve_installed_tag(
+                    binding.installed_pinned_tag.as_deref(),
+                    installed,
+                )
+                .ok()
+                .map(ToOwned::to_owned)
+                .filter(|_| binding.artifact_source == ArtifactSource::Installed),
+                latest_tag,
+                update_available,
+                update_policy: binding.update_policy.mode,
+            }
+        })
+        .collect()
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use ms_manager_core::{
+        BridgeApp, BridgeMode, FirmwareTarget, UpdatePolicy, INSTALL_STATE_SCHEMA,
+    };
+
+    use super::*;
+
+    fn binding(id: &str, channel: Option<Channel>, pinned: Option<&str>) -> BridgeInstanceBinding {
+        BridgeInstanceBinding {
+            instance_id: id.to_string(),
+            display_name: None,
+            app: BridgeApp::Bitwig,
+            mode: BridgeMode::Hardware,
+            controller_serial: id.to_string(),
+            controller_vid: 0x16C0,
+            controller_pid: 0x0489,
+            target: FirmwareTarget::bitwig(),
+            artifact_source: ArtifactSource::Installed,
+            installed_channel: channel,
+            installed_pinned_tag: pinned.map(ToOwned::to_owned),
+            installed_version_range: None,
+            host_udp_port: 9000,
+            control_port: 7999,
+            log_broadcast_port: 9999,
+            enabled: true,
+            update_policy: UpdatePolicy::default(),
+        }
+    }
+
+    fn installed(tag: &str) -> InstallState {
+        InstallState {
+            schema: INSTALL_STATE_SCHEMA,
+            channel: Channel::Stable,
+            profile: "default".to_string(),
+            tag: tag.to_string(),
+        }
+    }
+
+    fn check(channel: Channel, tag: &str) -> UpdateCheckState {
+        UpdateCheckState {
+            checked_at_ms: Some(1),
+            channels: vec![ChannelLatestTag {
+                channel,
+                tag: Some(tag.to_string()),
+                stale: false,
+                error: None,
+            }],
+            ranges: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn followed_channels_skip_disabled_and_workspace_instances() {
+        let mut disabled = binding("a", Some(Channel::Beta), None);
+        disabled.enabled = false;
+        let mut workspace = binding("b", Some(Channel::Nightly), None);
+        workspace.artifact_source = ArtifactSource::Workspace;
+        let bindings = BridgeInstancesState {
+            instances: vec![
+                disabled,
+                workspace,
+                binding("c", Some(Channel::Stable), None),
+                binding("d", Some(Channel::Stable), None),
+                binding("e", None, None),
+            ],
+            ..BridgeInstancesState::default()
+        };
+        assert_eq!(followed_channels(&bindings), vec![Channel::Stable]);
+    }
+
+    #[test]
+    fn update_is_available_when_latest_is_newer_than_the_running_release() {
+        let installed = installed("v1.0.0");
+        let check = check(Channel::Stable, "v1.1.0");
+
+        let (latest, available) = update_for_binding(
+            &binding("a", Some(Channel::Stable), None),
+            Some(&installed),
+            &check,
+        );
+        assert_eq!(latest.as_deref(), Some("v1.1.0"));
+        assert!(available);
+
+        let (_, available) = update_for_binding(
+            &binding("a", Some(Channel::Stable), Some("v1.1.0")),
+            Some(&installed),
+            &check,
+        );
+        assert!(!available);
+
+        let (latest, available) = update_for_binding(
+            &binding("a", Some(Channel::Beta), None),
+            Some(&installed),
+            &check,
+        );
+        assert_eq!(latest, None);
+        assert!(!available);
+    }
+
+    #[test]
+    fn ranged_instances_follow_the_highest_tag_in_their_range() {
+        let installed = installed("v0.2.1");
+        let mut check = check(Channel::Stable, "v0.3.0");
+        check.ranges.push(RangeLatestTag {
+            channel: Channel::Stable,
+            range: "~0.2".to_string(),
+            tag: Some("v0.2.4".to_string()),
+            error: None,
+        });
+        let mut ranged = binding("a", Some(Channel::Stable), Some("v0.2.1"));
+        ranged.installed_version_range = Some("~0.2".to_string());
+        let bindings = BridgeInstancesState {
+            instances: vec![ranged.clone(), binding("b", Some(Channel::Stable), None)],
+            ..BridgeInstancesState::default()
+        };
+        assert_eq!(
+            followed_ranges(&bindings),
+            vec![(Channel::Stable, "~0.2".to_string())]
+        );
+
+        let (latest, available) = update_for_binding(&ranged, Some(&installed), &check);
+        assert_eq!(latest.as_deref(), Some("v0.2.4"));
+        assert!(available);
+
+        ranged.installed_version_range = Some("^0.3".to_string());
+        let (latest, available) = update_for_binding(&ranged, Some(&installed), &check);
+        assert_eq!(latest, None);
+        assert!(!available);
+    }
+}
@@ -0,0 +1,320 @@
+use std::path::Path;
+
+use ms_manager_core::Keyring;
+
+use crate::api_error::{ApiError, ApiResult};
+use crate::layout::{PayloadLayout, VERSION_ASSETS_FILE};
+use crate::models::{AssetPlan, InstallJournalPhase, VersionAuditReport};
+use crate::services::assets::{self, CachedAsset};
+use crate::services::{install, install_journal};
+
+/// Compare an installed version with the inventory written when it was installed.
+pub fn audit_installed_version(layout: &PayloadLayout, tag: &str) -> ApiResult<VersionAuditReport> {
+    let version_dir = layout.version_dir(tag);
+    if !version_dir.is_dir() {
+        return Err(ApiError::new(
+            "install_missing_version",
+            format!("missing installed version dir: {}", version_dir.display()),
+        ));
+    }
+    let expected = install::read_version_inventory(layout, tag)?.ok_or_else(|| {
+        ApiError::new(
+            "version_inventory_missing",
+            format!("{tag} was installed without a file inventory; reinstall it to audit it"),
+        )
+    })?;
+    let actual = install::build_inventory(&version_dir)?;
+
+    let mut report = VersionAuditReport {
+        tag: tag.to_string(),
+        ok: true,
+        files_checked: expected.files.len(),
+        missing: Vec::new(),
+        modified: Vec::new(),
+        extra: Vec::new(),
+    };
+    for (path, file) in &expected.files {
+        match actual.files.get(path) {
+            None => report.missing.push(path.clone()),
+            Some(found) if found != file => report.modified.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+    report.extra = actual
+        .files
+        .keys()
+        .filter(|path| !expected.files.contains_key(*path))
+        .cloned()
+        .collect();
+    report.ok = report.missing.is_empty() && report.modified.is_empty() && report.extra.is_empty();
+    Ok(report)
+}
+
+/// Rebuild an installed version from the cached assets it was installed from, then re-audit it.
+///
+/// The rebuilt copy is staged next to the damaged one and swapped in, so a failure leaves the
+/// existing directory in place. Cached assets are re-verified first; when one is gone the
+/// version has to be reinstalled instead.
+pub async fn repair_installed_version(
+    layout: &PayloadLayout,
+    keyring: &Keyring,
+    tag: &str,
+) -> ApiResult<VersionAuditReport> {
+    let record = install::read_version_assets(layout, tag).ok_or_else(|| {
+        ApiError::new(
+            "version_repair_unavailable",
+            format!("{tag} has no record of its assets; reinstall it instead"),
+        )
+    })?;
+
+    let mut cached = Vec::with_capacity(record.assets.len());
+    let mut unavailable = Vec::new();
+    for asset in &record.assets {
+        let path = layout.asset_cache_path(&asset.sha256, &asset.filename);
+        if !path.is_file() {
+            unavailable.push(asset.filename.clone());
+            continue;
+        }
+        assets::verify_cached_asset(layout, keyring, &asset.sha256, &asset.filename)?;
+        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        cached.push(CachedAsset {
+            plan: AssetPlan {
+                id: asset.filename.clone(),
+                kind: asset.kind.clone(),
+                filename: asset.filename.clone(),
+                sha256: asset.sha256.clone(),
+                size,
+                url: String::new(),
+                description: None,
+                signature: None,
+            },
+            path,
+        });
+    }
+    if !unavailable.is_empty() {
+        return Err(ApiError::new(
+            "repair_assets_missing",
+            format!(
+                "cached assets of {tag} are gone: {}; reinstall it instead",
+                unavailable.join(", ")
+            ),
+        )
+        .with_details(serde_json::json!({ "tag": tag, "assets": unavailable })));
+    }
+
+    let repair_dir = layout.version_repair_dir(tag);
+    // Journaled before the first write, like an install, so an interruption at any point is
+    // reported and cleaned up.
+    install_journal::begin(layout, InstallJournalPhase::RepairStage, tag)?;
+    let result = stage_and_swap_in(layout, tag, &repair_dir, &cached).await;
+    if result.is_err() {
+        let _ = std::fs::remove_dir_all(&repair_dir);
+    }
+    install_journal::clear(layout);
+    result?;
+    audit_installed_version(layout, tag)
+}
+
+async fn stage_and_swap_in(
+    layout: &PayloadLayout,
+    tag: &str,
+    repair_dir: &Path,
+    cached: &[CachedAsset],
+) -> ApiResult<()> {
+    remove_dir_if_exists(repair_dir)?;
+    install::stage_version(repair_dir, cached).await?;
+    let record_path = layout.version_assets_file(tag);
+    std::fs::copy(&record_path, repair_dir.join(VERSION_ASSETS_FILE)).map_err(|e| {
+        ApiError::new(
+            "io_copy_failed",
+            format!("copy {}: {e}", record_path.display()),
+        )
+    })?;
+    swap_in(layout, tag, repair_dir)
+}
+
+fn swap_in(layout: &PayloadLayout, tag: &str, repair_dir: &Path) -> ApiResult<()> {
+    let version_dir = layout.version_dir(tag);
+    let replaced_dir = layout.version_replaced_dir(tag);
+    remove_dir_if_exists(&replaced_dir)?;
+
+    let rename = |from: &Path, to: &Path| {
+        std::fs::rename(from, to).map_err(|e| {
+            ApiError::new(
+                "io_rename_failed",
+                format!("rename {} -> {}: {e}", from.display(), to.display()),
+            )
+        })
+    };
+    install_journal::begin(layout, InstallJournalPhase::RepairSwap, tag)?;
+    let had_version = version_dir.exists();
+    if had_version {
+        rename(&version_dir, &replaced_dir)?;
+    }
+    if let Err(err) = rename(repair_dir, &version_dir) {
+        if had_version {
+            let _ = std::fs::rename(&replaced_dir, &version_dir);
+        }
+        return Err(err);
+    }
+    let _ = std::fs::remove_dir_all(&replaced_dir);
+    Ok(())
+}
+
+fn remove_dir_if_exists(dir: &Path) -> ApiResult<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    std::fs::remove_dir_all(dir)
+        .map_err(|e| ApiError::new("io_remove_failed", format!("remove {}: {e}", dir.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use ms_manager_core::{Channel, Platform};
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+    use crate::models::InstallPlan;
+    use crate::services::assets::digest_hex_lower;
+    use crate::test_support::TestRoot;
+
+    fn cache(layout: &PayloadLayout, kind: &str, filename: &str, bytes: &[u8]) -> CachedAsset {
+        let sha256 = digest_hex_lower(Sha256::digest(bytes));
+        let path = layout.asset_cache_path(&sha256, filename);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, bytes).unwrap();
+        CachedAsset {
+            plan: AssetPlan {
+                id: filename.to_string(),
+                kind: kind.to_string(),
+                filename: filename.to_string(),
+                sha256,
+                size: bytes.len() as u64,
+                url: String::new(),
+                description: None,
+                signature: None,
+            },
+            path,
+        }
+    }
+
+    /// Install `v1.0.0` from a bundle with two files plus a firmware image.
+    fn install(layout: &PayloadLayout) -> Vec<CachedAsset> {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, body) in [("bin/oc-bridge", "bridge"), ("README.txt", "readme")] {
+            zip.start_file(name, zip::write::FileOptions::default())
+                .unwrap();
+            zip.write_all(body.as_bytes()).unwrap();
+        }
+        let bundle = zip.finish().unwrap().into_inner();
+        let cached = vec![
+            cache(layout, "bundle", "bundle.zip", &bundle),
+            cache(layout, "firmware", "default.hex", b"firmware"),
+        ];
+        let plan = InstallPlan {
+            channel: Channel::Stable,
+            tag: "v1.0.0".to_string(),
+            profile: "default".to_string(),
+            platform: Platform::current().unwrap(),
+            assets: cached.iter().map(|c| c.plan.clone()).collect(),
+            notes: None,
+        };
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(install::apply_install(layout, &plan, &cached, false))
+            .unwrap();
+        cached
+    }
+
+    #[test]
+    fn fresh_install_audits_clean() {
+        let root = TestRoot::new("version-audit");
+        let layout = root.layout();
+        install(&layout);
+
+        let report = audit_installed_version(&layout, "v1.0.0").unwrap();
+        assert!(report.ok);
+        assert_eq!(report.files_checked, 3);
+    }
+
+    #[test]
+    fn audit_reports_missing_modified_and_extra_files_and_repair_restores() {
+        let root = TestRoot::new("version-audit");
+        let layout = root.layout();
+        install(&layout);
+        let dir = layout.version_dir("v1.0.0");
+        std::fs::remove_file(dir.join("README.txt")).unwrap();
+        std::fs::write(dir.join("firmware/default.hex"), "patched").unwrap();
+        std::fs::write(dir.join("bin/stray"), "stray").unwrap();
+
+        let report = audit_installed_version(&layout, "v1.0.0").unwrap();
+        assert!(!report.ok);
+        assert_eq!(report.missing, ["README.txt"]);
+        assert_eq!(report.modified, ["firmware/default.hex"]);
+        assert_eq!(report.extra, ["bin/stray"]);
+
+        let report = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(repair_installed_version(
+                &layout,
+                &Keyring::default(),
+                "v1.0.0",
+            ))
+            .unwrap();
+        assert!(report.ok, "{report:?}");
+        assert_eq!(
+            std::fs::read_to_string(dir.join("firmware/default.hex")).unwrap(),
+            "firmware"
+        );
+        assert!(!layout.version_repair_dir("v1.0.0").exists());
+        assert!(!layout.version_replaced_dir("v1.0.0").exists());
+    }
+
+    #[test]
+    fn repair_needs_the_cached_assets() {
+        let root = TestRoot::new("version-audit");
+        let layout = root.layout();
+        let cached = install(&layout);
+        std::fs::remove_file(&cached[0].path).unwrap();
+
+        let err = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(repair_installed_version(
+                &layout,
+                &Keyring::default(),
+                "v1.0.0",
+            ))
+            .unwrap_err();
+        assert_eq!(err.code, "repair_assets_missing");
+        assert!(layout.version_dir("v1.0.0").exists());
+    }
+
+    #[test]
+    fn failed_repair_clears_the_journal_and_repair_dir() {
+        let root = TestRoot::new("version-audit");
+        let layout = root.layout();
+        install(&layout);
+        // A file where the swap wants to move the old copy makes the swap fail.
+        std::fs::write(layout.version_replaced_dir("v1.0.0"), "in the way").unwrap();
+
+        let err = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(repair_installed_version(
+                &layout,
+                &Keyring::default(),
+                "v1.0.0",
+            ))
+            .unwrap_err();
+        assert_eq!(err.code, "io_remove_failed");
+        assert!(!layout.install_journal_file().exists());
+        assert!(!layout.version_repair_dir("v1.0.0").exists());
+        assert!(layout.version_dir("v1.0.0").exists());
+    }
+}
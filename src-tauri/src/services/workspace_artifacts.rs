@@ -1,6 +1,8 @@
 use std::path::{Path, PathBuf};
 
-use ms_manager_core::{ArtifactSource, FirmwareTarget};
+use ms_manager_core::{
+    ArtifactSource, BridgeInstanceBinding, BridgeMode, FirmwareTarget, BITWIG_EXTENSION_KIND,
+};
 use serde::Deserialize;
 
 use crate::api_error::{ApiError, ApiResult};
@@ -14,11 +16,49 @@ pub struct WorkspaceArtifacts {
     pub oc_bridge_exe: PathBuf,
     pub loader_exe: PathBuf,
     pub ms_core_file_tool: Option<PathBuf>,
+    /// Native controller simulator run by `native_sim` instances.
+    pub native_sim_exe: Option<PathBuf>,
     pub firmware_standalone: PathBuf,
     pub firmware_bitwig: PathBuf,
     pub bitwig_extension: PathBuf,
 }
 
+impl WorkspaceArtifacts {
+    /// Config key and firmware path for `target`; workspaces only build standalone and Bitwig.
+    pub fn firmware_for(&self, target: &FirmwareTarget) -> ApiResult<(&'static str, &PathBuf)> {
+        if target.is_standalone() {
+            Ok(("firmware_standalone", &self.firmware_standalone))
+        } else if target.is_bitwig() {
+            Ok(("firmware_bitwig", &self.firmware_bitwig))
+        } else {
+            Err(unsupported_workspace_target(target))
+        }
+    }
+
+    pub fn native_sim(&self) -> ApiResult<&PathBuf> {
+        let path = self.native_sim_exe.as_ref().ok_or_else(|| {
+            ApiError::new(
+                "artifact_missing",
+                "native_sim_exe is not set in the workspace artifact config",
+            )
+        })?;
+        ensure_file_exists("native_sim_exe", path)?;
+        Ok(path)
+    }
+
+    /// Config key and path of the host extension of asset kind `kind`, if the workspace builds it.
+    pub fn extension_for(&self, kind: &str) -> Option<(&'static str, &PathBuf)> {
+        (kind == BITWIG_EXTENSION_KIND).then_some(("bitwig_extension", &self.bitwig_extension))
+    }
+}
+
+pub fn unsupported_workspace_target(target: &FirmwareTarget) -> ApiError {
+    ApiError::new(
+        "workspace_target_unsupported",
+        format!("workspace builds do not provide firmware target {target}"),
+    )
+}
+
 #[derive(Debug, Deserialize)]
 struct DevArtifactsFile {
     schema: u32,
@@ -32,6 +72,7 @@ struct DevArtifactsMap {
     oc_bridge_exe: String,
     loader_exe: String,
     ms_core_file_tool: Option<String>,
+    native_sim_exe: Option<String>,
     firmware_standalone: String,
     firmware_bitwig: String,
     bitwig_extension: String,
@@ -46,7 +87,7 @@ pub fn load_workspace_artifacts() -> ApiResult<WorkspaceArtifacts> {
 }
 
 fn load_workspace_artifacts_from_path(path: &Path) -> ApiResult<WorkspaceArtifacts> {
-    let bytes = std::fs::read(&path).map_err(|e| {
+    let bytes = std::fs::read(path).map_err(|e| {
         ApiError::new(
             "artifact_config_missing",
             format!("workspace artifact config not found: {}", path.display()),
@@ -99,6 +140,11 @@ fn load_workspace_artifacts_from_path(path: &Path) -> ApiResult<WorkspaceArtifac
             file.artifacts.ms_core_file_tool.as_deref(),
             "ms_core_file_tool",
         )?,
+        native_sim_exe: resolve_optional_declared_path(
+            &root,
+            file.artifacts.native_sim_exe.as_deref(),
+            "native_sim_exe",
+        )?,
         firmware_standalone: resolve_declared_path(
             &root,
             &file.artifacts.firmware_standalone,
@@ -154,17 +200,18 @@ pub fn workspace_artifact_health() -> ArtifactHealth {
     }
 }
 
-pub fn workspace_artifact_health_for_target(target: FirmwareTarget) -> ArtifactHealth {
+/// Health of what `binding` runs from the workspace: its firmware, or the simulator for
+/// `native_sim` instances.
+pub fn workspace_artifact_health_for_binding(binding: &BridgeInstanceBinding) -> ArtifactHealth {
     match load_workspace_artifacts() {
         Ok(workspace) => {
-            let (key, firmware) = match target {
-                FirmwareTarget::Standalone => {
-                    ("firmware_standalone", &workspace.firmware_standalone)
-                }
-                FirmwareTarget::Bitwig => ("firmware_bitwig", &workspace.firmware_bitwig),
+            let checked = match binding.mode {
+                BridgeMode::NativeSim => workspace.native_sim().map(|_| ()),
+                BridgeMode::Hardware | BridgeMode::WasmSim => workspace
+                    .firmware_for(&binding.target)
+                    .and_then(|(key, firmware)| ensure_file_exists(key, firmware)),
             };
-
-            if let Err(err) = ensure_file_exists(key, firmware) {
+            if let Err(err) = checked {
                 return ArtifactHealth {
                     source: ArtifactSource::Workspace,
                     ready: false,
@@ -267,5 +314,6 @@ mod tests {
             root.join("firmware/bitwig/firmware.hex")
         );
         assert!(workspace.ms_core_file_tool.is_none());
+        assert!(workspace.native_sim_exe.is_none());
     }
 }
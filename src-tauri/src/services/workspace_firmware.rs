@@ -7,6 +7,7 @@ use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 
 use crate::api_error::{ApiError, ApiResult};
 use crate::models::FlashMessageLevel;
+use crate::services::workspace_artifacts::unsupported_workspace_target;
 use crate::services::{artifact_paths, flash, process};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -19,9 +20,10 @@ pub struct WorkspaceFirmwareProfile {
     pub source_dirty: bool,
 }
 
-pub async fn profiles(target: FirmwareTarget) -> ApiResult<Vec<WorkspaceFirmwareProfile>> {
+pub async fn profiles(target: &FirmwareTarget) -> ApiResult<Vec<WorkspaceFirmwareProfile>> {
+    let app_name = app_name(target)?;
     let root = workspace_root()?;
-    let output = run_ms(&root, &["profiles", app_name(target), "--json"]).await?;
+    let output = run_ms(&root, &["profiles", app_name, "--json"]).await?;
     if !output.status.success() {
         return Err(command_error(
             "firmware_profile_discovery_failed",
@@ -40,9 +42,10 @@ pub async fn profiles(target: FirmwareTarget) -> ApiResult<Vec<WorkspaceFirmware
 
 pub async fn build(
     app: &tauri::AppHandle,
-    target: FirmwareTarget,
+    target: &FirmwareTarget,
     profile_id: &str,
 ) -> ApiResult<WorkspaceFirmwareProfile> {
+    let app_name = app_name(target)?;
     let selected_profile = profile(target, profile_id).await?;
 
     if selected_profile.source_dirty {
@@ -51,7 +54,7 @@ pub async fn build(
             FlashMessageLevel::Warn,
             format!(
                 "Building {}/{} from a source repository with uncommitted changes; this firmware will not map to a clean commit.",
-                app_name(target), selected_profile.id
+                app_name, selected_profile.id
             ),
         );
     }
@@ -62,7 +65,7 @@ pub async fn build(
         &root,
         &[
             "build",
-            app_name(target),
+            app_name,
             "--target",
             "teensy",
             "--env",
@@ -97,7 +100,7 @@ pub async fn build(
 }
 
 pub async fn profile(
-    target: FirmwareTarget,
+    target: &FirmwareTarget,
     profile_id: &str,
 ) -> ApiResult<WorkspaceFirmwareProfile> {
     let profile_id = profile_id.trim();
@@ -113,10 +116,13 @@ pub async fn profile(
         })
 }
 
-fn app_name(target: FirmwareTarget) -> &'static str {
-    match target {
-        FirmwareTarget::Standalone => "core",
-        FirmwareTarget::Bitwig => "bitwig",
+fn app_name(target: &FirmwareTarget) -> ApiResult<&'static str> {
+    if target.is_standalone() {
+        Ok("core")
+    } else if target.is_bitwig() {
+        Ok("bitwig")
+    } else {
+        Err(unsupported_workspace_target(target))
     }
 }
 
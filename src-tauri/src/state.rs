@@ -3,37 +3,69 @@ use std::sync::Mutex;
 
 use ms_manager_core::{
     ArtifactSource, BridgeInstanceBinding, BridgeInstancesState, Channel, ControllerState,
-    FirmwareTarget, InstallState, LastFlashed, Settings, BRIDGE_INSTANCES_SCHEMA,
-    CONTROLLER_STATE_SCHEMA, INSTALL_STATE_SCHEMA, SETTINGS_SCHEMA,
+    DistributionSource, DownloadSettings, FirmwareTarget, InstallHistoryAction, InstallState,
+    LastFlashed, NetworkSettings, Settings, UpdateCheckSettings, UpdatePolicy, VersionRange,
+    BRIDGE_INSTANCES_SCHEMA, CONTROLLER_STATE_SCHEMA, INSTALL_STATE_SCHEMA, SETTINGS_SCHEMA,
 };
 use reqwest::Client;
-use tauri::path::BaseDirectory;
-use tauri::Manager;
+#[cfg(feature = "desktop")]
+use tauri::{path::BaseDirectory, Manager};
 
 use crate::api_error::{ApiError, ApiResult};
 use crate::layout::PayloadLayout;
+use crate::models::{PayloadRecoveryReport, UpdateCheckState};
+use crate::services::http_client::{self, HttpClient};
+use crate::services::payload_lock::PayloadLock;
+use crate::services::{install_history, install_journal, offline_bundle};
 use crate::storage::{read_json_optional, write_json_atomic};
 
 pub struct AppState {
-    pub http: Client,
+    http: Mutex<Client>,
     layout: Mutex<PayloadLayout>,
     settings_path: PathBuf,
     settings: Mutex<Settings>,
     install_state: Mutex<Option<InstallState>>,
     controller_state: Mutex<ControllerState>,
     bridge_instances: Mutex<BridgeInstancesState>,
+    recovery: Mutex<Option<PayloadRecoveryReport>>,
+    update_check: Mutex<UpdateCheckState>,
+    install: tokio::sync::Mutex<()>,
+}
+
+/// Held while a step writes to the payload: install, rollback, repair, GC or an auto-update.
+///
+/// Steps of this process queue on the mutex; the [`PayloadLock`] keeps out the other processes
+/// (the desktop app and `ms-manager-cli`) sharing the payload root.
+pub struct InstallGuard<'a> {
+    _local: tokio::sync::MutexGuard<'a, ()>,
+    _payload: PayloadLock,
 }
 
 impl AppState {
+    #[cfg(feature = "desktop")]
     pub fn load(app: &tauri::AppHandle) -> ApiResult<Self> {
         let settings_path = app
             .path()
             .resolve("settings.json", BaseDirectory::AppConfig)
             .map_err(|e| ApiError::new("io_path_failed", e.to_string()))?;
+        Self::load_from_settings_path(settings_path, None)
+    }
 
+    /// Load settings from `settings_path` and the payload state files they point at.
+    ///
+    /// `payload_root` replaces the settings' payload root for this state only (it is not saved),
+    /// so crash recovery runs on the root that will actually be used.
+    pub fn load_from_settings_path(
+        settings_path: PathBuf,
+        payload_root: Option<&str>,
+    ) -> ApiResult<Self> {
         let settings = load_settings(&settings_path)?;
-        let layout = PayloadLayout::resolve(settings.payload_root_override.as_deref())?;
+        let payload_root = payload_root
+            .filter(|root| !root.trim().is_empty())
+            .or(settings.payload_root_override.as_deref());
+        let layout = PayloadLayout::resolve(payload_root)?;
         let install_state = load_install_state(&layout, &layout.install_state_file())?;
+        let recovery = install_journal::recover(&layout, install_state.as_ref());
         let bridge_instances = load_bridge_instances_state(&layout.bridge_instances_file())?;
         let controller_state_raw = load_controller_state(&layout.controller_state_file())?;
         let controller_state =
@@ -42,21 +74,42 @@ impl AppState {
             let _ = write_json_atomic(&layout.controller_state_file(), &controller_state);
         }
 
-        let http = Client::builder()
-            .user_agent("ms-manager")
-            .build()
-            .map_err(|e| ApiError::new("http_client_failed", e.to_string()))?;
+        // A proxy or CA file that went bad must not keep the app from starting; fall back to a
+        // plain client and let the next network settings change rebuild it.
+        let http = http_client::build_client(&settings.network)
+            .or_else(|_| http_client::build_client(&NetworkSettings::default()))?;
         Ok(Self {
-            http,
+            http: Mutex::new(http),
             layout: Mutex::new(layout),
             settings_path,
             settings: Mutex::new(settings),
             install_state: Mutex::new(install_state),
             controller_state: Mutex::new(controller_state),
             bridge_instances: Mutex::new(bridge_instances),
+            recovery: Mutex::new(recovery),
+            update_check: Mutex::new(UpdateCheckState::default()),
+            install: tokio::sync::Mutex::new(()),
         })
     }
 
+    /// The shared HTTP client; cheap to clone, rebuilt when the network settings change.
+    pub fn http(&self) -> HttpClient {
+        let client = self.http.lock().unwrap().clone();
+        HttpClient::new(client)
+            .with_cache_dir(self.layout_get().http_cache_dir())
+            .with_github_token(self.settings_get().network.github_token)
+    }
+
+    /// Where releases are resolved from: the configured source, or the imported release
+    /// bundles in offline mode.
+    pub fn distribution_source_get(&self) -> DistributionSource {
+        let settings = self.settings_get();
+        if settings.network.offline {
+            return offline_bundle::offline_source(&self.layout_get());
+        }
+        settings.distribution_source
+    }
+
     pub fn layout_get(&self) -> PayloadLayout {
         self.layout.lock().unwrap().clone()
     }
@@ -65,9 +118,46 @@ impl AppState {
         *self.layout.lock().unwrap() = next;
     }
 
+    /// Cleanup the last payload state load did after an interrupted install, if any.
+    pub fn payload_recovery_get(&self) -> Option<PayloadRecoveryReport> {
+        self.recovery.lock().unwrap().clone()
+    }
+
+    /// Latest tags found by the last background update check.
+    pub fn update_check_get(&self) -> UpdateCheckState {
+        self.update_check.lock().unwrap().clone()
+    }
+
+    pub fn update_check_set(&self, next: UpdateCheckState) {
+        *self.update_check.lock().unwrap() = next;
+    }
+
+    /// Wait until no other step of this process writes to the payload, then lock the payload
+    /// root against other processes. Fails with `payload_busy` while another process holds it.
+    pub async fn install_lock(&self) -> ApiResult<InstallGuard<'_>> {
+        let local = self.install.lock().await;
+        let payload = PayloadLock::acquire(&self.layout_get())?;
+        Ok(InstallGuard {
+            _local: local,
+            _payload: payload,
+        })
+    }
+
+    /// Like [`Self::install_lock`], but `None` instead of waiting when any step, in this
+    /// process or another one, is running.
+    pub fn install_try_lock(&self) -> Option<InstallGuard<'_>> {
+        let local = self.install.try_lock().ok()?;
+        let payload = PayloadLock::try_acquire(&self.layout_get()).ok()??;
+        Some(InstallGuard {
+            _local: local,
+            _payload: payload,
+        })
+    }
+
     pub fn payload_state_reload(&self) -> ApiResult<()> {
         let layout = self.layout_get();
         let install_state = load_install_state(&layout, &layout.install_state_file())?;
+        let recovery = install_journal::recover(&layout, install_state.as_ref());
         let bridge_instances = load_bridge_instances_state(&layout.bridge_instances_file())?;
         let controller_state_raw = load_controller_state(&layout.controller_state_file())?;
         let controller_state =
@@ -75,6 +165,7 @@ impl AppState {
         if controller_state != controller_state_raw {
             let _ = write_json_atomic(&layout.controller_state_file(), &controller_state);
         }
+        *self.recovery.lock().unwrap() = recovery;
         *self.install_state.lock().unwrap() = install_state;
         *self.bridge_instances.lock().unwrap() = bridge_instances;
         *self.controller_state.lock().unwrap() = controller_state;
@@ -125,6 +216,95 @@ impl AppState {
         Ok(s.clone())
     }
 
+    pub fn settings_set_distribution_source(
+        &self,
+        source: DistributionSource,
+    ) -> ApiResult<Settings> {
+        let source = normalize_distribution_source(source);
+        source.validate()?;
+
+        let mut s = self.settings.lock().unwrap();
+        if s.distribution_source != source {
+            s.distribution_source = source;
+        }
+        if s.schema != SETTINGS_SCHEMA {
+            s.schema = SETTINGS_SCHEMA;
+        }
+
+        write_json_atomic(&self.settings_path, &*s)?;
+        Ok(s.clone())
+    }
+
+    pub fn settings_set_app_update_source(
+        &self,
+        source: DistributionSource,
+    ) -> ApiResult<Settings> {
+        let source = normalize_distribution_source(source);
+        source.validate()?;
+
+        let mut s = self.settings.lock().unwrap();
+        if s.app_update_source != source {
+            s.app_update_source = source;
+        }
+        if s.schema != SETTINGS_SCHEMA {
+            s.schema = SETTINGS_SCHEMA;
+        }
+
+        write_json_atomic(&self.settings_path, &*s)?;
+        Ok(s.clone())
+    }
+
+    pub fn settings_set_downloads(&self, downloads: DownloadSettings) -> ApiResult<Settings> {
+        let downloads = downloads.normalized();
+
+        let mut s = self.settings.lock().unwrap();
+        if s.downloads != downloads {
+            s.downloads = downloads;
+        }
+        if s.schema != SETTINGS_SCHEMA {
+            s.schema = SETTINGS_SCHEMA;
+        }
+
+        write_json_atomic(&self.settings_path, &*s)?;
+        Ok(s.clone())
+    }
+
+    pub fn settings_set_network(&self, network: NetworkSettings) -> ApiResult<Settings> {
+        let network = network.normalized();
+        network.validate()?;
+        let http = http_client::build_client(&network)?;
+
+        let mut s = self.settings.lock().unwrap();
+        if s.network != network {
+            s.network = network;
+        }
+        if s.schema != SETTINGS_SCHEMA {
+            s.schema = SETTINGS_SCHEMA;
+        }
+
+        write_json_atomic(&self.settings_path, &*s)?;
+        *self.http.lock().unwrap() = http;
+        Ok(s.clone())
+    }
+
+    pub fn settings_set_update_check(
+        &self,
+        update_check: UpdateCheckSettings,
+    ) -> ApiResult<Settings> {
+        let update_check = update_check.normalized();
+
+        let mut s = self.settings.lock().unwrap();
+        if s.update_check != update_check {
+            s.update_check = update_check;
+        }
+        if s.schema != SETTINGS_SCHEMA {
+            s.schema = SETTINGS_SCHEMA;
+        }
+
+        write_json_atomic(&self.settings_path, &*s)?;
+        Ok(s.clone())
+    }
+
     pub fn install_state_get(&self) -> Option<InstallState> {
         self.install_state.lock().unwrap().clone()
     }
@@ -146,6 +326,19 @@ impl AppState {
         Ok(next)
     }
 
+    /// Record `next` as the active release and append it to the install history.
+    pub fn install_state_activate(
+        &self,
+        next: InstallState,
+        action: InstallHistoryAction,
+    ) -> ApiResult<InstallState> {
+        let previous = self.install_state_get();
+        let next = self.install_state_set(next)?;
+        let entry = install_history::entry(action, &next, previous.as_ref());
+        install_history::append(&self.layout_get(), &entry)?;
+        Ok(next)
+    }
+
     pub fn controller_state_get(&self) -> ControllerState {
         self.controller_state.lock().unwrap().clone()
     }
@@ -266,6 +459,33 @@ impl AppState {
         })
     }
 
+    pub fn bridge_instance_set_version_range(
+        &self,
+        instance_id: &str,
+        version_range: Option<String>,
+    ) -> ApiResult<BridgeInstancesState> {
+        let version_range = version_range
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .map(|value| VersionRange::parse(&value).map(|range| range.to_string()))
+            .transpose()?;
+        self.update_bridge_instance(instance_id, |instance| {
+            instance.installed_version_range = version_range;
+            Ok(())
+        })
+    }
+
+    pub fn bridge_instance_set_update_policy(
+        &self,
+        instance_id: &str,
+        update_policy: UpdatePolicy,
+    ) -> ApiResult<BridgeInstancesState> {
+        self.update_bridge_instance(instance_id, |instance| {
+            instance.update_policy = update_policy;
+            Ok(())
+        })
+    }
+
     pub fn bridge_instance_set_enabled(
         &self,
         instance_id: &str,
@@ -420,6 +640,20 @@ fn normalize_tab_order(tab_order: Vec<String>) -> Vec<String> {
     normalized
 }
 
+fn normalize_distribution_source(source: DistributionSource) -> DistributionSource {
+    match source {
+        DistributionSource::Github { repo } => DistributionSource::Github {
+            repo: repo.trim().trim_matches('/').to_string(),
+        },
+        DistributionSource::Https { base_url } => DistributionSource::Https {
+            base_url: base_url.trim().trim_end_matches('/').to_string(),
+        },
+        DistributionSource::Directory { path } => DistributionSource::Directory {
+            path: path.trim().to_string(),
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::normalize_tab_order;
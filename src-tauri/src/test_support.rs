@@ -0,0 +1,65 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::layout::PayloadLayout;
+use crate::state::AppState;
+
+static NEXT_ROOT: AtomicU64 = AtomicU64::new(0);
+
+/// A fresh directory under the system temp dir, removed on drop.
+///
+/// `name` tells apart the directories of different tests; the pid, time and a counter keep
+/// parallel tests and earlier runs from sharing one.
+pub struct TestRoot(PathBuf);
+
+impl TestRoot {
+    pub fn new(name: &str) -> Self {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!(
+            "ms-manager-{name}-{}-{nonce}-{}",
+            std::process::id(),
+            NEXT_ROOT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&root).unwrap();
+        Self(root)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// The payload layout rooted at `payload/` in this directory.
+    pub fn layout(&self) -> PayloadLayout {
+        PayloadLayout::resolve(Some(self.0.join("payload").to_str().unwrap())).unwrap()
+    }
+
+    /// Write a `settings.json` whose payload root is [`Self::layout`] and return its path.
+    pub fn settings_file(&self) -> PathBuf {
+        let settings = self.0.join("settings.json");
+        std::fs::write(
+            &settings,
+            serde_json::json!({
+                "schema": 1,
+                "payload_root_override": self.layout().root().display().to_string(),
+            })
+            .to_string(),
+        )
+        .unwrap();
+        settings
+    }
+
+    /// Load the app state from [`Self::settings_file`].
+    pub fn state(&self) -> AppState {
+        AppState::load_from_settings_path(self.settings_file(), None).unwrap()
+    }
+}
+
+impl Drop for TestRoot {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
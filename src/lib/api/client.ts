@@ -1,13 +1,18 @@
 import { invokeApi } from "$lib/api/tauri";
 import type {
+  AppUpdatePending,
   AppUpdateStatus,
   ArtifactSource,
+  AssetCacheVerifyRequest,
+  AssetCacheVerifyResponse,
   BridgeInstanceArtifactSourceSetRequest,
   BridgeInstanceBindingResponse,
   BridgeInstanceBindRequest,
   BridgeInstanceInstalledReleaseSetRequest,
   BridgeInstanceNameSetRequest,
   BridgeInstanceTargetSetRequest,
+  BridgeInstanceUpdatePolicySetRequest,
+  BridgeInstanceVersionRangeSetRequest,
   BridgeInstancesResponse,
   Channel,
   BridgeStatus,
@@ -21,18 +26,38 @@ import type {
   ControllerFsRenameRequest,
   ControllerFsTransferResponse,
   DeviceStatus,
+  DistributionSourceResponse,
+  DistributionSourceSetRequest,
+  DownloadSettingsResponse,
+  DownloadSettingsSetRequest,
   FirmwareTarget,
+  FirmwareTargetsRequest,
+  FirmwareTargetsResponse,
+  InstallHistoryResponse,
   InstallState,
+  InstalledVersionRequest,
   LastFlashed,
+  LatestRelease,
+  LatestReleaseRequest,
   LocalFsDeleteRequest,
   LocalFsListRequest,
   LocalFsListResponse,
   LocalFsPathRequest,
   LocalFsRenameRequest,
   MidiInventoryStatus,
+  NetworkSettingsResponse,
+  NetworkSettingsSetRequest,
+  PayloadGcReport,
+  PayloadGcRequest,
   ProjectMigrationInspectRequest,
   ProjectMigrationMigrateRequest,
   ProjectMigrationReport,
+  ReleaseBundleExportReport,
+  ReleaseBundleExportRequest,
+  ReleaseBundleImportReport,
+  ReleaseBundleImportRequest,
+  ReleaseDiffRequest,
+  ReleaseDiffResponse,
   RemoteStepPresetIdentityRequest,
   RemoteStepPresetInspectRequest,
   RemoteStepPresetRenameRequest,
@@ -43,7 +68,10 @@ import type {
   StepPresetReport,
   TabOrderResponse,
   TabOrderSetRequest,
+  UpdateCheckSettingsResponse,
+  UpdateCheckSettingsSetRequest,
   UxRecordingSessionInfo,
+  VersionAuditReport,
   WorkspaceFirmwareProfile,
 } from "$lib/api/types";
 
@@ -210,14 +238,66 @@ export function bridgeInstanceNameSet(
   return invokeApi<BridgeInstancesResponse>("bridge_instance_name_set", { request });
 }
 
+export function bridgeInstanceUpdatePolicySet(
+  request: BridgeInstanceUpdatePolicySetRequest,
+): Promise<BridgeInstancesResponse> {
+  return invokeApi<BridgeInstancesResponse>("bridge_instance_update_policy_set", { request });
+}
+
+export function bridgeInstanceVersionRangeSet(
+  request: BridgeInstanceVersionRangeSetRequest,
+): Promise<BridgeInstancesResponse> {
+  return invokeApi<BridgeInstancesResponse>("bridge_instance_version_range_set", { request });
+}
+
 export function listChannelTags(channel: Channel): Promise<string[]> {
   return invokeApi<string[]>("list_channel_tags", { channel });
 }
 
+export function latestReleaseGet(request: LatestReleaseRequest): Promise<LatestRelease> {
+  return invokeApi<LatestRelease>("latest_release_get", { request });
+}
+
+export function firmwareTargetsGet(
+  request: FirmwareTargetsRequest,
+): Promise<FirmwareTargetsResponse> {
+  return invokeApi<FirmwareTargetsResponse>("firmware_targets_get", { request });
+}
+
+export function releaseDiff(request: ReleaseDiffRequest): Promise<ReleaseDiffResponse> {
+  return invokeApi<ReleaseDiffResponse>("release_diff", { request });
+}
+
+export function assetCacheVerify(
+  request: AssetCacheVerifyRequest,
+): Promise<AssetCacheVerifyResponse> {
+  return invokeApi<AssetCacheVerifyResponse>("asset_cache_verify", { request });
+}
+
 export function installBridgeInstance(instanceId: string): Promise<InstallState> {
   return invokeApi<InstallState>("install_bridge_instance", { instanceId });
 }
 
+export function installHistoryGet(): Promise<InstallHistoryResponse> {
+  return invokeApi<InstallHistoryResponse>("install_history_get");
+}
+
+export function installRollback(): Promise<InstallState> {
+  return invokeApi<InstallState>("install_rollback");
+}
+
+export function installedVersionAudit(
+  request: InstalledVersionRequest = {},
+): Promise<VersionAuditReport> {
+  return invokeApi<VersionAuditReport>("installed_version_audit", { request });
+}
+
+export function installedVersionRepair(
+  request: InstalledVersionRequest = {},
+): Promise<VersionAuditReport> {
+  return invokeApi<VersionAuditReport>("installed_version_repair", { request });
+}
+
 export function workspaceFirmwareProfiles(
   target: FirmwareTarget,
 ): Promise<WorkspaceFirmwareProfile[]> {
@@ -261,14 +341,72 @@ export function tabOrderSet(request: TabOrderSetRequest): Promise<TabOrderRespon
   return invokeApi<TabOrderResponse>("tab_order_set", { request });
 }
 
+export function releaseBundleImport(
+  request: ReleaseBundleImportRequest,
+): Promise<ReleaseBundleImportReport> {
+  return invokeApi<ReleaseBundleImportReport>("release_bundle_import", { request });
+}
+
+export function releaseBundleExport(
+  request: ReleaseBundleExportRequest,
+): Promise<ReleaseBundleExportReport> {
+  return invokeApi<ReleaseBundleExportReport>("release_bundle_export", { request });
+}
+
+export function distributionSourceSet(
+  request: DistributionSourceSetRequest,
+): Promise<DistributionSourceResponse> {
+  return invokeApi<DistributionSourceResponse>("distribution_source_set", { request });
+}
+
+export function payloadGc(request: PayloadGcRequest): Promise<PayloadGcReport> {
+  return invokeApi<PayloadGcReport>("payload_gc", { request });
+}
+
+export function downloadSettingsSet(
+  request: DownloadSettingsSetRequest,
+): Promise<DownloadSettingsResponse> {
+  return invokeApi<DownloadSettingsResponse>("download_settings_set", { request });
+}
+
+export function networkSettingsSet(
+  request: NetworkSettingsSetRequest,
+): Promise<NetworkSettingsResponse> {
+  return invokeApi<NetworkSettingsResponse>("network_settings_set", { request });
+}
+
+export function updateCheckSettingsSet(
+  request: UpdateCheckSettingsSetRequest,
+): Promise<UpdateCheckSettingsResponse> {
+  return invokeApi<UpdateCheckSettingsResponse>("update_check_settings_set", { request });
+}
+
 export function appUpdateCheck(): Promise<AppUpdateStatus> {
   return invokeApi<AppUpdateStatus>("app_update_check");
 }
 
+export function appUpdateInstall(): Promise<AppUpdatePending> {
+  return invokeApi<AppUpdatePending>("app_update_install");
+}
+
+export function appUpdateRollback(): Promise<AppUpdatePending> {
+  return invokeApi<AppUpdatePending>("app_update_rollback");
+}
+
+export function appUpdateRestart(): Promise<void> {
+  return invokeApi<void>("app_update_restart");
+}
+
 export function appUpdateOpenLatest(): Promise<void> {
   return invokeApi<void>("app_update_open_latest");
 }
 
+export function appUpdateSourceSet(
+  request: DistributionSourceSetRequest,
+): Promise<DistributionSourceResponse> {
+  return invokeApi<DistributionSourceResponse>("app_update_source_set", { request });
+}
+
 export function uxRecordingsOpen(): Promise<void> {
   return invokeApi<void>("ux_recordings_open");
 }
@@ -1,6 +1,7 @@
-export type Channel = "stable" | "beta";
+export type Channel = "stable" | "rc" | "beta" | "alpha" | "nightly";
 export type ArtifactSource = "installed" | "workspace";
-export type FirmwareTarget = "standalone" | "bitwig";
+/** Id of a manifest install set; "standalone" (the `default` set) and "bitwig" always work. */
+export type FirmwareTarget = string;
 
 export type Os = "windows" | "macos" | "linux";
 export type Arch = "x86_64" | "arm64";
@@ -11,6 +12,16 @@ export type ApiError = {
   details?: unknown;
 };
 
+/** `details` of an `http_rate_limited` error. */
+export type RateLimitedDetails = {
+  url: string;
+  status: number;
+  retry_after_secs: number;
+  limit: number | null;
+  remaining: number | null;
+  reset_at: number | null;
+};
+
 export type InstallState = {
   schema: number;
   channel: Channel;
@@ -18,6 +29,32 @@ export type InstallState = {
   tag: string;
 };
 
+export type InstallHistoryEntry = {
+  at_ms: number;
+  action: "install" | "rollback";
+  channel: Channel;
+  profile: string;
+  tag: string;
+  previous_tag: string | null;
+};
+
+export type InstallHistoryResponse = {
+  entries: InstallHistoryEntry[];
+};
+
+export type InstalledVersionRequest = {
+  tag?: string | null;
+};
+
+export type VersionAuditReport = {
+  tag: string;
+  ok: boolean;
+  files_checked: number;
+  missing: string[];
+  modified: string[];
+  extra: string[];
+};
+
 export type LastFlashed = {
   channel: Channel;
   tag: string;
@@ -91,10 +128,13 @@ export type BridgeInstanceStatus = {
   instance_id: string;
   display_name?: string | null;
   configured_serial: string;
+  app: BridgeApp;
+  mode: BridgeMode;
   target: FirmwareTarget;
   artifact_source: ArtifactSource;
   installed_channel?: Channel | null;
   installed_pinned_tag?: string | null;
+  installed_version_range?: string | null;
   artifacts_ready: boolean;
   artifact_message?: string | null;
   enabled: boolean;
@@ -106,13 +146,16 @@ export type BridgeInstanceStatus = {
   connected_serial?: string | null;
   message?: string | null;
   last_flashed?: LastFlashed | null;
+  latest_tag: string | null;
+  update_available: boolean;
+  update_policy: UpdatePolicyMode;
   artifact_location_path?: string | null;
   host_udp_port: number;
   control_port: number;
   log_broadcast_port: number;
 };
 
-export type BridgeApp = "bitwig";
+export type BridgeApp = "bitwig" | "midi_host";
 export type BridgeMode = "hardware" | "native_sim" | "wasm_sim";
 
 export type BridgeInstanceBinding = {
@@ -127,10 +170,25 @@ export type BridgeInstanceBinding = {
   artifact_source: ArtifactSource;
   installed_channel?: Channel | null;
   installed_pinned_tag?: string | null;
+  installed_version_range?: string | null;
   host_udp_port: number;
   control_port: number;
   log_broadcast_port: number;
   enabled: boolean;
+  update_policy: UpdatePolicy;
+};
+
+export type UpdatePolicyMode = "manual" | "notify" | "auto_install" | "auto_install_and_flash";
+
+export type MaintenanceWindow = {
+  start_hour: number;
+  end_hour: number;
+};
+
+export type UpdatePolicy = {
+  mode: UpdatePolicyMode;
+  same_minor_only: boolean;
+  maintenance_window: MaintenanceWindow | null;
 };
 
 export type BridgeInstancesState = {
@@ -173,6 +231,16 @@ export type BridgeInstanceInstalledReleaseSetRequest = {
   pinned_tag?: string | null;
 };
 
+export type BridgeInstanceUpdatePolicySetRequest = {
+  instance_id: string;
+  update_policy: UpdatePolicy;
+};
+
+export type BridgeInstanceVersionRangeSetRequest = {
+  instance_id: string;
+  version_range: string | null;
+};
+
 export type BridgeInstanceNameSetRequest = {
   instance_id: string;
   display_name?: string | null;
@@ -219,6 +287,8 @@ export type ManifestAsset = {
   size: number;
   sha256: string;
   url?: string | null;
+  description?: string | null;
+  signature?: string | null;
 };
 
 export type ManifestInstallSet = {
@@ -242,6 +312,101 @@ export type Manifest = {
   assets: ManifestAsset[];
   install_sets: ManifestInstallSet[];
   pages?: ManifestPages | null;
+  notes?: string | null;
+  min_manager_version?: string | null;
+};
+
+export type ChangeKind = "added" | "removed" | "changed";
+
+export type AssetChange = {
+  id: string;
+  from_sha256: string;
+  to_sha256: string;
+  from_size: number;
+  to_size: number;
+};
+
+export type RepoChange = {
+  id: string;
+  kind: ChangeKind;
+  from_sha: string | null;
+  to_sha: string | null;
+};
+
+export type ToolingChange = {
+  from: ManifestTooling | null;
+  to: ManifestTooling | null;
+};
+
+export type InstallSetChange = {
+  id: string;
+  os: string | null;
+  arch: string | null;
+  kind: ChangeKind;
+  added_assets: string[];
+  removed_assets: string[];
+};
+
+export type ManifestDiff = {
+  from_tag: string;
+  to_tag: string;
+  assets_added: ManifestAsset[];
+  assets_removed: ManifestAsset[];
+  assets_changed: AssetChange[];
+  repos: RepoChange[];
+  tooling: ToolingChange | null;
+  install_sets: InstallSetChange[];
+};
+
+export type LatestReleaseRequest = {
+  channel: Channel;
+};
+
+export type LatestRelease = {
+  channel: Channel;
+  tag: string;
+  notes: string | null;
+  stale: boolean;
+  fetched_at_ms: number | null;
+};
+
+export type FirmwareTargetsRequest = {
+  channel: Channel;
+};
+
+export type FirmwareTargetInfo = {
+  target: FirmwareTarget;
+  profile_id: string;
+  label: string;
+  description: string | null;
+};
+
+export type FirmwareTargetsResponse = {
+  channel: Channel;
+  tag: string;
+  targets: FirmwareTargetInfo[];
+};
+
+export type ReleaseDiffRequest = {
+  from_channel: Channel;
+  from_tag: string;
+  to_channel: Channel;
+  to_tag: string;
+  profile?: string | null;
+};
+
+export type ReleaseDiffResponse = {
+  diff: ManifestDiff;
+  download_size: number | null;
+};
+
+export type AssetCacheVerifyRequest = {
+  sha256: string;
+  filename: string;
+};
+
+export type AssetCacheVerifyResponse = {
+  key_id: string | null;
 };
 
 export type Status = {
@@ -251,10 +416,31 @@ export type Status = {
   artifact_config_path: string | null;
   artifact_message: string | null;
   tab_order: string[];
+  distribution_source: DistributionSource;
+  downloads: DownloadSettings;
+  network: NetworkSettingsView;
+  update_check: UpdateCheckSettings;
   platform: Platform;
   payload_root: string;
   device: DeviceStatus;
   bridge: BridgeStatus;
+  recovery: PayloadRecoveryReport | null;
+};
+
+export type InstallJournalPhase =
+  | "stage"
+  | "promote"
+  | "switch_current"
+  | "repair_stage"
+  | "repair_swap";
+
+export type PayloadRecoveryReport = {
+  interrupted: InstallJournalPhase | null;
+  interrupted_tag: string | null;
+  removed_dirs: string[];
+  restored_versions: string[];
+  current_restored: string | null;
+  failed: string[];
 };
 
 export type TabOrderSetRequest = {
@@ -265,6 +451,186 @@ export type TabOrderResponse = {
   tab_order: string[];
 };
 
+export type ReleaseBundleImportRequest = {
+  path: string;
+};
+
+export type BundleInstallSet = {
+  id: string;
+  os: string | null;
+  arch: string | null;
+};
+
+export type ReleaseBundleImportReport = {
+  channel: Channel;
+  tag: string;
+  assets_imported: string[];
+  install_sets: BundleInstallSet[];
+};
+
+export type ReleaseBundleExportRequest = {
+  channel: Channel;
+  tag: string;
+  profiles: string[];
+  platform?: Platform | null;
+  path: string;
+};
+
+export type ReleaseBundleExportReport = {
+  channel: Channel;
+  tag: string;
+  path: string;
+  assets: string[];
+};
+
+export type DistributionSource =
+  | { kind: "github"; repo: string }
+  | { kind: "https"; base_url: string }
+  | { kind: "directory"; path: string };
+
+export type DistributionSourceSetRequest = {
+  source: DistributionSource;
+};
+
+export type DistributionSourceResponse = {
+  source: DistributionSource;
+};
+
+export type PayloadGcRequest = {
+  dry_run: boolean;
+  keep_per_channel?: number;
+};
+
+export type GcKeepReason =
+  | { kind: "current" }
+  | { kind: "install_state" }
+  | { kind: "rollback_target" }
+  | { kind: "pinned"; instance_id: string }
+  | { kind: "channel_resolved"; instance_id: string }
+  | { kind: "retention" }
+  | { kind: "unknown_tag" };
+
+export type GcRemovedEntry = {
+  name: string;
+  path: string;
+  bytes: number;
+};
+
+export type PayloadGcReport = {
+  dry_run: boolean;
+  kept_versions: { tag: string; reasons: GcKeepReason[] }[];
+  removed_versions: GcRemovedEntry[];
+  removed_blobs: GcRemovedEntry[];
+  failed: { path: string; message: string }[];
+  bytes_freed: number;
+};
+
+export type DownloadSettings = {
+  concurrency: number;
+  bandwidth_limit_kibps: number | null;
+};
+
+export type DownloadSettingsSetRequest = {
+  downloads: DownloadSettings;
+};
+
+export type DownloadSettingsResponse = {
+  downloads: DownloadSettings;
+};
+
+// Secrets: omit to keep the stored value, "" to clear it.
+export type ProxySettings = {
+  url: string;
+  username?: string | null;
+  password?: string | null;
+};
+
+export type NetworkSettings = {
+  proxy: ProxySettings | null;
+  extra_ca_certificates: string[];
+  connect_timeout_secs: number;
+  read_timeout_secs: number;
+  offline: boolean;
+  github_token?: string | null;
+};
+
+export type ProxySettingsView = {
+  url: string;
+  username: string | null;
+  password_set: boolean;
+};
+
+export type NetworkSettingsView = {
+  proxy: ProxySettingsView | null;
+  extra_ca_certificates: string[];
+  connect_timeout_secs: number;
+  read_timeout_secs: number;
+  offline: boolean;
+  github_token_set: boolean;
+};
+
+export type NetworkSettingsSetRequest = {
+  network: NetworkSettings;
+};
+
+export type NetworkSettingsResponse = {
+  network: NetworkSettingsView;
+};
+
+export type UpdateCheckSettings = {
+  enabled: boolean;
+  interval_mins: number;
+};
+
+export type UpdateCheckSettingsSetRequest = {
+  update_check: UpdateCheckSettings;
+};
+
+export type UpdateCheckSettingsResponse = {
+  update_check: UpdateCheckSettings;
+};
+
+export type ChannelLatestTag = {
+  channel: Channel;
+  tag: string | null;
+  stale: boolean;
+  error: string | null;
+};
+
+export type UpdateCheckState = {
+  checked_at_ms: number | null;
+  channels: ChannelLatestTag[];
+  ranges: RangeLatestTag[];
+};
+
+export type RangeLatestTag = {
+  channel: Channel;
+  range: string;
+  tag: string | null;
+  error: string | null;
+};
+
+export type InstanceUpdateAvailability = {
+  instance_id: string;
+  current_tag: string | null;
+  latest_tag: string | null;
+  update_available: boolean;
+  update_policy: UpdatePolicyMode;
+};
+
+export type AutoUpdateEvent = {
+  instance_id: string;
+  tag: string;
+  installed: boolean;
+  flashed: boolean;
+  error: string | null;
+};
+
+export type UpdateCheckEvent = {
+  check: UpdateCheckState;
+  instances: InstanceUpdateAvailability[];
+};
+
 export type AppUpdateInfo = {
   version: string;
   pub_date?: string | null;
@@ -277,6 +643,13 @@ export type AppUpdateStatus = {
   available: boolean;
   update: AppUpdateInfo | null;
   error?: string | null;
+  self_update: boolean;
+  pending: AppUpdatePending;
+};
+
+export type AppUpdatePending = {
+  staged_version: string | null;
+  rollback_version: string | null;
 };
 
 export type InstallEvent =
@@ -286,6 +659,7 @@ export type InstallEvent =
       tag: string;
       profile: string;
       assets_total: number;
+      notes: string | null;
     }
   | {
       type: "downloading";
@@ -294,6 +668,13 @@ export type InstallEvent =
       asset_id: string;
       filename: string;
     }
+  | {
+      type: "download_progress";
+      bytes_done: number;
+      bytes_total: number;
+      bytes_per_sec: number;
+      eta_ms: number | null;
+    }
   | {
       type: "applying";
       step: string;
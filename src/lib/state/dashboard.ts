@@ -1,7 +1,12 @@
 import { get, writable } from "svelte/store";
 import { listen } from "@tauri-apps/api/event";
 
-import type { FlashEvent, InstallEvent } from "$lib/api/types";
+import type {
+  AutoUpdateEvent,
+  FlashEvent,
+  InstallEvent,
+  UpdateCheckEvent,
+} from "$lib/api/types";
 import { bridgeStatusGet, deviceStatusGet } from "$lib/api/client";
 import type { ActivityFilter, ActivityLevel, ActivityScope } from "$lib/state/activity";
 import { createDashboardMutationController } from "$lib/state/dashboard_mutations";
@@ -20,6 +25,8 @@ const INSTALL_EVENT = "ms-manager://install";
 const FLASH_EVENT = "ms-manager://flash";
 const BRIDGE_LOG_EVENT = "ms-manager://bridge-log";
 const UX_RECORDER_EVENT = "ms-manager://ux-recorder";
+const UPDATE_CHECK_EVENT = "ms-manager://update-check";
+const AUTO_UPDATE_EVENT = "ms-manager://auto-update";
 
 export type { DashboardState } from "$lib/state/dashboard_shared";
 
@@ -136,7 +143,17 @@ export function createDashboard(activity: Activity) {
     }
 
     const unlistenInstall = await listen<InstallEvent>(INSTALL_EVENT, (event) => {
-      state.update((current) => ({ ...current, now: nowFromInstall(event.payload) }));
+      const payload = event.payload;
+      state.update((current) => ({
+        ...current,
+        now: nowFromInstall(payload),
+        releaseNotes:
+          payload.type === "begin"
+            ? payload.notes
+              ? { tag: payload.tag, notes: payload.notes }
+              : null
+            : current.releaseNotes,
+      }));
       if (event.payload.type === "done") {
         activity.add("ok", "install", `done ${event.payload.tag} (${event.payload.profile})`);
       }
@@ -183,6 +200,32 @@ export function createDashboard(activity: Activity) {
       },
     );
 
+    const unlistenUpdateCheck = await listen<UpdateCheckEvent>(UPDATE_CHECK_EVENT, (event) => {
+      for (const instance of event.payload.instances) {
+        if (!instance.update_available || instance.update_policy === "manual") continue;
+        activity.add(
+          "info",
+          "net",
+          `update available for ${instance.instance_id}: ${instance.current_tag} -> ${instance.latest_tag}`,
+        );
+      }
+    });
+
+    const unlistenAutoUpdate = await listen<AutoUpdateEvent>(AUTO_UPDATE_EVENT, (event) => {
+      const payload = event.payload;
+      if (payload.error) {
+        activity.add(
+          "error",
+          "install",
+          `auto-update ${payload.instance_id} to ${payload.tag} failed`,
+          payload,
+        );
+        return;
+      }
+      const action = payload.flashed ? "installed and flashed" : "installed";
+      activity.add("ok", "install", `auto-update ${payload.instance_id}: ${action} ${payload.tag}`);
+    });
+
     let devicePolling = false;
     const pollDevice = setInterval(async () => {
       if (devicePolling || get(state).relocating) return;
@@ -223,6 +266,8 @@ export function createDashboard(activity: Activity) {
       unlistenFlash();
       unlistenBridgeLog();
       unlistenUxRecorder();
+      unlistenUpdateCheck();
+      unlistenAutoUpdate();
       clearInterval(pollDevice);
       clearInterval(pollBridge);
     };
@@ -141,7 +141,7 @@ export function createDashboardMutationController({
     activity.add("info", "bridge", `bind bridge serial=${controllerSerial}`);
     try {
       const result = await bridgeInstanceBind({
-        app: "bitwig",
+        app: defaults.app,
         mode,
         controller_serial: controllerSerial,
         controller_vid: target.vid,
@@ -161,6 +161,19 @@ export function createDashboardMutationController({
     return null;
   }
 
+  async function bindNativeSimulator(preset: DashboardBindPreset = "standalone") {
+    const usedSerials = new Set(
+      get(state).bridge.instances.map((instance) => instance.configured_serial),
+    );
+    let index = 1;
+    while (usedSerials.has(`sim${index}`)) index += 1;
+    return bindHardwareBridge(
+      { serial_number: `sim${index}`, vid: 0, pid: 0 },
+      "native_sim",
+      preset,
+    );
+  }
+
   async function removeBridge(instanceId: string) {
     state.update((current) => ({ ...current, bridgeMutating: true }));
     clearApiError(state);
@@ -310,6 +323,7 @@ export function createDashboardMutationController({
     confirmRelocateModal,
     flashInstance,
     bindHardwareBridge,
+    bindNativeSimulator,
     removeBridge,
     setBridgeEnabled,
     installForBridgeInstance,
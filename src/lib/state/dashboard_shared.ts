@@ -5,6 +5,7 @@ import type {
   ApiError,
   AppUpdateStatus,
   ArtifactSource,
+  BridgeApp,
   BridgeLogEvent,
   BridgeMode,
   BridgeStatus,
@@ -52,6 +53,10 @@ export type DashboardState = {
   activeBridgeInstanceId: string | null;
   relocating: boolean;
   now: string | null;
+  releaseNotes: {
+    tag: string;
+    notes: string;
+  } | null;
   error: ApiError | null;
   activityOpen: boolean;
   activityFilter: ActivityFilter;
@@ -108,6 +113,7 @@ export function createInitialDashboardState(): DashboardState {
     activeBridgeInstanceId: null,
     relocating: false,
     now: null,
+    releaseNotes: null,
     error: null,
     activityOpen: false,
     activityFilter: "all",
@@ -198,11 +204,22 @@ export function nowFromInstall(event: InstallEvent): string {
   if (event.type === "begin") return `Installing ${event.tag} (${event.profile})…`;
   if (event.type === "downloading")
     return `Downloading ${event.index}/${event.total}: ${event.filename}`;
+  if (event.type === "download_progress") {
+    const done = formatMegabytes(event.bytes_done);
+    const total = formatMegabytes(event.bytes_total);
+    const rate = event.bytes_per_sec > 0 ? `, ${formatMegabytes(event.bytes_per_sec)}/s` : "";
+    const eta = event.eta_ms != null ? `, ${Math.ceil(event.eta_ms / 1000)}s left` : "";
+    return `Downloading ${done} / ${total}${rate}${eta}`;
+  }
   if (event.type === "applying") return `Applying: ${event.step}`;
   if (event.type === "done") return `Installed ${event.tag} (${event.profile})`;
   return "";
 }
 
+function formatMegabytes(bytes: number): string {
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
 export function nowFromFlash(event: FlashEvent): string {
   if (event.type === "begin") return `Flashing firmware: ${event.profile}…`;
   if (event.type === "message") return event.message;
@@ -236,22 +253,34 @@ export type DashboardArtifactSource = ArtifactSource;
 export type DashboardFirmwareTarget = FirmwareTarget;
 export type DashboardBridgeLogEvent = BridgeLogEvent;
 export type DashboardUxRecorderEvent = UxRecorderEvent;
-export type DashboardBindPreset = "standalone" | "bitwig";
+export type DashboardBindPreset = "standalone" | "bitwig" | "midi_host";
 
 export function bindPresetDefaults(preset: DashboardBindPreset): {
+  app: BridgeApp;
   target: DashboardFirmwareTarget;
   artifactSource: DashboardArtifactSource;
   installedChannel: Channel | null;
 } {
   if (preset === "bitwig") {
     return {
+      app: "bitwig",
       target: "bitwig",
       artifactSource: "installed",
       installedChannel: "stable",
     };
   }
 
+  if (preset === "midi_host") {
+    return {
+      app: "midi_host",
+      target: "standalone",
+      artifactSource: "installed",
+      installedChannel: "stable",
+    };
+  }
+
   return {
+    app: "bitwig",
     target: "standalone",
     artifactSource: "workspace",
     installedChannel: null,
@@ -2,7 +2,9 @@ import { get } from "svelte/store";
 
 import {
   appUpdateCheck,
+  appUpdateInstall,
   appUpdateOpenLatest,
+  appUpdateRestart,
   listChannelTags,
   midiInventoryGet,
   statusGet,
@@ -98,9 +100,20 @@ export function createDashboardStatusController({ state, activity }: DashboardSt
 
     state.update((current) => ({ ...current, installingAppUpdate: true }));
     clearApiError(state);
-    activity.add("info", "ui", "opening ms-manager latest release page");
     try {
-      await appUpdateOpenLatest();
+      if (snapshot.appUpdate.self_update) {
+        activity.add("info", "install", `staging ms-manager ${snapshot.appUpdate.update?.version}`);
+        const pending = await appUpdateInstall();
+        state.update((current) => ({
+          ...current,
+          appUpdate: current.appUpdate ? { ...current.appUpdate, pending } : current.appUpdate,
+        }));
+        activity.add("ok", "install", `ms-manager ${pending.staged_version} staged, restarting`);
+        await appUpdateRestart();
+      } else {
+        activity.add("info", "ui", "opening ms-manager latest release page");
+        await appUpdateOpenLatest();
+      }
     } catch (error) {
       setApiError(state, activity, error);
     } finally {
@@ -1,4 +1,10 @@
-import type { ArtifactSource, Channel, FirmwareTarget, LastFlashed } from "$lib/api/types";
+import type {
+  ArtifactSource,
+  BridgeApp,
+  Channel,
+  FirmwareTarget,
+  LastFlashed,
+} from "$lib/api/types";
 
 export function formatEnvironmentLabel(source: ArtifactSource): string {
   return source === "workspace" ? "Development" : "Distribution";
@@ -6,10 +12,18 @@ export function formatEnvironmentLabel(source: ArtifactSource): string {
 
 export function formatChannelLabel(channel: Channel): string {
   if (channel === "stable") return "Stable";
+  if (channel === "rc") return "Release Candidate";
   if (channel === "beta") return "Beta";
+  if (channel === "alpha") return "Alpha";
+  if (channel === "nightly") return "Nightly";
   return channel;
 }
 
+export function formatHostAppLabel(app: BridgeApp): string {
+  if (app === "midi_host") return "MIDI Host";
+  return "Bitwig";
+}
+
 export function formatTargetLabel(target: FirmwareTarget | string): string {
   if (target === "standalone" || target === "default") return "Standalone";
   if (target === "bitwig") return "Bitwig";